@@ -19,6 +19,20 @@
 
 //! Derive macros for Activity Streams
 //!
+//! The `Properties` derive inspects the type of each annotated field to decide how values are
+//! stored. Fields can be `T`, `Option<T>`, `OneOrMany<T>` or `Option<OneOrMany<T>>`, where `T` is a
+//! concrete type, a `serde_json::Value`, or an `Either<C, serde_json::Value>`. Getters for the
+//! concrete type that is actually stored return references, while every other variant is
+//! converted through JSON. The `*_vec` getters always return owned values, and stored values can
+//! be borrowed all at once through the matching `*_refs` accessor.
+//!
+//! Optional properties are best stored as `Either<C, serde_json::Value>`, so that a value of the
+//! wrong type is kept as JSON rather than rejecting the whole document.
+//!
+//! The derive also generates a `PROPERTIES` table, which describes every annotated field with its
+//! name in JSON, the range written in the `- Range:` line of its documentation, whether it is
+//! functional, and whether it is required.
+//!
 //! ## Examples
 //!
 //! ```rust
@@ -30,7 +44,7 @@
 //! extern crate serde_derive;
 //! extern crate serde_json;
 //!
-//! use activitystreams_traits::{Link, Object};
+//! use activitystreams_traits::{properties::OneOrMany, Link, Object};
 //!
 //! /// Using the UnitString derive macro
 //! ///
@@ -40,6 +54,12 @@
 //! #[activitystreams(SomeKind)]
 //! pub struct MyKind;
 //!
+//! /// A tuple struct with a `Vec<String>` also accepts a list of types including "SomeKind", and
+//! /// keeps the list so it is serialized again as it was.
+//! #[derive(Clone, Debug, Default, UnitString)]
+//! #[activitystreams(SomeKind)]
+//! pub struct MyTypes(Vec<String>);
+//!
 //! /// Using the Properties derive macro
 //! ///
 //! /// This macro generates getters and setters for the associated fields.
@@ -60,11 +80,29 @@
 //!     /// In the Activity Streams spec, 'functional' means there can only be one item for this
 //!     /// key. This means all fields not labeled 'functional' can also be serialized/deserialized
 //!     /// as Vec<T>.
+//!     ///
+//!     /// - Range: `xsd:string`
+//!     /// - Functional: true
 //!     #[activitystreams(concrete(String), functional)]
 //!     pub required_key: serde_json::Value,
+//!
+//!     /// Derive getters and setters for tags stored as typed Strings.
+//!     ///
+//!     /// Since this field is stored as `OneOrMany<String>`, `tags_string` and `tags_string_refs`
+//!     /// return references rather than deserializing the values.
+//!     #[serde(skip_serializing_if = "Option::is_none")]
+//!     #[activitystreams(concrete(String))]
+//!     pub tags: Option<OneOrMany<String>>,
+//! }
+//!
+//! fn main () {
+//!     let required_key = &MyProperties::PROPERTIES[1];
+//!
+//!     assert_eq!(required_key.name, "requiredKey");
+//!     assert_eq!(required_key.range, "`xsd:string`");
+//!     assert!(required_key.functional);
+//!     assert!(required_key.required);
 //! }
-//! #
-//! # fn main () {}
 //! ```
 
 extern crate proc_macro;
@@ -77,7 +115,10 @@ extern crate quote;
 use proc_macro::TokenStream;
 use proc_macro2::TokenTree;
 use quote::Tokens;
-use syn::{Attribute, Data, DeriveInput, Fields, Ident, Type};
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericArgument, Ident, Lit, Meta, NestedMeta,
+    PathArguments, Type,
+};
 
 #[proc_macro_derive(UnitString, attributes(activitystreams))]
 pub fn unit_string(input: TokenStream) -> TokenStream {
@@ -88,21 +129,35 @@ pub fn unit_string(input: TokenStream) -> TokenStream {
     let attr = input
         .attrs
         .iter()
-        .find(|attribute| {
-            attribute
-                .path
-                .segments
-                .last()
-                .map(|segment| {
-                    let segment = segment.into_value();
-                    segment.ident == Ident::new("activitystreams", segment.ident.span())
-                }).unwrap_or(false)
-        }).unwrap()
+        .find(|attribute| is_our_attr(attribute))
+        .unwrap()
         .clone();
 
     let value = from_value(attr);
 
-    let visitor_name = Ident::from(format!("{}Visitor", value));
+    // A struct with a single field keeps the list of types the value was given with
+    let keeps_types = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Unit => false,
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => true,
+            _ => panic!("Can only derive for unit structs, or tuple structs with one field"),
+        },
+        _ => panic!("Can only derive for structs"),
+    };
+
+    let visitor_name = Ident::from(format!("{}Visitor", name));
+
+    let serialize_value = if keeps_types {
+        quote! {
+            if self.0.is_empty() {
+                serializer.serialize_str(#value)
+            } else {
+                ::serde::ser::Serialize::serialize(&self.0, serializer)
+            }
+        }
+    } else {
+        quote! { serializer.serialize_str(#value) }
+    };
 
     let serialize = quote! {
         impl ::serde::ser::Serialize for #name {
@@ -110,15 +165,51 @@ pub fn unit_string(input: TokenStream) -> TokenStream {
             where
                 S: ::serde::ser::Serializer,
             {
-                serializer.serialize_str(#value)
+                #serialize_value
             }
         }
     };
 
-    let expecting = quote! {
-        fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-            write!(formatter, "The string '{}'", #value)
+    let expecting = if keeps_types {
+        quote! {
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(formatter, "The string '{}', or a list of types including it", #value)
+            }
         }
+    } else {
+        quote! {
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(formatter, "The string '{}'", #value)
+            }
+        }
+    };
+
+    let found = if keeps_types {
+        quote! { #name(Vec::new()) }
+    } else {
+        quote! { #name }
+    };
+
+    let visit_seq = if keeps_types {
+        quote! {
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: ::serde::de::SeqAccess<'de>,
+            {
+                let mut types: Vec<String> = Vec::new();
+                while let Some(kind) = seq.next_element()? {
+                    types.push(kind);
+                }
+
+                if types.iter().any(|kind| kind == #value) {
+                    Ok(#name(types))
+                } else {
+                    Err(::serde::de::Error::custom("Invalid type"))
+                }
+            }
+        }
+    } else {
+        quote! {}
     };
 
     let visit = quote! {
@@ -127,11 +218,13 @@ pub fn unit_string(input: TokenStream) -> TokenStream {
             E: ::serde::de::Error,
         {
             if v == #value {
-                Ok(#name)
+                Ok(#found)
             } else {
                 Err(::serde::de::Error::custom("Invalid type"))
             }
         }
+
+        #visit_seq
     };
 
     let visitor = quote! {
@@ -146,13 +239,19 @@ pub fn unit_string(input: TokenStream) -> TokenStream {
         }
     };
 
+    let deserialize_call = if keeps_types {
+        quote! { deserializer.deserialize_any(#visitor_name) }
+    } else {
+        quote! { deserializer.deserialize_str(#visitor_name) }
+    };
+
     let deserialize = quote! {
         impl<'de> ::serde::de::Deserialize<'de> for #name {
             fn deserialize<D>(deserializer: D) -> Result<#name, D::Error>
             where
                 D: ::serde::de::Deserializer<'de>,
             {
-                deserializer.deserialize_str(#visitor_name)
+                #deserialize_call
             }
         }
     };
@@ -204,61 +303,51 @@ pub fn properties_derive(input: TokenStream) -> TokenStream {
         _ => panic!("Can only derive for named fields"),
     };
 
+    let camel_case = renames_to_camel_case(&input.attrs);
+
+    let infos = fields
+        .named
+        .iter()
+        .filter_map(|field| {
+            let attr = field
+                .attrs
+                .iter()
+                .find(|attribute| is_our_attr(attribute))?;
+            let name = json_name(field, camel_case);
+            let range = documented_range(&field.attrs);
+            let functional = is_functional(attr.clone());
+            let required =
+                !Storage::from_type(&field.ty).optional && !has_serde_default(&field.attrs);
+
+            Some(quote! {
+                ::activitystreams_traits::properties::PropertyInfo {
+                    name: #name,
+                    range: #range,
+                    functional: #functional,
+                    required: #required,
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
     let impls = fields
         .named
         .iter()
         .filter_map(|field| {
-            let our_attr = field.attrs.iter().find(|attribute| {
-                attribute
-                    .path
-                    .segments
-                    .last()
-                    .map(|segment| {
-                        let segment = segment.into_value();
-                        segment.ident == Ident::new("activitystreams", segment.ident.span())
-                    })
-                    .unwrap_or(false)
-            });
-
-            if our_attr.is_some() {
-                let our_attr = our_attr.unwrap();
-
-                let is_option = match field.ty {
-                    Type::Path(ref path) => path.path
-                        .segments
-                        .last()
-                        .map(|seg| {
-                            let seg = seg.into_value();
-                            seg.ident == Ident::new("Option", seg.ident.span())
-                        })
-                        .unwrap_or(false),
-                    _ => false,
-                };
-
-                let is_vec = match field.ty {
-                    Type::Path(ref path) => path.path
-                        .segments
-                        .last()
-                        .map(|seg| {
-                            let seg = seg.into_value();
-                            seg.ident == Ident::new("Vec", seg.ident.span())
-                        })
-                        .unwrap_or(false),
-                    _ => false,
-                };
-
-                Some((
-                    field.ident.clone().unwrap(),
-                    is_option,
-                    is_vec,
-                    is_functional(our_attr.clone()),
-                    our_attr.clone(),
-                ))
-            } else {
-                None
-            }
+            field
+                .attrs
+                .iter()
+                .find(|attribute| is_our_attr(attribute))
+                .map(|attr| {
+                    (
+                        field.ident.unwrap(),
+                        Storage::from_type(&field.ty),
+                        is_functional(attr.clone()),
+                        attr.clone(),
+                    )
+                })
         })
-        .flat_map(|(ident, is_option, is_vec, is_functional, attr)| {
+        .flat_map(|(ident, storage, is_functional, attr)| {
             variants(attr)
                 .into_iter()
                 .map(move |(variant, is_concrete)| {
@@ -267,327 +356,175 @@ pub fn properties_derive(input: TokenStream) -> TokenStream {
                     let fn_plural = Ident::from(format!("{}_{}_vec", ident, lower_variant));
                     let set_fn_name = Ident::from(format!("set_{}_{}", ident, lower_variant));
                     let set_fn_plural = Ident::from(format!("set_{}_{}_vec", ident, lower_variant));
+                    let property = ident.to_string();
+                    let variant_name = variant.clone();
                     let variant = Ident::from(variant);
 
-                    if is_concrete {
-                        if is_option {
-                            let single_1 = quote! {
-                                /// Retrieve a value from the given struct
-                                ///
-                                /// This method deserializes the item from JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::NotFound` and
-                                /// `Error::Deserialize`
-                                pub fn #fn_name(&self) -> ::activitystreams_traits::Result<#variant> {
-                                    ::activitystreams_traits::properties::from_item(&self.#ident)
-                                }
-                            };
+                    // Errors record which property and variant they occurred for
+                    let context = quote! {
+                        .map_err(|e| e.for_property(#property, #variant_name))
+                    };
 
-                            let single_2 = quote! {
-                                /// Set a value in the given struct
-                                ///
-                                /// This method serializes the item to JSON, so be wary of using this a
-                                /// lot.
-                                ///
-                                /// Possible errors from this method are `Error::Serialize`
-                                pub fn #set_fn_name(&mut self, item: #variant) -> ::activitystreams_traits::Result<()> {
-                                    self.#ident = ::activitystreams_traits::properties::to_item(item)?;
-                                    Ok(())
-                                }
-                            };
-
-                            let single = quote! {
-                                #single_1
-                                #single_2
-                            };
-
-                            if is_functional {
-                                single
-                            } else {
-                                let plural_1 = quote! {
-                                    /// Retrieve many values from the given struct
-                                    ///
-                                    /// This method deserializes the item from JSON, so be wary of using
-                                    /// this a lot.
-                                    ///
-                                    /// Possible errors from this method are `Error::NotFound` and
-                                    /// `Error::Deserialize`
-                                    pub fn #fn_plural(&self) -> ::activitystreams_traits::Result<Vec<#variant>> {
-                                        ::activitystreams_traits::properties::from_item(&self.#ident)
-                                    }
-                                };
-
-                                let plural_2 = quote! {
-                                    /// Set many values in the given struct
-                                    ///
-                                    /// This method serializes the item to JSON, so be wary of using
-                                    /// this a lot.
-                                    ///
-                                    /// Possible errors from this method are `Error::Serialize`
-                                    pub fn #set_fn_plural(&mut self, item: Vec<#variant>) -> ::activitystreams_traits::Result<()> {
-                                        self.#ident = ::activitystreams_traits::properties::to_item(item)?;
-                                        Ok(())
-                                    }
-                                };
-
-                                quote! {
-                                    #single
-                                    #plural_1
-                                    #plural_2
-                                }
-                            }
-                        } else if is_vec {
-                            let single_1 = quote! {
-                                /// Retrieve many values from the given struct
-                                ///
-                                /// This method deserializes the item from JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::Deserialize`
-                                pub fn #fn_name(&self) -> ::activitystreams_traits::Result<Vec<#variant>> {
-                                    ::activitystreams_traits::properties::from_vec(&self.#ident)
-                                }
-                            };
+                    let is_ref = is_concrete && storage.concrete == Some(variant);
 
-                            let single_2 = quote! {
-                                /// Set many values in the given struct
-                                ///
-                                /// This method serializes the item to JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::Serialize`
-                                pub fn #set_fn_name(&mut self, item: Vec<#variant>>) -> ::activitystreams_traits::Result<()> {
-                                    self.#ident = ::activitystreams_traits::properties::to_vec(item)?;
-                                    Ok(())
-                                }
-                            };
+                    let (generics, ty) = if is_concrete {
+                        (quote! {}, quote! { #variant })
+                    } else {
+                        (quote! { <T: #variant> }, quote! { T })
+                    };
 
-                            quote! {
-                                #single_1
-                                #single_2
-                            }
-                        } else {
-                            let single = quote! {
-                                /// Retrieve a value from the given struct
-                                ///
-                                /// This method deserializes the item from JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::Deserialize`
-                                pub fn #fn_name(&self) -> ::activitystreams_traits::Result<#variant> {
-                                    ::activitystreams_traits::properties::from_value(&self.#ident)
-                                }
+                    let ret = if is_ref {
+                        quote! { &#ty }
+                    } else {
+                        quote! { #ty }
+                    };
 
-                                /// Set a value in the given struct
-                                ///
-                                /// This method serializes the item to JSON, so be wary of using this a
-                                /// lot.
-                                ///
-                                /// Possible errors from this method are `Error::Serialize`
-                                pub fn #set_fn_name(&mut self, item: #variant) -> ::activitystreams_traits::Result<()> {
-                                    self.#ident = ::activitystreams_traits::properties::to_value(item)?;
-                                    Ok(())
-                                }
-                            };
-
-                            if is_functional {
-                                single
-                            } else {
-                                let plural_1 = quote! {
-                                    /// Retrieve many values from the given struct
-                                    ///
-                                    /// This method deserializes the item from JSON, so be wary of using
-                                    /// this a lot.
-                                    ///
-                                    /// Possible errors from this method are `Error::Deserialize`
-                                    pub fn #fn_plural(&self) -> ::activitystreams_traits::Result<Vec<#variant>> {
-                                        ::activitystreams_traits::properties::from_value(&self.#ident)
-                                    }
-                                };
-
-                                let plural_2 = quote! {
-                                    /// Set many values in the given struct
-                                    ///
-                                    /// This method serializes the item to JSON, so be wary of using this
-                                    /// a lot.
-                                    ///
-                                    /// Possible errors from this method are `Error::Serialize`
-                                    pub #set_fn_plural(&mut self, item: Vec<#variant>) -> ::activitystreams_traits::Result<()> {
-                                        self.#ident = ::activitystreams_traits::properties::to_value(item)?;
-                                        Ok(())
-                                    }
-                                };
-
-                                quote! {
-                                    #single
-                                    #plural_1
-                                    #plural_2
-                                }
-                            }
+                    let item = storage.item(&ident);
+                    let read_variant = storage.read_variant(is_ref);
+                    let write_variant = storage.write_variant(is_ref);
+                    let wrap_one = storage.wrap(quote! {
+                        ::activitystreams_traits::properties::OneOrMany::One(item)
+                    });
+                    let wrap_item = storage.wrap(quote! { item });
+
+                    let store = if storage.many { wrap_one } else { wrap_item.clone() };
+
+                    let get_doc = if is_ref {
+                        quote! {
+                            /// Borrow a value from the given struct
+                            ///
+                            /// Possible errors from this method are of the kinds
+                            /// `ErrorKind::NotFound` and `ErrorKind::Deserialize`
                         }
-                    } else if is_option {
-                        let single_1 = quote! {
-                            /// Retrieve a value of type T from the given struct
+                    } else {
+                        quote! {
+                            /// Retrieve a value from the given struct
                             ///
                             /// This method deserializes the item from JSON, so be wary of using
                             /// this a lot.
                             ///
-                            /// Possible errors from this method are `Error::NotFound` and
-                            /// `Error::Deserialize`
-                            pub fn #fn_name<T: #variant>(&self) -> ::activitystreams_traits::Result<T> {
-                                ::activitystreams_traits::properties::from_item(&self.#ident)
-                            }
-                        };
+                            /// Possible errors from this method are of the kinds
+                            /// `ErrorKind::NotFound` and `ErrorKind::Deserialize`
+                        }
+                    };
 
-                        let single_2 = quote! {
-                            /// Set a value of type T in the given struct
-                            ///
-                            /// This method serializes the item to JSON, so be wary of using this a
-                            /// lot.
-                            ///
-                            /// Possible errors from this method are `Error::Serialize`
-                            pub fn #set_fn_name<T: #variant>(&mut self, item: T) -> ::activitystreams_traits::Result<()> {
-                                self.#ident = ::activitystreams_traits::properties::to_item(item)?;
-                                Ok(())
-                            }
-                        };
+                    let single = quote! {
+                        #get_doc
+                        pub fn #fn_name #generics(&self) -> ::activitystreams_traits::Result<#ret> {
+                            #item.and_then(|item| #read_variant)#context
+                        }
 
-                        let single = quote!{
-                            #single_1
-                            #single_2
-                        };
+                        /// Set a value in the given struct
+                        ///
+                        /// Possible errors from this method are of the kind `ErrorKind::Serialize`
+                        pub fn #set_fn_name #generics(&mut self, item: #ty) -> ::activitystreams_traits::Result<()> {
+                            let item = #write_variant#context?;
+                            self.#ident = #store;
+                            Ok(())
+                        }
+                    };
 
-                        if is_functional {
-                            single
-                        } else {
-                            let plural_1 = quote! {
-                                /// Retrieve many values of type T from the given struct
-                                ///
-                                /// This method deserializes the item from JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::NotFound` and
-                                /// `Error::Deserialize`
-                                pub fn #fn_plural<T: #variant>(&self) -> ::activitystreams_traits::Result<Vec<T>> {
-                                    ::activitystreams_traits::properties::from_item(&self.#ident)
-                                }
-                            };
+                    if is_functional {
+                        return single;
+                    }
 
-                            let plural_2 = quote! {
-                                /// Set many values of type T in the given struct
-                                ///
-                                /// This method serializes the item to JSON, so be wary of using
-                                /// this a lot.
+                    let plural = if storage.many {
+                        let items = storage.items(&ident);
+                        let wrap_many = storage.wrap(quote! {
+                            ::activitystreams_traits::properties::OneOrMany::Many(item)
+                        });
+
+                        // Stored values are cloned out of the plural getter, which keeps
+                        // returning owned values, and borrowed by a separate accessor
+                        let get_plural = if is_ref {
+                            let fn_refs = Ident::from(format!("{}_{}_refs", ident, lower_variant));
+
+                            quote! {
+                                /// Retrieve many values from the given struct
                                 ///
-                                /// Possible errors from this method are `Error::Serialize`
-                                pub fn #set_fn_plural<T: #variant>(&mut self, item: Vec<T>) -> ::activitystreams_traits::Result<()> {
-                                    self.#ident = ::activitystreams_traits::properties::to_item(item)?;
-                                    Ok(())
+                                /// Possible errors from this method are of the kinds
+                                /// `ErrorKind::NotFound` and `ErrorKind::Deserialize`
+                                pub fn #fn_plural(&self) -> ::activitystreams_traits::Result<Vec<#ty>> {
+                                    #items.and_then(|items| {
+                                        items.iter().map(|item| #read_variant.map(Clone::clone)).collect()
+                                    })#context
                                 }
-                            };
 
-                            quote! {
-                                #single
-                                #plural_1
-                                #plural_2
+                                #get_doc
+                                pub fn #fn_refs(&self) -> ::activitystreams_traits::Result<Vec<#ret>> {
+                                    #items.and_then(|items| {
+                                        items.iter().map(|item| #read_variant).collect()
+                                    })#context
+                                }
                             }
-                        }
-                    } else if is_vec {
-                        let single_1 = quote! {
-                            /// Retrieve many values of type T from the given struct
-                            ///
-                            /// This method deserializes the item from JSON, so be wary of using
-                            /// this a lot.
-                            ///
-                            /// Possible errors from this method are `Error::Deserialize`
-                            pub fn #fn_name<T: #variant>(&self) -> ::activitystreams_traits::Result<Vec<T>> {
-                                ::activitystreams_traits::properties::from_vec(&self.#ident)
+                        } else {
+                            quote! {
+                                #get_doc
+                                pub fn #fn_plural #generics(&self) -> ::activitystreams_traits::Result<Vec<#ret>> {
+                                    #items.and_then(|items| {
+                                        items.iter().map(|item| #read_variant).collect()
+                                    })#context
+                                }
                             }
                         };
 
-                        let single_2 = quote! {
-                            /// Set many values of type T in the given struct
-                            ///
-                            /// This method serializes the item to JSON, so be wary of using
-                            /// this a lot.
+                        quote! {
+                            #get_plural
+
+                            /// Set many values in the given struct
                             ///
-                            /// Possible errors from this method are `Error::Serialize`
-                            pub fn #set_fn_name<T: #variant>(&mut self, item: Vec<T>) -> ::activitystreams_traits::Result<()> {
-                                self.#ident = ::activitystreams_traits::properties::to_vec(item)?;
+                            /// Possible errors from this method are of the kind `ErrorKind::Serialize`
+                            pub fn #set_fn_plural #generics(&mut self, item: Vec<#ty>) -> ::activitystreams_traits::Result<()> {
+                                let item = item
+                                    .into_iter()
+                                    .map(|item| #write_variant)
+                                    .collect::<::activitystreams_traits::Result<Vec<_>>>()#context?;
+                                self.#ident = #wrap_many;
                                 Ok(())
                             }
+                        }
+                    } else {
+                        let (to_vec, from_vec) = if storage.value {
+                            (
+                                quote! { ::activitystreams_traits::properties::from_value(item) },
+                                quote! { ::activitystreams_traits::properties::to_value(item) },
+                            )
+                        } else {
+                            (
+                                quote! { ::activitystreams_traits::properties::convert(item) },
+                                quote! { ::activitystreams_traits::properties::convert_into(&item) },
+                            )
                         };
 
                         quote! {
-                            #single_1
-                            #single_2
-                        }
-                    } else {
-                        let single_1 = quote! {
-                            /// Retrieve a value of type T from the given struct
+                            /// Retrieve many values from the given struct
                             ///
                             /// This method deserializes the item from JSON, so be wary of using
                             /// this a lot.
                             ///
-                            /// Possible errors from this method are `Error::Deserialize`
-                            pub fn #fn_name<T: #variant>(&self) -> ::activitystreams_traits::Result<T> {
-                                ::activitystreams_traits::properties::from_value(&self.#ident)
+                            /// Possible errors from this method are of the kinds
+                            /// `ErrorKind::NotFound` and `ErrorKind::Deserialize`
+                            pub fn #fn_plural #generics(&self) -> ::activitystreams_traits::Result<Vec<#ty>> {
+                                #item.and_then(|item| #to_vec)#context
                             }
-                        };
 
-                        let single_2 = quote! {
-                            /// Set a value of type T in the given struct
+                            /// Set many values in the given struct
                             ///
-                            /// This method serializes the item to JSON, so be wary of using this a
-                            /// lot.
+                            /// This method serializes the item to JSON, so be wary of using this
+                            /// a lot.
                             ///
-                            /// Possible errors from this method are `Error::Serialize`
-                            pub fn #set_fn_name<T: #variant>(&mut self, item: T) -> ::activitystreams_traits::Result<()> {
-                                self.#ident = ::activitystreams_traits::properties::to_value(item)?;
+                            /// Possible errors from this method are of the kind `ErrorKind::Serialize`
+                            pub fn #set_fn_plural #generics(&mut self, item: Vec<#ty>) -> ::activitystreams_traits::Result<()> {
+                                let item = #from_vec#context?;
+                                self.#ident = #wrap_item;
                                 Ok(())
                             }
-                        };
-
-                        let single = quote! {
-                            #single_1
-                            #single_2
-                        };
-
-                        if is_functional {
-                            single
-                        } else {
-                            let plural_1 = quote! {
-                                /// Retrieve many values of type T from the given struct
-                                ///
-                                /// This method deserializes the item from JSON, so be wary of using
-                                /// this a lot.
-                                ///
-                                /// Possible errors from this method are `Error::Deserialize`
-                                pub fn #fn_plural<T: #variant>(&self) -> ::activitystreams_traits::Result<Vec<T>> {
-                                    ::activitystreams_traits::properties::from_value(&self.#ident)
-                                }
-                            };
-
-                            let plural_2 = quote! {
-                                /// Set many values of type T in the given struct
-                                ///
-                                /// This method serializes the item to JSON, so be wary of using this
-                                /// a lot.
-                                ///
-                                /// Possible errors from this method are `Error::Serialize`
-                                pub fn #set_fn_plural<T: #variant>(&mut self, item: Vec<T>) -> ::activitystreams_traits::Result<()> {
-                                    self.#ident = ::activitystreams_traits::properties::to_value(item)?;
-                                    Ok(())
-                                }
-                            };
-
-                            quote! {
-                                #single
-                                #plural_1
-                                #plural_2
-                            }
                         }
+                    };
+
+                    quote! {
+                        #single
+                        #plural
                     }
                 })
         });
@@ -597,6 +534,11 @@ pub fn properties_derive(input: TokenStream) -> TokenStream {
 
     let full = quote!{
         impl #name {
+            /// The properties of this struct, with the ranges documented for them
+            pub const PROPERTIES: &'static [::activitystreams_traits::properties::PropertyInfo] = &[
+                #(#infos),*
+            ];
+
             #tokens
         }
     };
@@ -604,6 +546,326 @@ pub fn properties_derive(input: TokenStream) -> TokenStream {
     full.into()
 }
 
+/// Describes how a property is stored in its struct
+///
+/// Properties can be stored as `T`, `Option<T>`, `OneOrMany<T>`, or `Option<OneOrMany<T>>`, where
+/// `T` is either a concrete type, `serde_json::Value`, or `Either<C, serde_json::Value>`.
+struct Storage {
+    /// Whether the property is wrapped in an `Option`
+    optional: bool,
+
+    /// Whether the property is wrapped in a `OneOrMany`
+    many: bool,
+
+    /// Whether the stored item is an `Either`
+    either: bool,
+
+    /// Whether the stored item is a `serde_json::Value`
+    value: bool,
+
+    /// The concrete type that can be borrowed from the stored item
+    concrete: Option<Ident>,
+}
+
+impl Storage {
+    fn from_type(ty: &Type) -> Self {
+        let (optional, ty) = match unwrap_type(ty, "Option") {
+            Some(inner) => (true, inner),
+            None => (false, ty),
+        };
+
+        let (many, ty) = match unwrap_type(ty, "OneOrMany") {
+            Some(inner) => (true, inner),
+            None => (false, ty),
+        };
+
+        let (either, ty) = match unwrap_type(ty, "Either") {
+            Some(inner) => (true, inner),
+            None => (false, ty),
+        };
+
+        let concrete = last_ident(ty);
+        let value = !either && concrete == Some(Ident::from("Value"));
+
+        Storage {
+            optional,
+            many,
+            either,
+            value,
+            concrete,
+        }
+    }
+
+    /// Produce an expression borrowing the single stored item
+    fn item(&self, ident: &Ident) -> Tokens {
+        let not_found = error("NotFound");
+        let deserialize = error("Deserialize");
+
+        match (self.optional, self.many) {
+            (true, true) => quote! {
+                self.#ident
+                    .as_ref()
+                    .ok_or_else(|| #not_found)
+                    .and_then(|item| item.as_one().ok_or_else(|| #deserialize))
+            },
+            (true, false) => quote! {
+                self.#ident.as_ref().ok_or_else(|| #not_found)
+            },
+            (false, true) => quote! {
+                self.#ident.as_one().ok_or_else(|| #deserialize)
+            },
+            (false, false) => quote! {
+                ::activitystreams_traits::Result::Ok(&self.#ident)
+            },
+        }
+    }
+
+    /// Produce an expression borrowing all stored items as a slice
+    fn items(&self, ident: &Ident) -> Tokens {
+        let not_found = error("NotFound");
+
+        if self.optional {
+            quote! {
+                self.#ident
+                    .as_ref()
+                    .map(|items| items.as_slice())
+                    .ok_or_else(|| #not_found)
+            }
+        } else {
+            quote! {
+                ::activitystreams_traits::Result::Ok(self.#ident.as_slice())
+            }
+        }
+    }
+
+    /// Produce an expression turning a borrowed `item` into the requested variant
+    fn read_variant(&self, is_ref: bool) -> Tokens {
+        if is_ref && self.either {
+            let deserialize = error("Deserialize");
+            quote! { item.left().ok_or_else(|| #deserialize) }
+        } else if is_ref {
+            quote! { ::activitystreams_traits::Result::Ok(item) }
+        } else if self.value {
+            quote! { ::activitystreams_traits::properties::from_value(item) }
+        } else if self.either {
+            let read_left = self.read_concrete(quote! { left });
+
+            quote! {
+                match *item {
+                    ::activitystreams_traits::properties::Either::Left(ref left) => #read_left,
+                    ::activitystreams_traits::properties::Either::Right(ref right) => {
+                        ::activitystreams_traits::properties::from_value(right)
+                    }
+                }
+            }
+        } else {
+            self.read_concrete(quote! { item })
+        }
+    }
+
+    /// Produce an expression turning a borrowed concrete item into another type, reading strings
+    /// directly rather than through a `Value`
+    fn read_concrete(&self, item: Tokens) -> Tokens {
+        if self.concrete == Some(Ident::from("String")) {
+            quote! { ::activitystreams_traits::properties::from_str(#item) }
+        } else {
+            quote! { ::activitystreams_traits::properties::convert(#item) }
+        }
+    }
+
+    /// Produce an expression turning an owned `item` of the requested variant into the stored
+    /// type
+    fn write_variant(&self, is_ref: bool) -> Tokens {
+        if is_ref && self.either {
+            quote! {
+                ::activitystreams_traits::Result::Ok(
+                    ::activitystreams_traits::properties::Either::Left(item)
+                )
+            }
+        } else if is_ref {
+            quote! { ::activitystreams_traits::Result::Ok(item) }
+        } else if self.value {
+            quote! { ::activitystreams_traits::properties::to_value(item) }
+        } else {
+            quote! { ::activitystreams_traits::properties::convert_into(&item) }
+        }
+    }
+
+    /// Wrap an expression producing the stored type in an `Option` if needed
+    fn wrap(&self, tokens: Tokens) -> Tokens {
+        if self.optional {
+            quote! { Some(#tokens) }
+        } else {
+            tokens
+        }
+    }
+}
+
+/// Produce an expression creating an `Error` of the given kind, without any context
+fn error(kind: &str) -> Tokens {
+    let kind = Ident::from(kind);
+
+    quote! {
+        ::activitystreams_traits::Error::new(::activitystreams_traits::ErrorKind::#kind)
+    }
+}
+
+/// Fetch the type argument of `ty` if it is the type `wrapper`
+fn unwrap_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = match *ty {
+        Type::Path(ref path) => path.path.segments.last()?.into_value(),
+        _ => return None,
+    };
+
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) => {
+            args.args.iter().find_map(|arg| match *arg {
+                GenericArgument::Type(ref ty) => Some(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Fetch the final identifier in the path of `ty`
+fn last_ident(ty: &Type) -> Option<Ident> {
+    match *ty {
+        Type::Path(ref path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.into_value().ident),
+        _ => None,
+    }
+}
+
+/// Whether the struct's fields are renamed with `#[serde(rename_all = "camelCase")]`
+fn renames_to_camel_case(attrs: &[Attribute]) -> bool {
+    serde_values(attrs, "rename_all").any(|value| value == "camelCase")
+}
+
+/// The name of a field in JSON, as serde names it
+fn json_name(field: &Field, camel_case: bool) -> String {
+    if let Some(name) = serde_values(&field.attrs, "rename").next() {
+        return name;
+    }
+
+    let ident = field.ident.unwrap().to_string();
+    if !camel_case {
+        return ident;
+    }
+
+    let mut name = String::new();
+    let mut upper = false;
+    for c in ident.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            name.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Whether serde fills the field in when it is missing, with `default` or `default = "path"`
+fn has_serde_default(attrs: &[Attribute]) -> bool {
+    serde_values(attrs, "default").next().is_some()
+        || attrs
+            .iter()
+            .filter_map(|attr| attr.interpret_meta())
+            .filter_map(|meta| match meta {
+                Meta::List(ref list) if list.ident == "serde" => Some(list.nested.clone()),
+                _ => None,
+            })
+            .flat_map(|nested| nested.into_iter())
+            .any(|nested| match nested {
+                NestedMeta::Meta(Meta::Word(ref word)) => word == "default",
+                _ => false,
+            })
+}
+
+/// The values of `key = "value"` items in the `serde` attributes
+fn serde_values<'a>(
+    attrs: &'a [Attribute],
+    key: &'static str,
+) -> impl Iterator<Item = String> + 'a {
+    attrs
+        .iter()
+        .filter_map(|attr| attr.interpret_meta())
+        .filter_map(|meta| match meta {
+            Meta::List(list) => {
+                if list.ident == "serde" {
+                    Some(list.nested)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .flat_map(|nested| nested.into_iter())
+        .filter_map(move |nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(ref pair)) if pair.ident == key => match pair.lit {
+                Lit::Str(ref value) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// The range written in the `- Range:` line of a field's documentation
+///
+/// Ranges that are too long for one line continue until the next item of the list.
+fn documented_range(attrs: &[Attribute]) -> String {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| attr.interpret_meta())
+        .filter_map(|meta| match meta {
+            Meta::NameValue(pair) => {
+                if pair.ident != "doc" {
+                    return None;
+                }
+                match pair.lit {
+                    Lit::Str(value) => Some(value.value().trim().to_owned()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut range = Vec::new();
+    let mut in_range = false;
+    for line in &lines {
+        if let Some(rest) = line.strip_prefix("- Range:") {
+            in_range = true;
+            range.push(rest.trim());
+        } else if in_range && !line.is_empty() && !line.starts_with('-') {
+            range.push(line);
+        } else {
+            in_range = false;
+        }
+    }
+
+    range.join(" ").trim().to_owned()
+}
+
+fn is_our_attr(attribute: &Attribute) -> bool {
+    attribute
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.into_value().ident == "activitystreams")
+        .unwrap_or(false)
+}
+
 fn variants(attr: Attribute) -> Vec<(String, bool)> {
     let group = attr
         .tts
@@ -633,7 +895,7 @@ fn variants(attr: Attribute) -> Vec<(String, bool)> {
                 },
             )),
             _ => None,
-        }).flat_map(|i| i)
+        }).flatten()
         .collect()
 }
 
@@ -17,23 +17,173 @@
  * along with ActivityStreams Traits.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::result;
+use serde_json;
+use std::{error, fmt, result};
 
 /// The Error type
-#[derive(Copy, Clone, Debug, Eq, Fail, PartialEq)]
-pub enum Error {
+///
+/// Errors produced by the methods generated for a property carry the name of the property and
+/// the variant that was requested, such as `published` and `UtcTime`, and errors caused by
+/// serde carry the `serde_json::Error` as their source. Use `kind` to tell errors apart.
+///
+/// ```rust
+/// extern crate activitystreams_traits;
+/// extern crate serde_json;
+///
+/// use activitystreams_traits::{properties::from_value, ErrorKind};
+/// use std::error::Error;
+///
+/// # fn main() {
+/// let error = from_value::<u64>(&serde_json::Value::from("five"))
+///     .map_err(|e| e.for_property("total_items", "u64"))
+///     .unwrap_err();
+///
+/// assert_eq!(error.kind(), ErrorKind::Deserialize);
+/// assert_eq!(error.property(), Some("total_items"));
+/// assert_eq!(error.variant(), Some("u64"));
+/// assert!(error.source().is_some());
+/// assert_eq!(
+///     error.to_string(),
+///     "Failed to deserialize data as requested type for `total_items` as `u64`, \
+///      invalid type: string \"five\", expected u64"
+/// );
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    property: Option<String>,
+    variant: Option<&'static str>,
+    source: Option<Box<dyn error::Error + Send + Sync>>,
+}
+
+/// The kinds of Error
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ErrorKind {
     /// This error occurs when an Activity Streams type does not contain a requested value
-    #[fail(display = "Key not present")]
     NotFound,
 
     /// This error occurs when a requested value could not be deserialized into the requested type
-    #[fail(display = "Failed to deserialize data as requested type")]
     Deserialize,
 
     /// This error occurs when a provided item could not be serialized into an Activity Streams
     /// type
-    #[fail(display = "Failed to serialize data")]
     Serialize,
+
+    /// This error occurs when a provided value is not valid for the property it is set on, such
+    /// as a relative IRI for `id`
+    InvalidValue,
+}
+
+impl Error {
+    /// Create an error of the given kind, without any context
+    pub fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            property: None,
+            variant: None,
+            source: None,
+        }
+    }
+
+    /// Create a `Deserialize` error caused by serde
+    pub fn deserialize(source: serde_json::Error) -> Self {
+        Error {
+            source: Some(Box::new(source)),
+            ..Error::new(ErrorKind::Deserialize)
+        }
+    }
+
+    /// Create a `Serialize` error caused by serde
+    pub fn serialize(source: serde_json::Error) -> Self {
+        Error {
+            source: Some(Box::new(source)),
+            ..Error::new(ErrorKind::Serialize)
+        }
+    }
+
+    /// Create an `InvalidValue` error, whose source explains why the value was rejected
+    pub fn invalid_value<E>(source: E) -> Self
+    where
+        E: error::Error + Send + Sync + 'static,
+    {
+        Error {
+            source: Some(Box::new(source)),
+            ..Error::new(ErrorKind::InvalidValue)
+        }
+    }
+
+    /// Record the property and variant the error occurred for, unless they are already known
+    pub fn for_property<T>(mut self, property: T, variant: &'static str) -> Self
+    where
+        T: Into<String>,
+    {
+        if self.property.is_none() {
+            self.property = Some(property.into());
+            self.variant = Some(variant);
+        }
+        self
+    }
+
+    /// The kind of error, for matching on
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The name of the property the error occurred for, if known
+    pub fn property(&self) -> Option<&str> {
+        self.property.as_deref()
+    }
+
+    /// The variant that was requested from or provided for the property, if known
+    pub fn variant(&self) -> Option<&'static str> {
+        self.variant
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match *self {
+            ErrorKind::NotFound => "Key not present",
+            ErrorKind::Deserialize => "Failed to deserialize data as requested type",
+            ErrorKind::Serialize => "Failed to serialize data",
+            ErrorKind::InvalidValue => "Invalid value",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.kind, f)?;
+
+        if let Some(ref property) = self.property {
+            write!(f, " for `{}`", property)?;
+        }
+        if let Some(variant) = self.variant {
+            write!(f, " as `{}`", variant)?;
+        }
+        if let Some(ref source) = self.source {
+            write!(f, ", {}", source)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| &**source as &(dyn error::Error + 'static))
+    }
 }
 
 /// An alias for Result<T, Error>
@@ -49,9 +49,9 @@
 //! # fn main() {}
 //! ```
 
-#[macro_use]
-extern crate failure;
 extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate serde_json;
 
 mod activity;
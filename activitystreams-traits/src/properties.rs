@@ -19,18 +19,256 @@
 
 //! A module containing helpers for tranlsating common JSON representations to and from concrete
 //! types
+//!
+//! Properties in the Activity Streams vocabulary that are not functional can hold either a single
+//! value or an array of values. The `OneOrMany` type models this directly, so that typed values
+//! can be stored without losing track of how they were represented in JSON. Properties whose range
+//! mixes a concrete type with `Object` or `Link` types can use `Either` to keep the concrete values
+//! typed, while holding everything else as JSON.
 
-use serde::{de::DeserializeOwned, ser::Serialize};
+use serde::{
+    de::{value::StrDeserializer, DeserializeOwned, IntoDeserializer},
+    ser::Serialize,
+};
 use serde_json;
+use std::{mem, slice};
 
-use error::{Error, Result};
+use error::{Error, ErrorKind, Result};
+
+/// A value that is represented either as a single item or as an array of items
+///
+/// This serializes and deserializes exactly like the JSON that was received, so a single item
+/// stays a single item and an array stays an array.
+///
+/// ```rust
+/// extern crate activitystreams_traits;
+/// extern crate serde_json;
+///
+/// use activitystreams_traits::properties::OneOrMany;
+///
+/// # fn main() {
+/// let one: OneOrMany<String> = serde_json::from_str(r#""hi""#).unwrap();
+/// let many: OneOrMany<String> = serde_json::from_str(r#"["hi", "there"]"#).unwrap();
+///
+/// assert_eq!(one.as_one().map(|s| s.as_str()), Some("hi"));
+/// assert_eq!(many.as_slice().len(), 2);
+/// assert_eq!(serde_json::to_string(&one).unwrap(), r#""hi""#);
+/// # }
+/// ```
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    /// Holds a list of values, represented as a JSON array
+    Many(Vec<T>),
+
+    /// Holds a single value
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    /// Borrow the single value held by this type
+    ///
+    /// An array containing exactly one item is treated as a single value. Returns `None` if there
+    /// are zero or many values.
+    pub fn as_one(&self) -> Option<&T> {
+        match *self {
+            OneOrMany::One(ref one) => Some(one),
+            OneOrMany::Many(ref many) if many.len() == 1 => many.first(),
+            OneOrMany::Many(_) => None,
+        }
+    }
+
+    /// Mutably borrow the single value held by this type
+    ///
+    /// An array containing exactly one item is treated as a single value. Returns `None` if there
+    /// are zero or many values.
+    pub fn as_one_mut(&mut self) -> Option<&mut T> {
+        match *self {
+            OneOrMany::One(ref mut one) => Some(one),
+            OneOrMany::Many(ref mut many) if many.len() == 1 => many.first_mut(),
+            OneOrMany::Many(_) => None,
+        }
+    }
+
+    /// Borrow every value held by this type as a slice
+    pub fn as_slice(&self) -> &[T] {
+        match *self {
+            OneOrMany::One(ref one) => slice::from_ref(one),
+            OneOrMany::Many(ref many) => many,
+        }
+    }
+
+    /// Mutably borrow every value held by this type as a slice
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        match *self {
+            OneOrMany::One(ref mut one) => slice::from_mut(one),
+            OneOrMany::Many(ref mut many) => many,
+        }
+    }
+
+    /// Iterate over every value held by this type
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Iterate mutably over every value held by this type
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// The number of values held by this type
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Check whether this type holds no values
+    ///
+    /// This can only happen when an empty array was provided.
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Add a value, turning a single value into an array if needed
+    pub fn push(&mut self, item: T) {
+        let mut items = mem::replace(self, OneOrMany::Many(Vec::new())).into_vec();
+        items.push(item);
+        *self = OneOrMany::Many(items);
+    }
+
+    /// Take every value held by this type
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(one) => vec![one],
+            OneOrMany::Many(many) => many,
+        }
+    }
+}
+
+impl<T> Default for OneOrMany<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        OneOrMany::One(T::default())
+    }
+}
+
+impl<T> From<T> for OneOrMany<T> {
+    fn from(item: T) -> Self {
+        OneOrMany::One(item)
+    }
+}
+
+impl<T> From<Vec<T>> for OneOrMany<T> {
+    fn from(items: Vec<T>) -> Self {
+        OneOrMany::Many(items)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a OneOrMany<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A property of a struct deriving `Properties`, as described by its field
+///
+/// The `Properties` derive generates a `PROPERTIES` table of these for every struct, so the
+/// vocabulary a struct supports can be inspected without repeating it by hand.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PropertyInfo {
+    /// The name of the property in JSON, such as `inReplyTo`
+    pub name: &'static str,
+
+    /// The range of the property as written in the `- Range:` line of its documentation, empty
+    /// if it is not documented
+    pub range: &'static str,
+
+    /// Whether the property is functional, and so holds a single value
+    pub functional: bool,
+
+    /// Whether the property must be present, because it is neither stored in an `Option` nor
+    /// filled in by a serde `default`
+    pub required: bool,
+}
+
+/// A type whose properties are described by `PROPERTIES` tables
+///
+/// This tells which keys of a document a type keeps without deserializing and serializing it
+/// again. The types of `activitystreams-types` implement it with the tables of the structs they
+/// flatten.
+pub trait Vocabulary {
+    /// The range of `type`, such as "`Note`", or an empty string if any type is accepted
+    const TYPE: &'static str = "";
+
+    /// The properties kept by a value with the given types, apart from `type` itself
+    ///
+    /// Types that hold one of several types, such as `AnyObject`, pick the properties of the type
+    /// they would hold. This is `None` if every property is kept, as it is for a value whose type
+    /// is not recognised.
+    fn properties(kinds: &[&str]) -> Option<Vec<&'static PropertyInfo>>;
+}
+
+/// A value that is one of two types
+///
+/// When deserializing, the `Left` type is tried first. This is used for properties whose range
+/// contains a concrete type, such as `xsd:anyUri`, alongside `Object` or `Link` types.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Either<L, R> {
+    /// Holds the first type
+    Left(L),
+
+    /// Holds the second type
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    /// Borrow the value if it is `Left`
+    pub fn left(&self) -> Option<&L> {
+        match *self {
+            Either::Left(ref left) => Some(left),
+            Either::Right(_) => None,
+        }
+    }
+
+    /// Borrow the value if it is `Right`
+    pub fn right(&self) -> Option<&R> {
+        match *self {
+            Either::Left(_) => None,
+            Either::Right(ref right) => Some(right),
+        }
+    }
+
+    /// Check whether the value is `Left`
+    pub fn is_left(&self) -> bool {
+        self.left().is_some()
+    }
+
+    /// Check whether the value is `Right`
+    pub fn is_right(&self) -> bool {
+        self.right().is_some()
+    }
+}
 
 /// Deserialize a `Value` into concrete type I
 pub fn from_value<I>(item: &serde_json::Value) -> Result<I>
 where
     I: DeserializeOwned,
 {
-    serde_json::from_value(item.clone()).map_err(|_| Error::Deserialize)
+    I::deserialize(item).map_err(Error::deserialize)
+}
+
+/// Deserialize a string into concrete type I, such as a `DateTime`, without building a `Value`
+pub fn from_str<I>(item: &str) -> Result<I>
+where
+    I: DeserializeOwned,
+{
+    let deserializer: StrDeserializer<serde_json::Error> = item.into_deserializer();
+    I::deserialize(deserializer).map_err(Error::deserialize)
 }
 
 /// Serialize concrete type I into a `Value`
@@ -38,7 +276,34 @@ pub fn to_value<I>(item: I) -> Result<serde_json::Value>
 where
     I: Serialize,
 {
-    serde_json::to_value(item).map_err(|_| Error::Serialize)
+    serde_json::to_value(item).map_err(Error::serialize)
+}
+
+/// Convert between two types that share a JSON representation
+///
+/// This is used for reading a typed value as a different type than the one it is stored as, for
+/// example reading a `String` as a `DateTime`.
+pub fn convert<I, O>(item: &I) -> Result<O>
+where
+    I: Serialize,
+    O: DeserializeOwned,
+{
+    serde_json::to_value(item)
+        .and_then(serde_json::from_value)
+        .map_err(Error::deserialize)
+}
+
+/// Convert a value into the type it is stored as
+///
+/// This is the inverse of `convert`, and differs only in the error it produces.
+pub fn convert_into<I, O>(item: &I) -> Result<O>
+where
+    I: Serialize,
+    O: DeserializeOwned,
+{
+    serde_json::to_value(item)
+        .and_then(serde_json::from_value)
+        .map_err(Error::serialize)
 }
 
 /// Deserialize an `Option<Value>` into concrete type I
@@ -46,10 +311,10 @@ pub fn from_item<I>(item: &Option<serde_json::Value>) -> Result<I>
 where
     I: DeserializeOwned,
 {
-    if let &Some(ref item) = item {
+    if let Some(ref item) = *item {
         from_value(item)
     } else {
-        Err(Error::NotFound)
+        Err(Error::new(ErrorKind::NotFound))
     }
 }
 
@@ -61,18 +326,12 @@ where
     to_value(item).map(Some)
 }
 
-/// Deserialize a `Vec<Value>` into a `Vec<I>`
-pub fn from_vec<I>(v: &Vec<serde_json::Value>) -> Result<Vec<I>>
+/// Deserialize a slice of `Value`s into a `Vec<I>`
+pub fn from_vec<I>(v: &[serde_json::Value]) -> Result<Vec<I>>
 where
     I: DeserializeOwned,
 {
-    v.iter().fold(Ok(Vec::new()), |acc, item| match acc {
-        Ok(mut acc) => from_value(item).map(|item| {
-            acc.push(item);
-            acc
-        }),
-        e => e,
-    })
+    v.iter().map(from_value).collect()
 }
 
 /// Serialize a `Vec<I>` into a `Vec<Value>`
@@ -80,11 +339,5 @@ pub fn to_vec<I>(v: Vec<I>) -> Result<Vec<serde_json::Value>>
 where
     I: Serialize,
 {
-    v.into_iter().fold(Ok(Vec::new()), |acc, item| match acc {
-        Ok(mut acc) => to_value(item).map(|item| {
-            acc.push(item);
-            acc
-        }),
-        e => e,
-    })
+    v.into_iter().map(to_value).collect()
 }
@@ -17,116 +17,117 @@
  * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Namespace for Unit Structs that serialize to strings
+//! Namespace for the structs that serialize to the `type` of each activity, like those of
+//! `object::kind`
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Accept)]
-pub struct AcceptType;
+pub struct AcceptType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Add)]
-pub struct AddType;
+pub struct AddType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Move)]
-pub struct MoveType;
+pub struct MoveType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Announce)]
-pub struct AnnounceType;
+pub struct AnnounceType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Arrive)]
-pub struct ArriveType;
+pub struct ArriveType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Block)]
-pub struct BlockType;
+pub struct BlockType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Create)]
-pub struct CreateType;
+pub struct CreateType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Delete)]
-pub struct DeleteType;
+pub struct DeleteType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Dislike)]
-pub struct DislikeType;
+pub struct DislikeType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Flag)]
-pub struct FlagType;
+pub struct FlagType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Follow)]
-pub struct FollowType;
+pub struct FollowType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Ignore)]
-pub struct IgnoreType;
+pub struct IgnoreType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Invite)]
-pub struct InviteType;
+pub struct InviteType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Join)]
-pub struct JoinType;
+pub struct JoinType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Leave)]
-pub struct LeaveType;
+pub struct LeaveType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Like)]
-pub struct LikeType;
+pub struct LikeType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Listen)]
-pub struct ListenType;
+pub struct ListenType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Offer)]
-pub struct OfferType;
+pub struct OfferType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Question)]
-pub struct QuestionType;
+pub struct QuestionType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Real)]
-pub struct ReadType;
+pub struct ReadType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Reject)]
-pub struct RejectType;
+pub struct RejectType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Remove)]
-pub struct RemoveType;
+pub struct RemoveType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(TentativeAccept)]
-pub struct TentativeAcceptType;
+pub struct TentativeAcceptType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(TentativeReject)]
-pub struct TentativeRejectType;
+pub struct TentativeRejectType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Travel)]
-pub struct TravelType;
+pub struct TravelType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Undo)]
-pub struct UndoType;
+pub struct UndoType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Update)]
-pub struct UpdateType;
+pub struct UpdateType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(View)]
-pub struct ViewType;
+pub struct ViewType(Vec<String>);
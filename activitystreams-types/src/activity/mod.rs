@@ -77,9 +77,10 @@ pub use self::undo::*;
 pub use self::update::*;
 pub use self::view::*;
 
-use activitystreams_traits::Activity;
+use activitystreams_traits::{Activity, Object};
 
 use self::properties::ActivityProperties;
+use object::{properties::ObjectProperties, ObjectExt};
 
 /// The Activity Extension Trait
 ///
@@ -88,3 +89,116 @@ pub trait ActivityExt: Activity {
     fn props(&self) -> &ActivityProperties;
     fn props_mut(&mut self) -> &mut ActivityProperties;
 }
+
+vocabulary! {
+    Accept: "Accept" => [properties::AcceptProperties, ObjectProperties, ActivityProperties],
+    Add: "Add" => [properties::AddProperties, ObjectProperties, ActivityProperties],
+    AMove: "Move" => [properties::MoveProperties, ObjectProperties, ActivityProperties],
+    Announce: "Announce" => [properties::AnnounceProperties, ObjectProperties, ActivityProperties],
+    Arrive: "Arrive" => [properties::ArriveProperties, ObjectProperties, ActivityProperties],
+    Block: "Block" => [properties::BlockProperties, ObjectProperties, ActivityProperties],
+    Create: "Create" => [properties::CreateProperties, ObjectProperties, ActivityProperties],
+    Delete: "Delete" => [properties::DeleteProperties, ObjectProperties, ActivityProperties],
+    Dislike: "Dislike" => [properties::DislikeProperties, ObjectProperties, ActivityProperties],
+    Flag: "Flag" => [properties::FlagProperties, ObjectProperties, ActivityProperties],
+    Follow: "Follow" => [properties::FollowProperties, ObjectProperties, ActivityProperties],
+    Ignore: "Ignore" => [properties::IgnoreProperties, ObjectProperties, ActivityProperties],
+    Invite: "Invite" => [properties::InviteProperties, ObjectProperties, ActivityProperties],
+    Join: "Join" => [properties::JoinProperties, ObjectProperties, ActivityProperties],
+    Leave: "Leave" => [properties::LeaveProperties, ObjectProperties, ActivityProperties],
+    Like: "Like" => [properties::LikeProperties, ObjectProperties, ActivityProperties],
+    Listen: "Listen" => [properties::ListenProperties, ObjectProperties, ActivityProperties],
+    Offer: "Offer" => [properties::OfferProperties, ObjectProperties, ActivityProperties],
+    Question: "Question" => [properties::QuestionProperties, ObjectProperties, ActivityProperties],
+    Read: "Read" => [properties::ReadProperties, ObjectProperties, ActivityProperties],
+    Reject: "Reject" => [properties::RejectProperties, ObjectProperties, ActivityProperties],
+    Remove: "Remove" => [properties::RemoveProperties, ObjectProperties, ActivityProperties],
+    TentativeAccept: "TentativeAccept" => [
+        properties::TentativeAcceptProperties,
+        ObjectProperties,
+        ActivityProperties
+    ],
+    TentativeReject: "TentativeReject" => [
+        properties::TentativeRejectProperties,
+        ObjectProperties,
+        ActivityProperties
+    ],
+    Travel: "Travel" => [properties::TravelProperties, ObjectProperties, ActivityProperties],
+    Undo: "Undo" => [properties::UndoProperties, ObjectProperties, ActivityProperties],
+    Update: "Update" => [properties::UpdateProperties, ObjectProperties, ActivityProperties],
+    View: "View" => [properties::ViewProperties, ObjectProperties, ActivityProperties],
+}
+
+any_type! {
+    /// Any of the Activity types defined in this crate
+    ///
+    /// When deserializing, the `type` field is used to decide which Activity type to produce.
+    /// Activities with a `type` that is not recognised are kept as `AnyActivity::Unknown`.
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::activity::AnyActivity;
+    ///
+    /// # fn main() {
+    /// let activity: AnyActivity = serde_json::from_str(r#"{
+    ///     "type": "Follow",
+    ///     "actor": "https://example.com/users/alice",
+    ///     "object": "https://example.com/users/bob"
+    /// }"#).unwrap();
+    ///
+    /// match activity {
+    ///     AnyActivity::Follow(ref follow) => {
+    ///         let actor = follow.follow_props.actor.as_one().and_then(|actor| actor.as_str());
+    ///         assert_eq!(actor, Some("https://example.com/users/alice"));
+    ///     }
+    ///     _ => panic!("Expected a Follow"),
+    /// }
+    ///
+    /// let activity: AnyActivity = serde_json::from_str(r#"{ "type": "EmojiReact" }"#).unwrap();
+    ///
+    /// assert_eq!(activity.kind(), Some("EmojiReact"));
+    /// assert!(activity.is_unknown());
+    /// # }
+    /// ```
+    pub enum AnyActivity {
+        "Accept" => Accept,
+        "Add" => Add,
+        "Move" => AMove,
+        "Announce" => Announce,
+        "Arrive" => Arrive,
+        "Block" => Block,
+        "Create" => Create,
+        "Delete" => Delete,
+        "Dislike" => Dislike,
+        "Flag" => Flag,
+        "Follow" => Follow,
+        "Ignore" => Ignore,
+        "Invite" => Invite,
+        "Join" => Join,
+        "Leave" => Leave,
+        "Like" => Like,
+        "Listen" => Listen,
+        "Offer" => Offer,
+        "Question" => Question,
+        "Read" => Read,
+        "Reject" => Reject,
+        "Remove" => Remove,
+        "TentativeAccept" => TentativeAccept,
+        "TentativeReject" => TentativeReject,
+        "Travel" => Travel,
+        "Undo" => Undo,
+        "Update" => Update,
+        "View" => View,
+    }
+
+    /// Borrow the object properties of the contained Activity
+    ObjectExt::props -> ObjectProperties as object_props, object_props_mut;
+
+    /// Borrow the activity properties of the contained Activity
+    ActivityExt::props -> ActivityProperties as activity_props, activity_props_mut;
+}
+
+impl Object for AnyActivity {}
+impl Activity for AnyActivity {}
@@ -56,7 +56,7 @@
 //! # fn main() {}
 //! ```
 
-use activitystreams_traits::{Link, Object};
+use activitystreams_traits::{properties::OneOrMany, Link, Object};
 use serde_json;
 
 /// Activity objects are specializations of the base Object type that provide information about
@@ -74,7 +74,7 @@ pub struct ActivityProperties {
     /// - Funcitonal: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub result: Option<serde_json::Value>,
+    pub result: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies one or more objects used (or to be used) in the completion of an `Activity`.
     ///
@@ -82,7 +82,7 @@ pub struct ActivityProperties {
     /// - Funcitonal: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub instrument: Option<serde_json::Value>,
+    pub instrument: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Struct with `actor` and optional `origin` and `target` properties
@@ -98,7 +98,7 @@ pub struct ActorOptOriginAndTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// Describes an indirect object of the activity from which the activity is directed.
     ///
@@ -110,7 +110,7 @@ pub struct ActorOptOriginAndTarget {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub origin: Option<serde_json::Value>,
+    pub origin: Option<OneOrMany<serde_json::Value>>,
 
     /// Describes the indirect object, or target, of the activity.
     ///
@@ -123,7 +123,7 @@ pub struct ActorOptOriginAndTarget {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub target: Option<serde_json::Value>,
+    pub target: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Struct with `actor` and `object` properties
@@ -139,7 +139,7 @@ pub struct ActorAndObject {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// When used within an Activity, describes the direct object of the activity.
     ///
@@ -149,7 +149,7 @@ pub struct ActorAndObject {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub object: serde_json::Value,
+    pub object: OneOrMany<serde_json::Value>,
 }
 
 /// Struct with `actor`, `object`, and `target` properties
@@ -165,7 +165,7 @@ pub struct ActorObjectAndTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// When used within an Activity, describes the direct object of the activity.
     ///
@@ -175,7 +175,7 @@ pub struct ActorObjectAndTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub object: serde_json::Value,
+    pub object: OneOrMany<serde_json::Value>,
 
     /// Describes the indirect object, or target, of the activity.
     ///
@@ -187,7 +187,7 @@ pub struct ActorObjectAndTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub target: serde_json::Value,
+    pub target: OneOrMany<serde_json::Value>,
 }
 
 /// Struct with `actor`, `object`, and optional `target` properties
@@ -203,7 +203,7 @@ pub struct ActorAndObjectOptTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// When used within an Activity, describes the direct object of the activity.
     ///
@@ -213,7 +213,7 @@ pub struct ActorAndObjectOptTarget {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub object: serde_json::Value,
+    pub object: OneOrMany<serde_json::Value>,
 
     /// Describes the indirect object, or target, of the activity.
     ///
@@ -226,7 +226,7 @@ pub struct ActorAndObjectOptTarget {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub target: Option<serde_json::Value>,
+    pub target: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Struct with `actor`, `object`, and optional `origin` properties
@@ -242,7 +242,7 @@ pub struct ActorAndObjectOptOrigin {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// When used within an Activity, describes the direct object of the activity.
     ///
@@ -252,7 +252,7 @@ pub struct ActorAndObjectOptOrigin {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub object: serde_json::Value,
+    pub object: OneOrMany<serde_json::Value>,
 
     /// Describes an indirect object of the activity from which the activity is directed.
     ///
@@ -264,7 +264,7 @@ pub struct ActorAndObjectOptOrigin {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub origin: Option<serde_json::Value>,
+    pub origin: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Struct with `actor`, `object`, and optional `origin` and `target` properties
@@ -280,7 +280,7 @@ pub struct ActorAndObjectOptOthers {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// When used within an Activity, describes the direct object of the activity.
     ///
@@ -290,7 +290,7 @@ pub struct ActorAndObjectOptOthers {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub object: serde_json::Value,
+    pub object: OneOrMany<serde_json::Value>,
 
     /// Describes an indirect object of the activity from which the activity is directed.
     ///
@@ -302,7 +302,7 @@ pub struct ActorAndObjectOptOthers {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub origin: Option<serde_json::Value>,
+    pub origin: Option<OneOrMany<serde_json::Value>>,
 
     /// Describes the indirect object, or target, of the activity.
     ///
@@ -315,7 +315,7 @@ pub struct ActorAndObjectOptOthers {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub target: Option<serde_json::Value>,
+    pub target: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Struct with `actor` and `origin` properties
@@ -331,7 +331,7 @@ pub struct ActorAndOrigin {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub actor: serde_json::Value,
+    pub actor: OneOrMany<serde_json::Value>,
 
     /// Describes an indirect object of the activity from which the activity is directed.
     ///
@@ -342,14 +342,14 @@ pub struct ActorAndOrigin {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    pub origin: serde_json::Value,
+    pub origin: OneOrMany<serde_json::Value>,
 }
 
 /// Properties for the Accept activity
 pub type AcceptProperties = ActorAndObject;
 
 /// Properties for the Add activity
-pub type AddProperties = ActorAndObject;
+pub type AddProperties = ActorAndObjectOptOthers;
 
 /// Properties for the Move activity
 pub type MoveProperties = ActorAndObjectOptOthers;
@@ -412,7 +412,7 @@ pub struct QuestionProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub one_of: Option<serde_json::Value>,
+    pub one_of: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies an inclusive option for a Question.
     ///
@@ -423,7 +423,7 @@ pub struct QuestionProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub any_of: Option<serde_json::Value>,
+    pub any_of: Option<OneOrMany<serde_json::Value>>,
 }
 
 /// Properties for the Read activity
@@ -17,24 +17,25 @@
  * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Namespace for Unit Structs that serialize to strings
+//! Namespace for the structs that serialize to the `type` of each actor, like those of
+//! `object::kind`
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Application)]
-pub struct ApplicationType;
+pub struct ApplicationType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Group)]
-pub struct GroupType;
+pub struct GroupType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Organization)]
-pub struct OrganizationType;
+pub struct OrganizationType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Person)]
-pub struct PersonType;
+pub struct PersonType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Service)]
-pub struct ServiceType;
+pub struct ServiceType(Vec<String>);
@@ -22,9 +22,20 @@
 use activitystreams_traits::{Actor, Object};
 
 use object::{properties::ObjectProperties, ObjectExt};
+use security::{properties::SecurityProperties, SecurityExt};
 
 pub mod kind;
+pub mod properties;
 use self::kind::*;
+use self::properties::*;
+
+/// The Actor Extension Trait
+///
+/// This trait provides generic access to an actor's properties
+pub trait ActorExt: Actor {
+    fn props(&self) -> &ActorProperties;
+    fn props_mut(&mut self) -> &mut ActorProperties;
+}
 
 /// Describes a software application.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -36,6 +47,14 @@ pub struct Application {
     /// Adds all valid object properties to this struct
     #[serde(flatten)]
     pub object_props: ObjectProperties,
+
+    /// Adds all valid actor properties to this struct
+    #[serde(flatten)]
+    pub actor_props: ActorProperties,
+
+    /// Adds the security properties used to verify this actor's signatures
+    #[serde(flatten)]
+    pub security_props: SecurityProperties,
 }
 
 impl Object for Application {}
@@ -49,6 +68,24 @@ impl ObjectExt for Application {
     }
 }
 impl Actor for Application {}
+impl ActorExt for Application {
+    fn props(&self) -> &ActorProperties {
+        &self.actor_props
+    }
+
+    fn props_mut(&mut self) -> &mut ActorProperties {
+        &mut self.actor_props
+    }
+}
+impl SecurityExt for Application {
+    fn props(&self) -> &SecurityProperties {
+        &self.security_props
+    }
+
+    fn props_mut(&mut self) -> &mut SecurityProperties {
+        &mut self.security_props
+    }
+}
 
 /// Represents a formal or informal collective of Actors.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -60,6 +97,14 @@ pub struct Group {
     /// Adds all valid object properties to this struct
     #[serde(flatten)]
     pub object_props: ObjectProperties,
+
+    /// Adds all valid actor properties to this struct
+    #[serde(flatten)]
+    pub actor_props: ActorProperties,
+
+    /// Adds the security properties used to verify this actor's signatures
+    #[serde(flatten)]
+    pub security_props: SecurityProperties,
 }
 
 impl Object for Group {}
@@ -73,6 +118,24 @@ impl ObjectExt for Group {
     }
 }
 impl Actor for Group {}
+impl ActorExt for Group {
+    fn props(&self) -> &ActorProperties {
+        &self.actor_props
+    }
+
+    fn props_mut(&mut self) -> &mut ActorProperties {
+        &mut self.actor_props
+    }
+}
+impl SecurityExt for Group {
+    fn props(&self) -> &SecurityProperties {
+        &self.security_props
+    }
+
+    fn props_mut(&mut self) -> &mut SecurityProperties {
+        &mut self.security_props
+    }
+}
 
 /// Represents an organization.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -84,6 +147,14 @@ pub struct Organization {
     /// Adds all valid object properties to this struct
     #[serde(flatten)]
     pub object_props: ObjectProperties,
+
+    /// Adds all valid actor properties to this struct
+    #[serde(flatten)]
+    pub actor_props: ActorProperties,
+
+    /// Adds the security properties used to verify this actor's signatures
+    #[serde(flatten)]
+    pub security_props: SecurityProperties,
 }
 
 impl Object for Organization {}
@@ -97,6 +168,24 @@ impl ObjectExt for Organization {
     }
 }
 impl Actor for Organization {}
+impl ActorExt for Organization {
+    fn props(&self) -> &ActorProperties {
+        &self.actor_props
+    }
+
+    fn props_mut(&mut self) -> &mut ActorProperties {
+        &mut self.actor_props
+    }
+}
+impl SecurityExt for Organization {
+    fn props(&self) -> &SecurityProperties {
+        &self.security_props
+    }
+
+    fn props_mut(&mut self) -> &mut SecurityProperties {
+        &mut self.security_props
+    }
+}
 
 /// Represents an individual person.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -108,6 +197,14 @@ pub struct Person {
     /// Adds all valid object properties to this struct
     #[serde(flatten)]
     pub object_props: ObjectProperties,
+
+    /// Adds all valid actor properties to this struct
+    #[serde(flatten)]
+    pub actor_props: ActorProperties,
+
+    /// Adds the security properties used to verify this actor's signatures
+    #[serde(flatten)]
+    pub security_props: SecurityProperties,
 }
 
 impl Object for Person {}
@@ -121,6 +218,24 @@ impl ObjectExt for Person {
     }
 }
 impl Actor for Person {}
+impl ActorExt for Person {
+    fn props(&self) -> &ActorProperties {
+        &self.actor_props
+    }
+
+    fn props_mut(&mut self) -> &mut ActorProperties {
+        &mut self.actor_props
+    }
+}
+impl SecurityExt for Person {
+    fn props(&self) -> &SecurityProperties {
+        &self.security_props
+    }
+
+    fn props_mut(&mut self) -> &mut SecurityProperties {
+        &mut self.security_props
+    }
+}
 
 /// Represents a service of any kind.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -132,6 +247,14 @@ pub struct Service {
     /// Adds all valid object properties to this struct
     #[serde(flatten)]
     pub object_props: ObjectProperties,
+
+    /// Adds all valid actor properties to this struct
+    #[serde(flatten)]
+    pub actor_props: ActorProperties,
+
+    /// Adds the security properties used to verify this actor's signatures
+    #[serde(flatten)]
+    pub security_props: SecurityProperties,
 }
 
 impl Object for Service {}
@@ -145,3 +268,55 @@ impl ObjectExt for Service {
     }
 }
 impl Actor for Service {}
+impl ActorExt for Service {
+    fn props(&self) -> &ActorProperties {
+        &self.actor_props
+    }
+
+    fn props_mut(&mut self) -> &mut ActorProperties {
+        &mut self.actor_props
+    }
+}
+impl SecurityExt for Service {
+    fn props(&self) -> &SecurityProperties {
+        &self.security_props
+    }
+
+    fn props_mut(&mut self) -> &mut SecurityProperties {
+        &mut self.security_props
+    }
+}
+
+vocabulary! {
+    Application: "Application" => [ObjectProperties, ActorProperties, SecurityProperties],
+    Group: "Group" => [ObjectProperties, ActorProperties, SecurityProperties],
+    Organization: "Organization" => [ObjectProperties, ActorProperties, SecurityProperties],
+    Person: "Person" => [ObjectProperties, ActorProperties, SecurityProperties],
+    Service: "Service" => [ObjectProperties, ActorProperties, SecurityProperties],
+}
+
+any_type! {
+    /// Any of the Actor types defined in this crate
+    ///
+    /// When deserializing, the `type` field is used to decide which Actor type to produce. Actors
+    /// with a `type` that is not recognised are kept as `AnyActor::Unknown`.
+    pub enum AnyActor {
+        "Application" => Application,
+        "Group" => Group,
+        "Organization" => Organization,
+        "Person" => Person,
+        "Service" => Service,
+    }
+
+    /// Borrow the object properties of the contained Actor
+    ObjectExt::props -> ObjectProperties as object_props, object_props_mut;
+
+    /// Borrow the actor properties of the contained Actor
+    ActorExt::props -> ActorProperties as actor_props, actor_props_mut;
+
+    /// Borrow the security properties of the contained Actor
+    SecurityExt::props -> SecurityProperties as security_props, security_props_mut;
+}
+
+impl Object for AnyActor {}
+impl Actor for AnyActor {}
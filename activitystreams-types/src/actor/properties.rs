@@ -0,0 +1,219 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Namespace for properties of standard Actor types
+//!
+//! To use these properties in your own types, you can flatten them into your struct with serde:
+//!
+//! ```rust
+//! extern crate activitystreams_traits;
+//! extern crate activitystreams_types;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use activitystreams_traits::{Actor, Object};
+//! use activitystreams_types::{
+//!   actor::properties::ActorProperties,
+//!   object::properties::ObjectProperties,
+//! };
+//!
+//! #[derive(Clone, Debug, Serialize, Deserialize)]
+//! #[serde(rename_all = "camelCase")]
+//! pub struct MyActor {
+//!     #[serde(rename = "type")]
+//!     pub kind: String,
+//!
+//!     /// Define a require property for the MyActor type
+//!     pub my_property: String,
+//!
+//!     #[serde(flatten)]
+//!     pub object_properties: ObjectProperties,
+//!
+//!     #[serde(flatten)]
+//!     pub actor_properties: ActorProperties,
+//! }
+//!
+//! impl Object for MyActor {}
+//! impl Actor for MyActor {}
+//! #
+//! # fn main() {}
+//! ```
+
+use activitystreams_traits::{
+    properties::{Either, OneOrMany},
+    Collection,
+};
+use serde_json;
+
+/// Define all the properties of the Actor type as described by the ActivityPub specification.
+///
+/// Actor objects MUST have, in addition to the properties mandated by the Object base type, the
+/// `inbox` and `outbox` properties. The remaining properties are optional, but are commonly
+/// required by other ActivityPub servers in order to federate with an actor.
+///
+/// All properties are optional, so that plain Activity Streams actors can still be
+/// deserialized.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorProperties {
+    /// A reference to an [[ActivityStreams](https://www.w3.org/ns/activitystreams)]
+    /// OrderedCollection comprised of all the messages received by the actor.
+    ///
+    /// - Range: `xsd:anyUri` | `OrderedCollection`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection), functional)]
+    pub inbox: Option<Either<String, serde_json::Value>>,
+
+    /// An [[ActivityStreams](https://www.w3.org/ns/activitystreams)] OrderedCollection comprised
+    /// of all the messages produced by the actor.
+    ///
+    /// - Range: `xsd:anyUri` | `OrderedCollection`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection), functional)]
+    pub outbox: Option<Either<String, serde_json::Value>>,
+
+    /// A link to an [[ActivityStreams](https://www.w3.org/ns/activitystreams)] collection of the
+    /// actors that this actor is following.
+    ///
+    /// - Range: `xsd:anyUri` | `Collection`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection), functional)]
+    pub following: Option<Either<String, serde_json::Value>>,
+
+    /// A link to an [[ActivityStreams](https://www.w3.org/ns/activitystreams)] collection of the
+    /// actors that follow this actor.
+    ///
+    /// - Range: `xsd:anyUri` | `Collection`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection), functional)]
+    pub followers: Option<Either<String, serde_json::Value>>,
+
+    /// A link to an [[ActivityStreams](https://www.w3.org/ns/activitystreams)] collection of
+    /// objects this actor has liked.
+    ///
+    /// - Range: `xsd:anyUri` | `Collection`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection), functional)]
+    pub liked: Option<Either<String, serde_json::Value>>,
+
+    /// A list of supplementary Collections which may be of interest.
+    ///
+    /// - Range: `xsd:anyUri` | `Collection`
+    /// - Functional: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), ab(Collection))]
+    pub streams: Option<OneOrMany<Either<String, serde_json::Value>>>,
+
+    /// A short username which may be used to refer to the actor, with no uniqueness guarantees.
+    ///
+    /// - Range: `xsd:string`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub preferred_username: Option<Either<String, serde_json::Value>>,
+
+    /// A json object which maps additional (typically server/domain-wide) endpoints which may be
+    /// useful either for this actor or someone referencing this actor.
+    ///
+    /// This mapping may be nested inside the actor document as the value or may be a link to a
+    /// JSON-LD document with these properties.
+    ///
+    /// - Range: `xsd:anyUri` | `Endpoints`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String, Endpoints), functional)]
+    pub endpoints: Option<Either<String, serde_json::Value>>,
+}
+
+/// Define all the properties of the Endpoints mapping as described by the ActivityPub
+/// specification.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoints {
+    /// Endpoint URI so this actor's clients may access remote ActivityStreams objects which
+    /// require authentication to access.
+    ///
+    /// To use this endpoint, the client posts an `x-www-form-urlencoded` id parameter with the
+    /// value being the id of the requested ActivityStreams object.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub proxy_url: Option<Either<String, serde_json::Value>>,
+
+    /// If OAuth 2.0 bearer tokens are being used for authenticating client to server
+    /// interactions, this endpoint specifies a URI at which a browser-authenticated user may
+    /// obtain a new authorization grant.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub oauth_authorization_endpoint: Option<Either<String, serde_json::Value>>,
+
+    /// If OAuth 2.0 bearer tokens are being used for authenticating client to server
+    /// interactions, this endpoint specifies a URI at which a client may acquire an access
+    /// token.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub oauth_token_endpoint: Option<Either<String, serde_json::Value>>,
+
+    /// If Linked Data Signatures and HTTP Signatures are being used for authentication and
+    /// authorization, this endpoint specifies a URI at which browser-authenticated users may
+    /// authorize a client's public key for client to server interactions.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub provide_client_key: Option<Either<String, serde_json::Value>>,
+
+    /// If Linked Data Signatures and HTTP Signatures are being used for authentication and
+    /// authorization, this endpoint specifies a URI at which a client key may be signed by the
+    /// actor's key for a time window to act on behalf of the actor in interacting with foreign
+    /// servers.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub sign_client_key: Option<Either<String, serde_json::Value>>,
+
+    /// An optional endpoint used for wide delivery of publicly addressed activities and
+    /// activities sent to followers.
+    ///
+    /// `shared_inbox` endpoints SHOULD also be publicly readable `OrderedCollection` objects
+    /// containing objects addressed to the Public special collection.
+    ///
+    /// - Range: `xsd:anyUri`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(String), functional)]
+    pub shared_inbox: Option<Either<String, serde_json::Value>>,
+}
@@ -0,0 +1,427 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Helpers for addressing objects and activities
+//!
+//! Objects are addressed with the `to`, `cc`, `bto`, `bcc` and `audience` properties. Addressing
+//! an object to the special `Public` collection makes it visible to everyone, and the choice of
+//! property decides whether it is also listed in public timelines.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     addressing::{Public, Visibility},
+//!     object::Note,
+//! };
+//!
+//! # fn main() {
+//! let note: Note = serde_json::from_str(r#"{
+//!     "type": "Note",
+//!     "to": "https://example.com/users/alice/followers",
+//!     "cc": "as:Public"
+//! }"#).unwrap();
+//!
+//! // Aliases of the Public collection are recognised, but kept as they were received
+//! assert_eq!(note.object_props.cc_link::<Public>().unwrap(), Public);
+//! assert_eq!(serde_json::to_value(&note).unwrap()["cc"], "as:Public");
+//!
+//! let followers = "https://example.com/users/alice/followers";
+//! assert_eq!(Visibility::of(&note, followers), Visibility::Unlisted);
+//!
+//! let mut reply = Note::default();
+//! reply.object_props.set_to_link(Public).unwrap();
+//! assert_eq!(
+//!     serde_json::to_value(&reply).unwrap()["to"],
+//!     "https://www.w3.org/ns/activitystreams#Public"
+//! );
+//! # }
+//! ```
+
+use activitystreams_traits::{
+    properties::{from_value, to_value, OneOrMany},
+    Link, Object, Result,
+};
+use serde::{
+    de::{Deserialize, DeserializeOwned, Deserializer, Error as DeError},
+    ser::{Serialize, Serializer},
+};
+use serde_json::{Map, Value};
+
+use object::{properties::ObjectProperties, ObjectExt};
+use primitives::XsdAnyUri;
+
+/// The IRI of the special `Public` collection, which includes everyone
+pub const PUBLIC: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// The properties an object is addressed with
+const ADDRESSING: &[&str] = &["to", "bto", "cc", "bcc", "audience"];
+
+/// The addressing properties that must not be delivered
+const HIDDEN_ADDRESSING: &[&str] = &["bto", "bcc"];
+
+/// The addressing properties that are copied from an object onto the `Create` wrapping it
+const COPIED_ADDRESSING: &[&str] = &["to", "cc", "audience"];
+
+/// The compact forms of `PUBLIC` that may be used in place of its full IRI
+const PUBLIC_ALIASES: &[&str] = &["as:Public", "Public"];
+
+/// The special `Public` collection, for use in addressing properties
+///
+/// `Public` can be set on any addressing property as a Link or an Object, and is serialized as
+/// the full IRI. Its compact forms, `as:Public` and `Public`, are accepted when it is
+/// deserialized.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Public;
+
+impl Public {
+    /// The IRI of the `Public` collection
+    pub fn id(&self) -> XsdAnyUri {
+        PUBLIC.parse().expect("PUBLIC is a valid IRI")
+    }
+}
+
+impl Object for Public {}
+impl Link for Public {}
+
+impl From<Public> for XsdAnyUri {
+    fn from(public: Public) -> Self {
+        public.id()
+    }
+}
+
+impl Serialize for Public {
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(PUBLIC)
+    }
+}
+
+impl<'de> Deserialize<'de> for Public {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let address = String::deserialize(deserializer)?;
+
+        if is_public(&address) {
+            Ok(Public)
+        } else {
+            Err(D::Error::custom(format!(
+                "Expected the Public collection, found {:?}",
+                address
+            )))
+        }
+    }
+}
+
+/// Check whether an address refers to the `Public` collection, either by its full IRI or by one
+/// of its compact forms
+pub fn is_public(address: &str) -> bool {
+    address == PUBLIC || PUBLIC_ALIASES.contains(&address)
+}
+
+/// How widely an object is shared, as decided by its addressing
+///
+/// These are the levels of visibility offered by most ActivityPub servers.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Visibility {
+    /// Addressed to `Public` in `to` or `audience`, and shown in public timelines
+    Public,
+
+    /// Addressed to `Public` only in `cc`, `bto` or `bcc`, so it is visible to everyone, but not
+    /// listed in public timelines
+    Unlisted,
+
+    /// Not addressed to `Public`, but addressed to the followers collection of its author
+    FollowersOnly,
+
+    /// Addressed only to specific actors
+    Direct,
+}
+
+impl Visibility {
+    /// Classify the addressing of an object, given the IRI of its author's followers collection
+    ///
+    /// Addresses are compared with the followers collection as parsed IRIs, so differences in
+    /// case or encoding do not matter.
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::{addressing::Visibility, object::Note};
+    ///
+    /// # fn main() {
+    /// let note: Note = serde_json::from_str(r#"{
+    ///     "type": "Note",
+    ///     "to": "HTTPS://Example.com/users/alice/followers"
+    /// }"#).unwrap();
+    ///
+    /// let followers = "https://example.com/users/alice/followers";
+    /// assert_eq!(Visibility::of(&note, followers), Visibility::FollowersOnly);
+    /// assert_eq!(
+    ///     Visibility::of(&note, "https://example.com/users/bob/followers"),
+    ///     Visibility::Direct
+    /// );
+    /// # }
+    /// ```
+    pub fn of<O>(object: &O, followers: &str) -> Self
+    where
+        O: ObjectExt,
+    {
+        Visibility::of_props(object.props(), followers)
+    }
+
+    /// Classify the addressing in a set of object properties, given the IRI of the author's
+    /// followers collection
+    pub fn of_props(props: &ObjectProperties, followers: &str) -> Self {
+        let primary = [&props.to, &props.audience];
+        let secondary = [&props.cc, &props.bto, &props.bcc];
+
+        let followers = followers.parse::<XsdAnyUri>().ok();
+        let is_followers =
+            |address: &str| followers.is_some() && address.parse::<XsdAnyUri>().ok() == followers;
+
+        if any_address(&primary, is_public) {
+            Visibility::Public
+        } else if any_address(&secondary, is_public) {
+            Visibility::Unlisted
+        } else if any_address(&primary, is_followers) || any_address(&secondary, is_followers) {
+            Visibility::FollowersOnly
+        } else {
+            Visibility::Direct
+        }
+    }
+
+    /// Check whether objects with this visibility can be seen by everyone
+    pub fn is_public(&self) -> bool {
+        match *self {
+            Visibility::Public | Visibility::Unlisted => true,
+            Visibility::FollowersOnly | Visibility::Direct => false,
+        }
+    }
+}
+
+/// An activity that is ready to be delivered, along with who it should be delivered to
+#[derive(Clone, Debug)]
+pub struct Delivery<T> {
+    /// The activity, with `bto` and `bcc` removed from it and from its embedded objects
+    pub activity: T,
+
+    /// Every actor or collection the activity is addressed to
+    ///
+    /// The list has no duplicates, and does not include the actor of the activity or the
+    /// `Public` collection, which cannot be delivered to.
+    pub recipients: Vec<XsdAnyUri>,
+}
+
+/// The Delivery Extension Trait
+///
+/// This trait prepares activities, or any other objects, to be sent to other servers.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{activity::Create, addressing::DeliveryExt};
+///
+/// # fn main() {
+/// let create: Create = serde_json::from_str(r#"{
+///     "type": "Create",
+///     "actor": "https://example.com/users/alice",
+///     "to": "https://example.com/users/alice/followers",
+///     "object": {
+///         "type": "Note",
+///         "content": "Hi Bob",
+///         "to": "https://example.com/users/alice/followers",
+///         "cc": "https://example.org/users/carol",
+///         "bcc": ["https://example.org/users/bob", "https://EXAMPLE.com/users/alice"]
+///     }
+/// }"#).unwrap();
+///
+/// let delivery = create.prepare_delivery().unwrap();
+///
+/// let recipients: Vec<&str> = delivery.recipients.iter().map(|r| r.as_str()).collect();
+/// assert_eq!(recipients, vec![
+///     "https://example.com/users/alice/followers",
+///     "https://example.org/users/carol",
+///     "https://example.org/users/bob",
+/// ]);
+///
+/// let create = serde_json::to_value(&delivery.activity).unwrap();
+/// assert_eq!(create["cc"], "https://example.org/users/carol");
+/// assert!(create["object"].get("bcc").is_none());
+/// # }
+/// ```
+pub trait DeliveryExt: ObjectExt + Serialize + DeserializeOwned {
+    /// Prepare this value to be delivered
+    ///
+    /// The recipients are collected from the addressing of this value and of any object embedded
+    /// in its `object` property. If this is a `Create`, the addressing of the objects it creates
+    /// is copied onto it, as servers may only look at the addressing of the activity. Finally,
+    /// `bto` and `bcc` are removed, since they must never be sent.
+    fn prepare_delivery(&self) -> Result<Delivery<Self>> {
+        let mut value = to_value(self)?;
+
+        let recipients = {
+            let actors: Vec<XsdAnyUri> = value
+                .get("actor")
+                .map(values)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(address_id)
+                .filter_map(|id| id.parse().ok())
+                .collect();
+
+            let objects = value.get("object").map(values).unwrap_or_default();
+
+            let mut recipients: Vec<XsdAnyUri> = Vec::new();
+            for object in Some(&value).into_iter().chain(objects) {
+                for property in ADDRESSING {
+                    let addresses = object.get(property).map(values).unwrap_or_default();
+
+                    for id in addresses.into_iter().filter_map(address_id) {
+                        let recipient = match id.parse() {
+                            Ok(recipient) => recipient,
+                            Err(_) => continue,
+                        };
+
+                        if !is_public(id)
+                            && !actors.contains(&recipient)
+                            && !recipients.contains(&recipient)
+                        {
+                            recipients.push(recipient);
+                        }
+                    }
+                }
+            }
+
+            recipients
+        };
+
+        if let Value::Object(ref mut activity) = value {
+            if activity.get("type").and_then(Value::as_str) == Some("Create") {
+                copy_object_addressing(activity);
+            }
+
+            strip_hidden(activity);
+            if let Some(object) = activity.get_mut("object") {
+                match *object {
+                    Value::Object(ref mut object) => strip_hidden(object),
+                    Value::Array(ref mut objects) => {
+                        for object in objects.iter_mut() {
+                            if let Value::Object(ref mut object) = *object {
+                                strip_hidden(object);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(Delivery {
+            activity: from_value(&value)?,
+            recipients,
+        })
+    }
+}
+
+impl<T> DeliveryExt for T where T: ObjectExt + Serialize + DeserializeOwned {}
+
+/// Borrow a property that may hold a single value or an array as a list of values
+pub(crate) fn values(value: &Value) -> Vec<&Value> {
+    match *value {
+        Value::Array(ref values) => values.iter().collect(),
+        Value::Null => Vec::new(),
+        ref value => vec![value],
+    }
+}
+
+/// Add the addressing of a `Create`'s embedded objects to the `Create` itself
+fn copy_object_addressing(activity: &mut Map<String, Value>) {
+    let objects: Vec<Value> = activity
+        .get("object")
+        .map(values)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|object| object.is_object())
+        .cloned()
+        .collect();
+
+    for property in COPIED_ADDRESSING {
+        let mut addresses: Vec<Value> = activity
+            .get(*property)
+            .map(values)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        let original_len = addresses.len();
+        for object in &objects {
+            for address in object.get(property).map(values).unwrap_or_default() {
+                let id = address_id(address);
+                if id.is_some() && !addresses.iter().any(|a| address_id(a) == id) {
+                    addresses.push(address.clone());
+                }
+            }
+        }
+
+        if addresses.len() != original_len {
+            let addresses = if addresses.len() == 1 {
+                addresses.remove(0)
+            } else {
+                Value::Array(addresses)
+            };
+            activity.insert((*property).to_owned(), addresses);
+        }
+    }
+}
+
+fn strip_hidden(object: &mut Map<String, Value>) {
+    for property in HIDDEN_ADDRESSING {
+        object.remove(*property);
+    }
+}
+
+/// Read the id of an address, which may be given as a string or as an embedded object
+pub(crate) fn address_id(address: &Value) -> Option<&str> {
+    match *address {
+        Value::String(ref id) => Some(id),
+        Value::Object(ref object) => object.get("id").and_then(Value::as_str),
+        _ => None,
+    }
+}
+
+fn any_address<F>(properties: &[&Option<OneOrMany<Value>>], f: F) -> bool
+where
+    F: FnMut(&str) -> bool,
+{
+    properties
+        .iter()
+        .filter_map(|property| property.as_ref())
+        .flat_map(|addresses| addresses.iter())
+        .filter_map(address_id)
+        .any(f)
+}
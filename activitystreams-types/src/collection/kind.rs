@@ -17,20 +17,21 @@
  * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Namespace for Unit Structs that serialize to strings
+//! Namespace for the structs that serialize to the `type` of each collection, like those of
+//! `object::kind`
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Collection)]
-pub struct CollectionType;
+pub struct CollectionType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(CollectionPage)]
-pub struct CollectionPageType;
+pub struct CollectionPageType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(OrderedCollection)]
-pub struct OrderedCollectionType;
+pub struct OrderedCollectionType(Vec<String>);
 
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(OrderedCollectionPage)]
-pub struct OrderedCollectionPageType;
+pub struct OrderedCollectionPageType(Vec<String>);
@@ -24,6 +24,7 @@ use activitystreams_traits::{Collection, CollectionPage, Object};
 use object::{properties::ObjectProperties, ObjectExt};
 
 pub mod kind;
+pub mod paginate;
 pub mod properties;
 use self::kind::*;
 use self::properties::*;
@@ -96,6 +97,10 @@ pub struct OrderedCollection {
     /// Adds all valid collection properties to this struct
     #[serde(flatten)]
     pub collection_props: CollectionProperties,
+
+    /// Adds all valid ordered collection properties to this struct
+    #[serde(flatten)]
+    pub ordered_collection_props: OrderedCollectionProperties,
 }
 
 impl Object for OrderedCollection {}
@@ -185,6 +190,10 @@ pub struct OrderedCollectionPage {
     #[serde(flatten)]
     pub collection_props: CollectionProperties,
 
+    /// Adds all valid ordered collection properties to this struct
+    #[serde(flatten)]
+    pub ordered_collection_props: OrderedCollectionProperties,
+
     /// Adds all valid collection page properties to this struct
     #[serde(flatten)]
     pub collection_page_props: CollectionPageProperties,
@@ -224,3 +233,46 @@ impl CollectionPageExt for OrderedCollectionPage {
         &mut self.collection_page_props
     }
 }
+
+vocabulary! {
+    UnorderedCollection: "Collection" => [ObjectProperties, CollectionProperties],
+    OrderedCollection: "OrderedCollection" => [
+        ObjectProperties,
+        CollectionProperties,
+        OrderedCollectionProperties
+    ],
+    UnorderedCollectionPage: "CollectionPage" => [
+        ObjectProperties,
+        CollectionProperties,
+        CollectionPageProperties
+    ],
+    OrderedCollectionPage: "OrderedCollectionPage" => [
+        ObjectProperties,
+        CollectionProperties,
+        OrderedCollectionProperties,
+        CollectionPageProperties,
+        OrderedCollectionPageProperties
+    ],
+}
+
+any_type! {
+    /// Any of the Collection types defined in this crate
+    ///
+    /// When deserializing, the `type` field is used to decide which Collection type to produce.
+    /// Collections with a `type` that is not recognised are kept as `AnyCollection::Unknown`.
+    pub enum AnyCollection {
+        "Collection" => UnorderedCollection,
+        "OrderedCollection" => OrderedCollection,
+        "CollectionPage" => UnorderedCollectionPage,
+        "OrderedCollectionPage" => OrderedCollectionPage,
+    }
+
+    /// Borrow the object properties of the contained Collection
+    ObjectExt::props -> ObjectProperties as object_props, object_props_mut;
+
+    /// Borrow the collection properties of the contained Collection
+    CollectionExt::props -> CollectionProperties as collection_props, collection_props_mut;
+}
+
+impl Object for AnyCollection {}
+impl Collection for AnyCollection {}
@@ -0,0 +1,514 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Splitting collections into pages
+//!
+//! Large collections, such as an outbox or a followers collection, are served as an
+//! `OrderedCollection` that links to `OrderedCollectionPage`s holding the items. A `Paginator`
+//! builds the collection and its pages from a `PageSource`, keeping `first`, `last`, `next`,
+//! `prev`, `partOf`, `startIndex` and `totalItems` consistent.
+//!
+//! The ids of the pages come from a template. In the offset style, which is the default,
+//! `{page}` is replaced with the number of the page, counting from 1, and `{offset}` with the
+//! index of its first item. In the cursor style, `{cursor}` is replaced with a key of the first
+//! item of the page, so the ids of pages do not change when items are added in front of them.
+//! `Paginator::page_for_cursor` builds the page a cursor names.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::collection::paginate::Paginator;
+//!
+//! # fn main() {
+//! let activities: Vec<String> = (1..6)
+//!     .map(|n| format!("https://example.com/activities/{}", n))
+//!     .collect();
+//!
+//! let paginator = Paginator::new(
+//!     "https://example.com/outbox".parse().unwrap(),
+//!     "https://example.com/outbox?page={page}",
+//!     2,
+//!     &activities[..],
+//! );
+//!
+//! let outbox = serde_json::to_value(paginator.collection().unwrap()).unwrap();
+//! assert_eq!(outbox["totalItems"], 5);
+//! assert_eq!(outbox["first"], "https://example.com/outbox?page=1");
+//! assert_eq!(outbox["last"], "https://example.com/outbox?page=3");
+//!
+//! let page = serde_json::to_value(paginator.page(2).unwrap().unwrap()).unwrap();
+//! assert_eq!(page["id"], "https://example.com/outbox?page=2");
+//! assert_eq!(page["partOf"], "https://example.com/outbox");
+//! assert_eq!(page["startIndex"], 2);
+//! assert_eq!(page["prev"], "https://example.com/outbox?page=1");
+//! assert_eq!(page["next"], "https://example.com/outbox?page=3");
+//! assert_eq!(page["orderedItems"][0], "https://example.com/activities/3");
+//!
+//! assert_eq!(paginator.pages().count(), 3);
+//! assert!(paginator.page(4).unwrap().is_none());
+//! # }
+//! ```
+
+use activitystreams_traits::{
+    self,
+    properties::{Either, OneOrMany},
+};
+use serde::ser::Serialize;
+use serde_json::{self, Value};
+use std::{convert::Infallible, error::Error, fmt};
+use url::form_urlencoded;
+
+use collection::{OrderedCollection, OrderedCollectionPage};
+use primitives::XsdAnyUri;
+
+/// The items of a collection, which a `Paginator` splits into pages
+pub trait PageSource {
+    /// The type of the items
+    type Item: Serialize;
+
+    /// The error produced when the items can not be read
+    type Error;
+
+    /// The number of items
+    fn total(&self) -> Result<usize, Self::Error>;
+
+    /// Read at most `limit` items, starting with the item at `offset`
+    fn fetch(&self, offset: usize, limit: usize) -> Result<Vec<Self::Item>, Self::Error>;
+
+    /// Find the offset of the item whose key, as read by `cursor`, is `key`
+    ///
+    /// This reads every item. Sources that can look keys up, such as a database with an index on
+    /// them, should override it.
+    fn position(
+        &self,
+        key: &str,
+        cursor: &dyn Fn(&Self::Item) -> String,
+    ) -> Result<Option<usize>, Self::Error> {
+        let items = self.fetch(0, self.total()?)?;
+        Ok(items.iter().position(|item| cursor(item) == key))
+    }
+}
+
+impl<T> PageSource for &[T]
+where
+    T: Serialize + Clone,
+{
+    type Item = T;
+    type Error = Infallible;
+
+    fn total(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
+
+    fn fetch(&self, offset: usize, limit: usize) -> Result<Vec<Self::Item>, Self::Error> {
+        Ok(self.iter().skip(offset).take(limit).cloned().collect())
+    }
+}
+
+impl<T> PageSource for Vec<T>
+where
+    T: Serialize + Clone,
+{
+    type Item = T;
+    type Error = Infallible;
+
+    fn total(&self) -> Result<usize, Self::Error> {
+        Ok(self.len())
+    }
+
+    fn fetch(&self, offset: usize, limit: usize) -> Result<Vec<Self::Item>, Self::Error> {
+        self.as_slice().fetch(offset, limit)
+    }
+}
+
+/// A source that reads items on demand, such as from a database
+///
+/// ```rust
+/// extern crate activitystreams_types;
+///
+/// use activitystreams_types::collection::paginate::{FetchSource, PageSource};
+///
+/// # fn main() {
+/// let source = FetchSource::new(1000, |offset: usize, limit: usize| -> Result<Vec<usize>, ()> {
+///     Ok((offset..(offset + limit).min(1000)).collect())
+/// });
+///
+/// assert_eq!(source.fetch(998, 5), Ok(vec![998, 999]));
+/// # }
+/// ```
+pub struct FetchSource<F> {
+    total: usize,
+    fetch: F,
+}
+
+impl<F> FetchSource<F> {
+    /// Create a source with `total` items, read by calling `fetch` with an offset and a limit
+    pub fn new(total: usize, fetch: F) -> Self {
+        FetchSource { total, fetch }
+    }
+}
+
+impl<F, T, E> PageSource for FetchSource<F>
+where
+    F: Fn(usize, usize) -> Result<Vec<T>, E>,
+    T: Serialize,
+{
+    type Item = T;
+    type Error = E;
+
+    fn total(&self) -> Result<usize, Self::Error> {
+        Ok(self.total)
+    }
+
+    fn fetch(&self, offset: usize, limit: usize) -> Result<Vec<Self::Item>, Self::Error> {
+        (self.fetch)(offset, limit)
+    }
+}
+
+/// The error produced when a collection or page can not be built
+#[derive(Debug)]
+pub enum PaginateError<E> {
+    /// The template does not produce a valid IRI, or is missing the placeholder that tells its
+    /// pages apart
+    InvalidTemplate(String),
+
+    /// The page does not start at a multiple of the page size, so the `{page}` placeholder can
+    /// not name it
+    UnalignedOffset(usize),
+
+    /// An item could not be serialized
+    Serialize(activitystreams_traits::Error),
+
+    /// The source failed
+    Source(E),
+}
+
+impl<E> fmt::Display for PaginateError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PaginateError::InvalidTemplate(ref template) => {
+                write!(f, "Invalid page template {:?}", template)
+            }
+            PaginateError::UnalignedOffset(offset) => {
+                write!(f, "Offset {} does not start a page", offset)
+            }
+            PaginateError::Serialize(ref e) => fmt::Display::fmt(e, f),
+            PaginateError::Source(ref e) => write!(f, "Source failed, {}", e),
+        }
+    }
+}
+
+impl<E> Error for PaginateError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            PaginateError::Serialize(ref e) => Some(e),
+            PaginateError::Source(ref e) => Some(e),
+            PaginateError::InvalidTemplate(_) | PaginateError::UnalignedOffset(_) => None,
+        }
+    }
+}
+
+/// Read the cursor of an item
+type Cursor<T> = Box<dyn Fn(&T) -> String + Send + Sync>;
+
+/// Builds an `OrderedCollection` and its `OrderedCollectionPage`s from a source of items
+pub struct Paginator<S>
+where
+    S: PageSource,
+{
+    id: XsdAnyUri,
+    template: String,
+    page_size: usize,
+    source: S,
+    cursor: Option<Cursor<S::Item>>,
+}
+
+impl<S> Paginator<S>
+where
+    S: PageSource,
+{
+    /// Create a paginator for the collection `id`, naming its pages with `template`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is zero.
+    pub fn new<T>(id: XsdAnyUri, template: T, page_size: usize, source: S) -> Self
+    where
+        T: Into<String>,
+    {
+        assert!(page_size > 0, "Pages must hold at least one item");
+
+        Paginator {
+            id,
+            template: template.into(),
+            page_size,
+            source,
+            cursor: None,
+        }
+    }
+
+    /// Use the cursor style, replacing `{cursor}` in the template with the key `cursor` reads
+    /// from the first item of each page
+    ///
+    /// A page includes the item its cursor names, so the template should give the key inclusive
+    /// semantics, such as `?from={cursor}` rather than `?before={cursor}`.
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// #[macro_use]
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::collection::paginate::Paginator;
+    /// use serde_json::Value;
+    ///
+    /// # fn main() {
+    /// let followers = vec![
+    ///     json!({ "id": "https://example.com/users/alice", "followed": "2018-06-03T10:00:00Z" }),
+    ///     json!({ "id": "https://example.org/users/bob", "followed": "2018-06-02T10:00:00Z" }),
+    ///     json!({ "id": "https://example.net/users/carol", "followed": "2018-06-01T10:00:00Z" }),
+    /// ];
+    ///
+    /// let paginator = Paginator::new(
+    ///     "https://example.com/followers".parse().unwrap(),
+    ///     "https://example.com/followers?from={cursor}",
+    ///     2,
+    ///     followers,
+    /// )
+    /// .cursor(|follower: &Value| follower["followed"].as_str().unwrap().to_owned());
+    ///
+    /// let collection = serde_json::to_value(paginator.collection().unwrap()).unwrap();
+    /// assert_eq!(
+    ///     collection["last"],
+    ///     "https://example.com/followers?from=2018-06-01T10%3A00%3A00Z"
+    /// );
+    ///
+    /// let first = serde_json::to_value(paginator.page_at(0).unwrap().unwrap()).unwrap();
+    /// assert_eq!(first["next"], collection["last"]);
+    /// assert!(first.get("prev").is_none());
+    ///
+    /// // The server reads the cursor back from the id of the last page
+    /// let last = paginator.page_for_cursor("2018-06-01T10:00:00Z").unwrap().unwrap();
+    /// let last = serde_json::to_value(last).unwrap();
+    /// assert_eq!(last["id"], collection["last"]);
+    /// assert_eq!(last["orderedItems"][0]["id"], "https://example.net/users/carol");
+    /// # }
+    /// ```
+    pub fn cursor<F>(mut self, cursor: F) -> Self
+    where
+        F: Fn(&S::Item) -> String + Send + Sync + 'static,
+    {
+        self.cursor = Some(Box::new(cursor));
+        self
+    }
+
+    /// The number of pages, which is zero for an empty collection
+    pub fn page_count(&self) -> Result<usize, PaginateError<S::Error>> {
+        let total = self.source.total().map_err(PaginateError::Source)?;
+        Ok(total.div_ceil(self.page_size))
+    }
+
+    /// Build the collection, which links to its first and last pages
+    pub fn collection(&self) -> Result<OrderedCollection, PaginateError<S::Error>> {
+        let total = self.source.total().map_err(PaginateError::Source)?;
+
+        let mut collection = OrderedCollection::default();
+        collection.object_props.id = Some(Either::Left(self.id.clone()));
+        collection.collection_props.total_items = Some(Either::Left(total as u64));
+
+        if total > 0 {
+            let last = (total - 1) / self.page_size * self.page_size;
+            collection.collection_props.first = Some(self.link(0)?);
+            collection.collection_props.last = Some(self.link(last)?);
+        }
+
+        Ok(collection)
+    }
+
+    /// Build a page by its number, counting from 1, or `None` if there is no such page
+    pub fn page(
+        &self,
+        number: usize,
+    ) -> Result<Option<OrderedCollectionPage>, PaginateError<S::Error>> {
+        match number.checked_sub(1) {
+            Some(index) => self.page_at(index * self.page_size),
+            None => Ok(None),
+        }
+    }
+
+    /// Build the page that starts with the item at `offset`, or `None` if there is no such item
+    ///
+    /// In the offset style, a template with `{page}` can only name pages starting at a multiple of
+    /// the page size, so other offsets are rejected with `PaginateError::UnalignedOffset`.
+    pub fn page_at(
+        &self,
+        offset: usize,
+    ) -> Result<Option<OrderedCollectionPage>, PaginateError<S::Error>> {
+        if self.cursor.is_none()
+            && self.template.contains("{page}")
+            && !offset.is_multiple_of(self.page_size)
+        {
+            return Err(PaginateError::UnalignedOffset(offset));
+        }
+
+        let total = self.source.total().map_err(PaginateError::Source)?;
+        if offset >= total {
+            return Ok(None);
+        }
+
+        let items = self
+            .source
+            .fetch(offset, self.page_size)
+            .map_err(PaginateError::Source)?;
+
+        let mut page = OrderedCollectionPage::default();
+        page.object_props.id = Some(Either::Left(self.id_from(offset, items.first())?));
+        page.ordered_collection_props.ordered_items = Some(OneOrMany::Many(
+            items
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<_, _>>()
+                .map_err(|e| {
+                    PaginateError::Serialize(activitystreams_traits::Error::serialize(e))
+                })?,
+        ));
+        page.collection_page_props.part_of = Some(Value::String(self.id.to_string()));
+        page.ordered_collection_page_props.start_index = Some(Either::Left(offset as u64));
+
+        if offset > 0 {
+            let prev = offset.saturating_sub(self.page_size);
+            page.collection_page_props.prev = Some(self.link(prev)?);
+        }
+        if offset + self.page_size < total {
+            page.collection_page_props.next = Some(self.link(offset + self.page_size)?);
+        }
+
+        Ok(Some(page))
+    }
+
+    /// Build the page that starts with the item whose key is `key`, or `None` if there is no such
+    /// item
+    ///
+    /// `key` is the decoded cursor read from the id of a page. Without the cursor style, there are
+    /// no cursors and this is always `None`.
+    pub fn page_for_cursor(
+        &self,
+        key: &str,
+    ) -> Result<Option<OrderedCollectionPage>, PaginateError<S::Error>> {
+        let cursor = match self.cursor {
+            Some(ref cursor) => cursor,
+            None => return Ok(None),
+        };
+
+        match self
+            .source
+            .position(key, &**cursor)
+            .map_err(PaginateError::Source)?
+        {
+            Some(offset) => self.page_at(offset),
+            None => Ok(None),
+        }
+    }
+
+    /// Build every page, in order
+    pub fn pages(&self) -> Pages<'_, S> {
+        Pages {
+            paginator: self,
+            offset: 0,
+        }
+    }
+
+    /// Build the link to the page starting at `offset`
+    fn link(&self, offset: usize) -> Result<Value, PaginateError<S::Error>> {
+        let first = match self.cursor {
+            Some(_) => self
+                .source
+                .fetch(offset, 1)
+                .map_err(PaginateError::Source)?
+                .into_iter()
+                .next(),
+            None => None,
+        };
+
+        Ok(Value::String(
+            self.id_from(offset, first.as_ref())?.to_string(),
+        ))
+    }
+
+    /// Name the page starting at `offset`, whose first item is `first`
+    fn id_from(
+        &self,
+        offset: usize,
+        first: Option<&S::Item>,
+    ) -> Result<XsdAnyUri, PaginateError<S::Error>> {
+        let invalid = || PaginateError::InvalidTemplate(self.template.clone());
+
+        let id = match self.cursor {
+            Some(ref cursor) => {
+                if !self.template.contains("{cursor}") {
+                    return Err(invalid());
+                }
+
+                let key = first.map(cursor).unwrap_or_default();
+                let key: String = form_urlencoded::byte_serialize(key.as_bytes()).collect();
+                self.template.replace("{cursor}", &key)
+            }
+            None => {
+                if !self.template.contains("{page}") && !self.template.contains("{offset}") {
+                    return Err(invalid());
+                }
+
+                self.template
+                    .replace("{page}", &(offset / self.page_size + 1).to_string())
+                    .replace("{offset}", &offset.to_string())
+            }
+        };
+
+        id.parse().map_err(|_| invalid())
+    }
+}
+
+/// An iterator over the pages of a collection, built by a `Paginator`
+pub struct Pages<'a, S>
+where
+    S: PageSource + 'a,
+{
+    paginator: &'a Paginator<S>,
+    offset: usize,
+}
+
+impl<'a, S> Iterator for Pages<'a, S>
+where
+    S: PageSource + 'a,
+{
+    type Item = Result<OrderedCollectionPage, PaginateError<S::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let page = self.paginator.page_at(self.offset).transpose()?;
+        self.offset += self.paginator.page_size;
+
+        Some(page)
+    }
+}
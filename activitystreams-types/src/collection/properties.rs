@@ -56,7 +56,10 @@
 //! # fn main() {}
 //! ```
 
-use activitystreams_traits::{Collection, CollectionPage, Link, Object};
+use activitystreams_traits::{
+    properties::{Either, OneOrMany},
+    Collection, CollectionPage, Link, Object,
+};
 use serde_json;
 
 /// `Collection` objects are a specialization of the base `Object` that serve as a container for
@@ -71,10 +74,14 @@ use serde_json;
 pub struct CollectionProperties {
     /// Identifies the items contained in a collection. The items might be ordered or unordered.
     ///
+    /// Ordered collections usually hold their items in `ordered_items` instead, so this is left
+    /// out of the JSON when it is empty or null.
+    ///
     /// - Range: `Object` | `Link` | Ordered List of [ `Object` | `Link` ]
     /// - Functional: false
+    #[serde(default, skip_serializing_if = "is_unset")]
     #[activitystreams(ab(Object, Link))]
-    pub items: serde_json::Value,
+    pub items: OneOrMany<serde_json::Value>,
 
     /// A non-negative integer specifying the total number of objects contained by the logical view
     /// of the collection.
@@ -86,7 +93,7 @@ pub struct CollectionProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(u64), functional)]
-    pub total_items: Option<serde_json::Value>,
+    pub total_items: Option<Either<u64, serde_json::Value>>,
 
     /// In a paged `Collection`, indicates the page that contains the most recently updated member
     /// items.
@@ -114,6 +121,23 @@ pub struct CollectionProperties {
     pub last: Option<serde_json::Value>,
 }
 
+/// The `OrderedCollection` type extends from the base `Collection` type, and holds its items in
+/// order.
+///
+/// `OrderedCollectionPage` inherits these properties, so the items of a page of an ordered
+/// collection are held in order as well.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderedCollectionProperties {
+    /// Identifies the items contained in an ordered collection, in order.
+    ///
+    /// - Range: Ordered List of [ `Object` | `Link` ]
+    /// - Functional: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(ab(Object, Link))]
+    pub ordered_items: Option<OneOrMany<serde_json::Value>>,
+}
+
 /// The `CollectionPage` type extends from the base `Collection` type and inherits all of it's
 /// properties.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
@@ -121,8 +145,8 @@ pub struct CollectionProperties {
 pub struct CollectionPageProperties {
     /// Identifies the `Collection` to which a `CollectionPage` objects items belong.
     ///
-    /// Range: `Collection` | `Link`
-    /// Functional: true
+    /// - Range: `Collection` | `Link`
+    /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Link, Collection), functional)]
     pub part_of: Option<serde_json::Value>,
@@ -155,5 +179,10 @@ pub struct OrderedCollectionPageProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(u64), functional)]
-    pub start_index: Option<serde_json::Value>,
+    pub start_index: Option<Either<u64, serde_json::Value>>,
+}
+
+/// Check whether `items` holds nothing, as it does when it is missing from the JSON
+fn is_unset(items: &OneOrMany<serde_json::Value>) -> bool {
+    items.is_empty() || items.as_one().is_some_and(serde_json::Value::is_null)
 }
@@ -19,11 +19,28 @@
 
 //! A collection of simple types for extending the ActivityStreams Types base types
 
-use serde::{de::DeserializeOwned, ser::Serialize};
+use serde::{
+    de::{self, Deserialize, DeserializeOwned, Deserializer},
+    ser::{self, Serialize, Serializer},
+};
+use serde_json::{self, Map, Value};
+use std::any::type_name;
 
 use activitystreams_traits::{
-    Activity, Actor, Collection, CollectionPage, IntransitiveActivity, Link, Object,
+    properties::{PropertyInfo, Vocabulary},
+    Activity, Actor, Collection, CollectionPage, Error, ErrorKind, IntransitiveActivity, Link,
+    Object, Result,
+};
+
+use activity::{properties::ActivityProperties, ActivityExt};
+use actor::{properties::ActorProperties, ActorExt};
+use collection::{
+    properties::{CollectionPageProperties, CollectionProperties},
+    CollectionExt, CollectionPageExt,
 };
+use link::{properties::LinkProperties, LinkExt};
+use object::{properties::ObjectProperties, ObjectExt};
+use security::{properties::SecurityProperties, SecurityExt};
 
 /// A custom type extending Link
 ///
@@ -77,6 +94,9 @@ where
 /// This type can also be used to extend any type deriving from Object, such as Actor, Activity, or
 /// Collection.
 ///
+/// To wrap it in `WithUnknown` or read it with `strict`, the extending properties implement
+/// `Vocabulary`, usually by returning the `PROPERTIES` table of their `Properties` derive.
+///
 /// ## Example
 /// ```rust
 /// use activitystreams_types::{
@@ -128,19 +148,248 @@ impl<C, O> Collection for CustomObject<C, O>
 where
     C: DeserializeOwned + Serialize,
     O: Collection,
-{}
+{
+}
 impl<C, O> CollectionPage for CustomObject<C, O>
 where
     C: DeserializeOwned + Serialize,
     O: CollectionPage,
-{}
+{
+}
 impl<C, O> Activity for CustomObject<C, O>
 where
     C: DeserializeOwned + Serialize,
     O: Activity,
-{}
+{
+}
 impl<C, O> IntransitiveActivity for CustomObject<C, O>
 where
     C: DeserializeOwned + Serialize,
     O: IntransitiveActivity,
-{}
+{
+}
+
+/// A wrapper that keeps the properties its inner type does not know about
+///
+/// Deserializing a type such as `Note` drops every key that is not one of its properties, such as
+/// the `sensitive` flag used by Mastodon or the `_misskey_content` used by Misskey. Wrapping the
+/// type in `WithUnknown` keeps those keys in `unknown`, and writes them back out when the value is
+/// serialized again, so that documents can be forwarded or cached without losing anything.
+///
+/// A key is unknown if it is not in the `PROPERTIES` tables the inner type reports through
+/// `Vocabulary`, so this works for any Object or Link, including a `CustomObject` whose extending
+/// properties implement `Vocabulary`.
+///
+/// ## Example
+/// ```rust
+/// extern crate activitystreams_types;
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{object::Note, WithUnknown};
+///
+/// # fn main() {
+/// let mut note: WithUnknown<Note> = serde_json::from_str(r#"{
+///     "type": "Note",
+///     "content": "Hello",
+///     "sensitive": true,
+///     "_misskey_content": "Hello"
+/// }"#).unwrap();
+///
+/// assert_eq!(note.inner.object_props.content_string().unwrap(), "Hello");
+/// assert_eq!(note.unknown_as::<bool>("sensitive").unwrap(), true);
+///
+/// note.set_unknown("sensitive", false).unwrap();
+///
+/// let value = serde_json::to_value(&note).unwrap();
+/// assert_eq!(value["sensitive"], false);
+/// assert_eq!(value["_misskey_content"], "Hello");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WithUnknown<T> {
+    /// The value, with the properties its type knows about
+    pub inner: T,
+
+    /// The properties the inner type does not know about, by key
+    pub unknown: Map<String, Value>,
+}
+
+impl<T> WithUnknown<T> {
+    /// Wrap a value, with no unknown properties
+    pub fn new(inner: T) -> Self {
+        WithUnknown {
+            inner,
+            unknown: Map::new(),
+        }
+    }
+
+    /// Unwrap the value, dropping the unknown properties
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Fetch the raw value of an unknown property
+    pub fn unknown(&self, key: &str) -> Option<&Value> {
+        self.unknown.get(key)
+    }
+
+    /// Fetch an unknown property as a given type
+    ///
+    /// This fails with an `ErrorKind::NotFound` error if the property is missing, and with an
+    /// `ErrorKind::Deserialize` error if it can not be deserialized as `V`.
+    pub fn unknown_as<V>(&self, key: &str) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        let variant = type_name::<V>();
+        let value = self
+            .unknown
+            .get(key)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound).for_property(key, variant))?;
+
+        serde_json::from_value(value.clone())
+            .map_err(|e| Error::deserialize(e).for_property(key, variant))
+    }
+
+    /// Set an unknown property, replacing any existing value
+    pub fn set_unknown<K, V>(&mut self, key: K, value: V) -> Result<()>
+    where
+        K: Into<String>,
+        V: Serialize,
+    {
+        let key = key.into();
+        let value = serde_json::to_value(value)
+            .map_err(|e| Error::serialize(e).for_property(key.as_str(), type_name::<V>()))?;
+        self.unknown.insert(key, value);
+        Ok(())
+    }
+
+    /// Remove an unknown property, returning its value
+    pub fn remove_unknown(&mut self, key: &str) -> Option<Value> {
+        self.unknown.remove(key)
+    }
+}
+
+impl<T> Serialize for WithUnknown<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = match serde_json::to_value(&self.inner).map_err(ser::Error::custom)? {
+            Value::Object(map) => map,
+            other => return other.serialize(serializer),
+        };
+
+        // Properties of the inner value take precedence over unknown ones with the same key
+        for (key, value) in &self.unknown {
+            if !map.contains_key(key) {
+                map.insert(key.clone(), value.clone());
+            }
+        }
+
+        map.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for WithUnknown<T>
+where
+    T: DeserializeOwned + Vocabulary,
+{
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = Map::deserialize(deserializer)?;
+
+        let (known, unknown) = match T::properties(&kinds(&map)) {
+            Some(properties) => map
+                .into_iter()
+                .partition(|(key, _)| key == "type" || properties.iter().any(|p| p.name == key)),
+            None => (map, Map::new()),
+        };
+
+        let inner = serde_json::from_value(Value::Object(known)).map_err(de::Error::custom)?;
+
+        Ok(WithUnknown { inner, unknown })
+    }
+}
+
+/// Every property is kept, by the inner value or as an unknown property
+impl<T: Vocabulary> Vocabulary for WithUnknown<T> {
+    const TYPE: &'static str = T::TYPE;
+
+    fn properties(_: &[&str]) -> Option<Vec<&'static PropertyInfo>> {
+        None
+    }
+}
+
+impl<C, O> Vocabulary for CustomObject<C, O>
+where
+    C: Vocabulary,
+    O: Vocabulary,
+{
+    const TYPE: &'static str = O::TYPE;
+
+    fn properties(kinds: &[&str]) -> Option<Vec<&'static PropertyInfo>> {
+        let mut properties = O::properties(kinds)?;
+        properties.extend(C::properties(kinds)?);
+        Some(properties)
+    }
+}
+
+impl<C, L> Vocabulary for CustomLink<C, L>
+where
+    C: Vocabulary,
+    L: Vocabulary,
+{
+    const TYPE: &'static str = L::TYPE;
+
+    fn properties(kinds: &[&str]) -> Option<Vec<&'static PropertyInfo>> {
+        let mut properties = L::properties(kinds)?;
+        properties.extend(C::properties(kinds)?);
+        Some(properties)
+    }
+}
+
+/// The types of a document, whose `type` may be a single string or an array of them
+pub(crate) fn kinds(map: &Map<String, Value>) -> Vec<&str> {
+    match map.get("type") {
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        Some(kind) => kind.as_str().into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+impl<T: Object + Vocabulary> Object for WithUnknown<T> {}
+impl<T: Link + Vocabulary> Link for WithUnknown<T> {}
+impl<T: Actor + Vocabulary> Actor for WithUnknown<T> {}
+impl<T: Collection + Vocabulary> Collection for WithUnknown<T> {}
+impl<T: CollectionPage + Vocabulary> CollectionPage for WithUnknown<T> {}
+impl<T: Activity + Vocabulary> Activity for WithUnknown<T> {}
+impl<T: IntransitiveActivity + Vocabulary> IntransitiveActivity for WithUnknown<T> {}
+
+/// Generate an extension trait implementation for `WithUnknown` that forwards to the inner value
+macro_rules! forward_ext {
+    ($ext:ident, $props:ty) => {
+        impl<T: $ext + Vocabulary> $ext for WithUnknown<T> {
+            fn props(&self) -> &$props {
+                $ext::props(&self.inner)
+            }
+
+            fn props_mut(&mut self) -> &mut $props {
+                $ext::props_mut(&mut self.inner)
+            }
+        }
+    };
+}
+
+forward_ext!(ObjectExt, ObjectProperties);
+forward_ext!(LinkExt, LinkProperties);
+forward_ext!(ActorExt, ActorProperties);
+forward_ext!(CollectionExt, CollectionProperties);
+forward_ext!(CollectionPageExt, CollectionPageProperties);
+forward_ext!(ActivityExt, ActivityProperties);
+forward_ext!(SecurityExt, SecurityProperties);
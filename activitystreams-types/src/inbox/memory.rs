@@ -0,0 +1,301 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+};
+
+use inbox::{FollowState, InboxStore, StateChange};
+use primitives::XsdAnyUri;
+
+/// A pair of actor and object, such as a liker and the object they like
+type Pair = (XsdAnyUri, XsdAnyUri);
+
+/// An `InboxStore` that keeps the state in memory
+///
+/// This store is meant for tests, and for servers that do not need to keep their state.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{
+///     activity::AnyActivity,
+///     inbox::{receive, MemoryStore},
+///     primitives::XsdAnyUri,
+/// };
+///
+/// # fn main() {
+/// let mut store = MemoryStore::new();
+///
+/// let alice = "https://example.com/users/alice".parse().unwrap();
+/// let featured: XsdAnyUri = "https://example.com/users/alice/featured".parse().unwrap();
+/// store.set_target_owner(featured.clone(), alice);
+///
+/// let add: AnyActivity = serde_json::from_value(json!({
+///     "type": "Add",
+///     "actor": "https://example.com/users/alice",
+///     "object": "https://example.com/notes/1",
+///     "target": "https://example.com/users/alice/featured"
+/// })).unwrap();
+/// receive(&add, &mut store).unwrap();
+///
+/// let like: AnyActivity = serde_json::from_value(json!({
+///     "type": "Like",
+///     "id": "https://example.org/likes/1",
+///     "actor": "https://example.org/users/bob",
+///     "object": "https://example.com/notes/1"
+/// })).unwrap();
+/// receive(&like, &mut store).unwrap();
+///
+/// let note: XsdAnyUri = "https://example.com/notes/1".parse().unwrap();
+/// assert_eq!(store.collection(&featured), &[note.clone()]);
+/// assert_eq!(store.likes(&note).len(), 1);
+///
+/// let undo: AnyActivity = serde_json::from_value(json!({
+///     "type": "Undo",
+///     "actor": "https://example.org/users/bob",
+///     "object": "https://example.org/likes/1"
+/// })).unwrap();
+/// receive(&undo, &mut store).unwrap();
+///
+/// assert!(store.likes(&note).is_empty());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore {
+    follows: HashMap<Pair, FollowState>,
+    likes: HashSet<Pair>,
+    announces: HashSet<Pair>,
+    blocks: HashSet<Pair>,
+    collections: HashMap<XsdAnyUri, Vec<XsdAnyUri>>,
+    owners: HashMap<XsdAnyUri, XsdAnyUri>,
+    activities: HashMap<XsdAnyUri, StateChange>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+
+    /// Find the state of the follow request of `follower` for `followed`
+    pub fn follow_state(&self, follower: &XsdAnyUri, followed: &XsdAnyUri) -> Option<FollowState> {
+        self.follows
+            .get(&(follower.clone(), followed.clone()))
+            .cloned()
+    }
+
+    /// List the actors whose follow requests for `followed` were accepted
+    pub fn followers(&self, followed: &XsdAnyUri) -> Vec<&XsdAnyUri> {
+        matching(
+            self.follows
+                .iter()
+                .filter(|&(_, state)| *state == FollowState::Accepted)
+                .map(|(pair, _)| pair),
+            |pair| &pair.1 == followed,
+            |pair| &pair.0,
+        )
+    }
+
+    /// List the actors `follower` follows, once their follow requests were accepted
+    pub fn following(&self, follower: &XsdAnyUri) -> Vec<&XsdAnyUri> {
+        matching(
+            self.follows
+                .iter()
+                .filter(|&(_, state)| *state == FollowState::Accepted)
+                .map(|(pair, _)| pair),
+            |pair| &pair.0 == follower,
+            |pair| &pair.1,
+        )
+    }
+
+    /// List the actors that like `object`
+    pub fn likes(&self, object: &XsdAnyUri) -> Vec<&XsdAnyUri> {
+        matching(self.likes.iter(), |pair| &pair.1 == object, |pair| &pair.0)
+    }
+
+    /// List the actors that shared `object`
+    pub fn announces(&self, object: &XsdAnyUri) -> Vec<&XsdAnyUri> {
+        matching(
+            self.announces.iter(),
+            |pair| &pair.1 == object,
+            |pair| &pair.0,
+        )
+    }
+
+    /// Check whether `actor` blocks `object`
+    pub fn is_blocked(&self, actor: &XsdAnyUri, object: &XsdAnyUri) -> bool {
+        self.blocks.contains(&(actor.clone(), object.clone()))
+    }
+
+    /// Record `owner` as the actor that may add objects to and remove objects from `target`
+    pub fn set_target_owner(&mut self, target: XsdAnyUri, owner: XsdAnyUri) {
+        self.owners.insert(target, owner);
+    }
+
+    /// List the items of the collection `target`, in the order they were added
+    pub fn collection(&self, target: &XsdAnyUri) -> &[XsdAnyUri] {
+        self.collections
+            .get(target)
+            .map(|items| items.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+impl InboxStore for MemoryStore {
+    type Error = Infallible;
+
+    fn lookup(&self, id: &XsdAnyUri) -> Result<Option<StateChange>, Self::Error> {
+        Ok(self.activities.get(id).cloned())
+    }
+
+    fn follow_state(
+        &self,
+        follower: &XsdAnyUri,
+        followed: &XsdAnyUri,
+    ) -> Result<Option<FollowState>, Self::Error> {
+        Ok(MemoryStore::follow_state(self, follower, followed))
+    }
+
+    fn target_owner(&self, target: &XsdAnyUri) -> Result<Option<XsdAnyUri>, Self::Error> {
+        Ok(self.owners.get(target).cloned())
+    }
+
+    fn apply(&mut self, change: &StateChange) -> Result<(), Self::Error> {
+        match *change {
+            StateChange::FollowRequested {
+                ref follower,
+                ref followed,
+                ..
+            } => {
+                self.follows
+                    .entry((follower.clone(), followed.clone()))
+                    .or_insert(FollowState::Pending);
+            }
+            StateChange::FollowAccepted {
+                ref follower,
+                ref followed,
+            } => {
+                if let Some(state) = self.follows.get_mut(&(follower.clone(), followed.clone())) {
+                    *state = FollowState::Accepted;
+                }
+            }
+            StateChange::FollowRejected {
+                ref follower,
+                ref followed,
+            }
+            | StateChange::Unfollowed {
+                ref follower,
+                ref followed,
+            } => {
+                self.follows.remove(&(follower.clone(), followed.clone()));
+            }
+            StateChange::Liked {
+                ref actor,
+                ref object,
+                ..
+            } => {
+                self.likes.insert((actor.clone(), object.clone()));
+            }
+            StateChange::Unliked {
+                ref actor,
+                ref object,
+            } => {
+                self.likes.remove(&(actor.clone(), object.clone()));
+            }
+            StateChange::Announced {
+                ref actor,
+                ref object,
+                ..
+            } => {
+                self.announces.insert((actor.clone(), object.clone()));
+            }
+            StateChange::Unannounced {
+                ref actor,
+                ref object,
+            } => {
+                self.announces.remove(&(actor.clone(), object.clone()));
+            }
+            StateChange::Blocked {
+                ref actor,
+                ref object,
+                ..
+            } => {
+                self.blocks.insert((actor.clone(), object.clone()));
+            }
+            StateChange::Unblocked {
+                ref actor,
+                ref object,
+            } => {
+                self.blocks.remove(&(actor.clone(), object.clone()));
+            }
+            StateChange::Added {
+                ref object,
+                ref target,
+            } => {
+                let items = self.collections.entry(target.clone()).or_default();
+                if !items.contains(object) {
+                    items.push(object.clone());
+                }
+            }
+            StateChange::Removed {
+                ref object,
+                ref target,
+            } => {
+                if let Some(items) = self.collections.get_mut(target) {
+                    items.retain(|item| item != object);
+                }
+            }
+        }
+
+        match *change {
+            StateChange::FollowRequested {
+                id: Some(ref id), ..
+            }
+            | StateChange::Liked {
+                id: Some(ref id), ..
+            }
+            | StateChange::Announced {
+                id: Some(ref id), ..
+            }
+            | StateChange::Blocked {
+                id: Some(ref id), ..
+            } => {
+                self.activities.insert(id.clone(), change.clone());
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Select the pairs matching a filter, sorted so the output does not depend on hashing
+fn matching<'a, I, F, G>(pairs: I, filter: F, select: G) -> Vec<&'a XsdAnyUri>
+where
+    I: Iterator<Item = &'a Pair>,
+    F: Fn(&Pair) -> bool,
+    G: Fn(&'a Pair) -> &'a XsdAnyUri,
+{
+    let mut selected: Vec<&XsdAnyUri> = pairs.filter(|pair| filter(pair)).map(select).collect();
+    selected.sort();
+    selected
+}
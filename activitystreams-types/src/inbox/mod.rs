@@ -0,0 +1,523 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Side effects of activities received in an inbox
+//!
+//! The ActivityPub specification describes how a server's state changes when it receives
+//! activities from other servers. A `Follow` creates a follow request that becomes accepted when
+//! the followed actor sends back an `Accept`, a `Like` is recorded against its object, and an
+//! `Undo` reverts the activity it wraps.
+//!
+//! `receive` works out these changes for a received activity as `StateChange`s and applies them to
+//! an `InboxStore`, which keeps the state of the server. `MemoryStore` keeps the state in memory,
+//! which is useful in tests.
+//!
+//! The activity should already be authenticated, for example with an HTTP signature and the checks
+//! of the `origin` module.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! #[macro_use]
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     activity::AnyActivity,
+//!     inbox::{receive, FollowState, MemoryStore, StateChange},
+//! };
+//!
+//! # fn main() {
+//! let alice = "https://example.com/users/alice".parse().unwrap();
+//! let bob = "https://example.org/users/bob".parse().unwrap();
+//!
+//! let mut store = MemoryStore::new();
+//!
+//! let follow: AnyActivity = serde_json::from_value(json!({
+//!     "type": "Follow",
+//!     "id": "https://example.com/follows/1",
+//!     "actor": "https://example.com/users/alice",
+//!     "object": "https://example.org/users/bob"
+//! })).unwrap();
+//!
+//! receive(&follow, &mut store).unwrap();
+//! assert_eq!(store.follow_state(&alice, &bob), Some(FollowState::Pending));
+//!
+//! // Bob accepts, referring to the Follow by its id
+//! let accept: AnyActivity = serde_json::from_value(json!({
+//!     "type": "Accept",
+//!     "actor": "https://example.org/users/bob",
+//!     "object": "https://example.com/follows/1"
+//! })).unwrap();
+//!
+//! let changes = receive(&accept, &mut store).unwrap();
+//! assert_eq!(changes, vec![StateChange::FollowAccepted {
+//!     follower: alice.clone(),
+//!     followed: bob.clone(),
+//! }]);
+//! assert_eq!(store.followers(&bob), vec![&alice]);
+//!
+//! // Alice changes her mind
+//! let undo: AnyActivity = serde_json::from_value(json!({
+//!     "type": "Undo",
+//!     "actor": "https://example.com/users/alice",
+//!     "object": {
+//!         "type": "Follow",
+//!         "actor": "https://example.com/users/alice",
+//!         "object": "https://example.org/users/bob"
+//!     }
+//! })).unwrap();
+//!
+//! receive(&undo, &mut store).unwrap();
+//! assert_eq!(store.follow_state(&alice, &bob), None);
+//! # }
+//! ```
+
+use activitystreams_traits::{self, properties::to_value};
+use serde::ser::Serialize;
+use serde_json::Value;
+use std::{error::Error, fmt};
+
+use addressing::{address_id, values};
+use primitives::XsdAnyUri;
+
+mod memory;
+
+pub use self::memory::MemoryStore;
+
+/// The state of a follow request
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FollowState {
+    /// The `Follow` was received, and the followed actor has not accepted it yet
+    Pending,
+
+    /// The followed actor sent an `Accept` for the `Follow`
+    Accepted,
+}
+
+/// A change to the state of a server, caused by a received activity
+///
+/// The changes that record a new activity carry the id of that activity, if it has one, so later
+/// activities can refer to it by its id, such as an `Accept` of a `Follow` or an `Undo` of a
+/// `Like`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum StateChange {
+    /// `follower` asked to follow `followed`
+    FollowRequested {
+        id: Option<XsdAnyUri>,
+        follower: XsdAnyUri,
+        followed: XsdAnyUri,
+    },
+
+    /// `followed` accepted the pending follow request of `follower`
+    FollowAccepted {
+        follower: XsdAnyUri,
+        followed: XsdAnyUri,
+    },
+
+    /// `followed` rejected the follow request of `follower`
+    FollowRejected {
+        follower: XsdAnyUri,
+        followed: XsdAnyUri,
+    },
+
+    /// `follower` no longer follows `followed`, or withdrew their follow request
+    Unfollowed {
+        follower: XsdAnyUri,
+        followed: XsdAnyUri,
+    },
+
+    /// `actor` liked `object`
+    Liked {
+        id: Option<XsdAnyUri>,
+        actor: XsdAnyUri,
+        object: XsdAnyUri,
+    },
+
+    /// `actor` no longer likes `object`
+    Unliked { actor: XsdAnyUri, object: XsdAnyUri },
+
+    /// `actor` shared `object`
+    Announced {
+        id: Option<XsdAnyUri>,
+        actor: XsdAnyUri,
+        object: XsdAnyUri,
+    },
+
+    /// `actor` no longer shares `object`
+    Unannounced { actor: XsdAnyUri, object: XsdAnyUri },
+
+    /// `actor` blocked `object`
+    Blocked {
+        id: Option<XsdAnyUri>,
+        actor: XsdAnyUri,
+        object: XsdAnyUri,
+    },
+
+    /// `actor` no longer blocks `object`
+    Unblocked { actor: XsdAnyUri, object: XsdAnyUri },
+
+    /// `object` was added to the collection `target`
+    Added {
+        object: XsdAnyUri,
+        target: XsdAnyUri,
+    },
+
+    /// `object` was removed from the collection `target`
+    Removed {
+        object: XsdAnyUri,
+        target: XsdAnyUri,
+    },
+}
+
+impl StateChange {
+    /// The actor that made this change, if it records a new activity
+    fn actor(&self) -> Option<&XsdAnyUri> {
+        match *self {
+            StateChange::FollowRequested { ref follower, .. } => Some(follower),
+            StateChange::Liked { ref actor, .. }
+            | StateChange::Announced { ref actor, .. }
+            | StateChange::Blocked { ref actor, .. } => Some(actor),
+            _ => None,
+        }
+    }
+}
+
+/// The state of a server that received activities change
+///
+/// `receive` reads the state it needs to decide on the changes an activity makes, and then applies
+/// those changes with `apply`.
+pub trait InboxStore {
+    /// The error produced when the state can not be read or changed
+    type Error;
+
+    /// Find the change recorded for the activity with the given id
+    ///
+    /// This is the change `apply` was given with this id, such as a `StateChange::Liked` for the
+    /// id of a `Like`.
+    fn lookup(&self, id: &XsdAnyUri) -> Result<Option<StateChange>, Self::Error>;
+
+    /// Find the state of the follow request of `follower` for `followed`
+    fn follow_state(
+        &self,
+        follower: &XsdAnyUri,
+        followed: &XsdAnyUri,
+    ) -> Result<Option<FollowState>, Self::Error>;
+
+    /// Find the actor that owns the collection `target`, such as the actor whose `featured`
+    /// collection it is
+    ///
+    /// Only the owner may `Add` objects to or `Remove` objects from a collection. `Add`s and
+    /// `Remove`s for collections without a known owner make no changes.
+    fn target_owner(&self, target: &XsdAnyUri) -> Result<Option<XsdAnyUri>, Self::Error>;
+
+    /// Apply a change to the state
+    fn apply(&mut self, change: &StateChange) -> Result<(), Self::Error>;
+}
+
+/// The error produced when a received activity can not be processed
+#[derive(Debug)]
+pub enum InboxError<E> {
+    /// The activity has no actor with a valid id
+    MissingActor,
+
+    /// The actor of the activity may not act on the activity it refers to, such as an `Accept` of a
+    /// `Follow` sent by someone other than the followed actor, or an `Undo` of another actor's
+    /// `Like`, or may not change the collection it targets
+    ActorMismatch { expected: String, actor: String },
+
+    /// The activity could not be serialized
+    Serialize(activitystreams_traits::Error),
+
+    /// The store failed
+    Store(E),
+}
+
+impl<E> fmt::Display for InboxError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InboxError::MissingActor => f.write_str("Activity has no actor"),
+            InboxError::ActorMismatch {
+                ref expected,
+                ref actor,
+            } => write!(f, "Actor {} may not act for {}", actor, expected),
+            InboxError::Serialize(ref e) => fmt::Display::fmt(e, f),
+            InboxError::Store(ref e) => write!(f, "Store failed, {}", e),
+        }
+    }
+}
+
+impl<E> Error for InboxError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            InboxError::Serialize(ref e) => Some(e),
+            InboxError::Store(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Process an activity received in an inbox
+///
+/// The activity may be any of the types of the `activity` module, including `AnyActivity`. The
+/// changes it makes are applied to the store, and returned in the order they were applied.
+/// Activities without side effects, and activities referring to activities the store does not
+/// know about, make no changes.
+pub fn receive<T, S>(activity: &T, store: &mut S) -> Result<Vec<StateChange>, InboxError<S::Error>>
+where
+    T: Serialize,
+    S: InboxStore,
+{
+    let activity = to_value(activity).map_err(InboxError::Serialize)?;
+    let actor = first_id(activity.get("actor")).ok_or(InboxError::MissingActor)?;
+
+    let changes = match kind(&activity) {
+        Some("Accept") | Some("Reject") => {
+            let accept = kind(&activity) == Some("Accept");
+            let mut changes = Vec::new();
+
+            for (follower, followed) in follows(&activity, store)? {
+                if followed != actor {
+                    return Err(InboxError::ActorMismatch {
+                        expected: followed.to_string(),
+                        actor: actor.to_string(),
+                    });
+                }
+
+                let state = store
+                    .follow_state(&follower, &followed)
+                    .map_err(InboxError::Store)?;
+
+                changes.push(match state {
+                    Some(FollowState::Pending) if accept => {
+                        StateChange::FollowAccepted { follower, followed }
+                    }
+                    Some(_) if !accept => StateChange::FollowRejected { follower, followed },
+                    _ => continue,
+                });
+            }
+
+            changes
+        }
+        Some("Undo") => {
+            let mut changes = Vec::new();
+
+            for change in undone(&activity, store)? {
+                if let Some(original) = change.actor() {
+                    if *original != actor {
+                        return Err(InboxError::ActorMismatch {
+                            expected: original.to_string(),
+                            actor: actor.to_string(),
+                        });
+                    }
+                }
+
+                changes.push(match change {
+                    StateChange::FollowRequested {
+                        follower, followed, ..
+                    } => {
+                        let state = store
+                            .follow_state(&follower, &followed)
+                            .map_err(InboxError::Store)?;
+                        if state.is_none() {
+                            continue;
+                        }
+
+                        StateChange::Unfollowed { follower, followed }
+                    }
+                    StateChange::Liked { actor, object, .. } => {
+                        StateChange::Unliked { actor, object }
+                    }
+                    StateChange::Announced { actor, object, .. } => {
+                        StateChange::Unannounced { actor, object }
+                    }
+                    StateChange::Blocked { actor, object, .. } => {
+                        StateChange::Unblocked { actor, object }
+                    }
+                    _ => continue,
+                });
+            }
+
+            changes
+        }
+        Some("Follow") => {
+            let mut changes = Vec::new();
+
+            for change in recorded(&activity) {
+                if let StateChange::FollowRequested {
+                    ref follower,
+                    ref followed,
+                    ..
+                } = change
+                {
+                    let state = store
+                        .follow_state(follower, followed)
+                        .map_err(InboxError::Store)?;
+                    if state == Some(FollowState::Accepted) {
+                        continue;
+                    }
+                }
+
+                changes.push(change);
+            }
+
+            changes
+        }
+        Some("Add") | Some("Remove") => {
+            let add = kind(&activity) == Some("Add");
+            let objects = ids(activity.get("object"));
+            let mut changes = Vec::new();
+
+            for target in ids(activity.get("target")) {
+                match store.target_owner(&target).map_err(InboxError::Store)? {
+                    Some(ref owner) if *owner != actor => {
+                        return Err(InboxError::ActorMismatch {
+                            expected: owner.to_string(),
+                            actor: actor.to_string(),
+                        });
+                    }
+                    Some(_) => (),
+                    None => continue,
+                }
+
+                for object in &objects {
+                    let object = object.clone();
+                    let target = target.clone();
+
+                    changes.push(if add {
+                        StateChange::Added { object, target }
+                    } else {
+                        StateChange::Removed { object, target }
+                    });
+                }
+            }
+
+            changes
+        }
+        _ => recorded(&activity),
+    };
+
+    for change in &changes {
+        store.apply(change).map_err(InboxError::Store)?;
+    }
+
+    Ok(changes)
+}
+
+/// Work out the changes that record a `Follow`, `Like`, `Announce` or `Block`
+fn recorded(activity: &Value) -> Vec<StateChange> {
+    let actor = match first_id(activity.get("actor")) {
+        Some(actor) => actor,
+        None => return Vec::new(),
+    };
+    let id = activity
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(|id| id.parse().ok());
+
+    let change = |object: XsdAnyUri| {
+        let id = id.clone();
+        let actor = actor.clone();
+
+        match kind(activity) {
+            Some("Follow") => Some(StateChange::FollowRequested {
+                id,
+                follower: actor,
+                followed: object,
+            }),
+            Some("Like") => Some(StateChange::Liked { id, actor, object }),
+            Some("Announce") => Some(StateChange::Announced { id, actor, object }),
+            Some("Block") => Some(StateChange::Blocked { id, actor, object }),
+            _ => None,
+        }
+    };
+
+    ids(activity.get("object"))
+        .into_iter()
+        .filter_map(change)
+        .collect()
+}
+
+/// Find the follow requests an `Accept` or `Reject` refers to, as pairs of follower and followed
+fn follows<S>(
+    activity: &Value,
+    store: &S,
+) -> Result<Vec<(XsdAnyUri, XsdAnyUri)>, InboxError<S::Error>>
+where
+    S: InboxStore,
+{
+    Ok(undone(activity, store)?
+        .into_iter()
+        .filter_map(|change| match change {
+            StateChange::FollowRequested {
+                follower, followed, ..
+            } => Some((follower, followed)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Find the changes recorded for the activities in the `object` of an activity
+///
+/// Embedded activities are read as they are, and activities referred to by id are looked up in
+/// the store.
+fn undone<S>(activity: &Value, store: &S) -> Result<Vec<StateChange>, InboxError<S::Error>>
+where
+    S: InboxStore,
+{
+    let mut changes = Vec::new();
+
+    for object in activity.get("object").map(values).unwrap_or_default() {
+        if object.is_object() && kind(object).is_some() {
+            changes.extend(recorded(object));
+        } else if let Some(id) = address_id(object).and_then(|id| id.parse().ok()) {
+            changes.extend(store.lookup(&id).map_err(InboxError::Store)?);
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Read the `type` of a value, using the first one if it has several
+fn kind(value: &Value) -> Option<&str> {
+    match value.get("type") {
+        Some(Value::Array(kinds)) => kinds.iter().find_map(Value::as_str),
+        Some(kind) => kind.as_str(),
+        None => None,
+    }
+}
+
+/// Read the valid ids of the objects and links in a property
+fn ids(value: Option<&Value>) -> Vec<XsdAnyUri> {
+    value
+        .map(values)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(address_id)
+        .filter_map(|id| id.parse().ok())
+        .collect()
+}
+
+fn first_id(value: Option<&Value>) -> Option<XsdAnyUri> {
+    ids(value).into_iter().next()
+}
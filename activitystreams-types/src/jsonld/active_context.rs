@@ -0,0 +1,295 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Processing of `@context` values into an active context
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::{JsonLd, JsonLdError};
+
+/// How deeply remote contexts may refer to other remote contexts
+const MAX_REMOTE_DEPTH: usize = 8;
+
+/// The definition of a term, mapping it to an IRI or a keyword
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct TermDefinition {
+    /// The IRI the term expands to, or a keyword such as `@id` if the term is an alias
+    pub(super) iri: String,
+
+    /// The `@type` of the term, which is `@id`, `@vocab`, or the IRI of a datatype
+    pub(super) type_mapping: Option<String>,
+
+    /// The `@container` of the term, such as `@list` or `@language`
+    pub(super) container: Option<String>,
+
+    /// The `@language` of the term, where `Some(None)` means strings have no language
+    pub(super) language: Option<Option<String>>,
+}
+
+/// The state built up from the `@context` values that apply to part of a document
+#[derive(Clone, Debug, Default)]
+pub(super) struct ActiveContext {
+    /// Defined terms, where `None` means the term was explicitly mapped to `null`
+    pub(super) terms: HashMap<String, Option<TermDefinition>>,
+
+    /// The `@vocab` IRI that undefined terms are expanded against
+    pub(super) vocab: Option<String>,
+
+    /// The default `@language` of strings
+    pub(super) language: Option<String>,
+}
+
+impl ActiveContext {
+    /// Process a `@context` value, producing the resulting active context
+    pub(super) fn process(
+        &self,
+        processor: &JsonLd,
+        local: &Value,
+    ) -> Result<ActiveContext, JsonLdError> {
+        self.process_with_depth(processor, local, 0)
+    }
+
+    fn process_with_depth(
+        &self,
+        processor: &JsonLd,
+        local: &Value,
+        depth: usize,
+    ) -> Result<ActiveContext, JsonLdError> {
+        match *local {
+            Value::Null => Ok(ActiveContext::default()),
+            Value::Array(ref contexts) => contexts.iter().try_fold(self.clone(), |ctx, local| {
+                ctx.process_with_depth(processor, local, depth)
+            }),
+            Value::String(ref iri) => {
+                if depth >= MAX_REMOTE_DEPTH {
+                    return Err(JsonLdError::InvalidContext(format!(
+                        "too many nested remote contexts at {}",
+                        iri
+                    )));
+                }
+
+                match processor.remote_context(iri)? {
+                    Some(document) => {
+                        let remote = document.get("@context").ok_or_else(|| {
+                            JsonLdError::InvalidContext(format!("{} has no @context", iri))
+                        })?;
+                        self.process_with_depth(processor, remote, depth + 1)
+                    }
+                    None => Ok(self.clone()),
+                }
+            }
+            Value::Object(ref local) => self.process_local(local),
+            _ => Err(JsonLdError::InvalidContext(
+                "@context must be an IRI, an object, or an array".to_owned(),
+            )),
+        }
+    }
+
+    fn process_local(&self, local: &Map<String, Value>) -> Result<ActiveContext, JsonLdError> {
+        let mut ctx = self.clone();
+
+        match local.get("@vocab") {
+            Some(&Value::Null) => ctx.vocab = None,
+            Some(Value::String(vocab)) => {
+                let vocab = ctx.expand_iri(vocab, true);
+                ctx.vocab = Some(vocab);
+            }
+            Some(_) => return Err(invalid("@vocab must be a string")),
+            None => (),
+        }
+
+        match local.get("@language") {
+            Some(&Value::Null) => ctx.language = None,
+            Some(Value::String(language)) => ctx.language = Some(language.clone()),
+            Some(_) => return Err(invalid("@language must be a string")),
+            None => (),
+        }
+
+        let mut defined = HashMap::new();
+        for term in local.keys() {
+            if !term.starts_with('@') {
+                ctx.define(local, term, &mut defined)?;
+            }
+        }
+
+        Ok(ctx)
+    }
+
+    /// Create the definition of a term from a local context, first defining any terms it depends
+    /// on
+    fn define(
+        &mut self,
+        local: &Map<String, Value>,
+        term: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<(), JsonLdError> {
+        match defined.get(term) {
+            Some(&true) => return Ok(()),
+            Some(&false) => {
+                return Err(JsonLdError::InvalidContext(format!(
+                    "term {} is defined in terms of itself",
+                    term
+                )))
+            }
+            None => (),
+        }
+        defined.insert(term.to_owned(), false);
+
+        let value = match local.get(term) {
+            Some(&Value::Null) | None => {
+                self.terms.insert(term.to_owned(), None);
+                defined.insert(term.to_owned(), true);
+                return Ok(());
+            }
+            Some(Value::String(iri)) => {
+                let mut map = Map::new();
+                map.insert("@id".to_owned(), Value::String(iri.clone()));
+                map
+            }
+            Some(Value::Object(map)) => map.clone(),
+            Some(_) => {
+                return Err(JsonLdError::InvalidContext(format!(
+                    "the definition of {} must be a string or an object",
+                    term
+                )))
+            }
+        };
+
+        let iri = match value.get("@id") {
+            Some(Value::String(id)) => self.expand_iri_defining(local, id, defined)?,
+            Some(&Value::Null) => {
+                self.terms.insert(term.to_owned(), None);
+                defined.insert(term.to_owned(), true);
+                return Ok(());
+            }
+            Some(_) => return Err(invalid("@id of a term must be a string")),
+            None if term.contains(':') => self.expand_iri_defining(local, term, defined)?,
+            None => match self.vocab {
+                Some(ref vocab) => format!("{}{}", vocab, term),
+                None => {
+                    return Err(JsonLdError::InvalidContext(format!(
+                        "term {} has no @id and there is no @vocab",
+                        term
+                    )))
+                }
+            },
+        };
+
+        let type_mapping = match value.get("@type") {
+            Some(Value::String(kind)) if kind == "@id" || kind == "@vocab" => Some(kind.clone()),
+            Some(Value::String(kind)) => Some(self.expand_iri_defining(local, kind, defined)?),
+            Some(_) => return Err(invalid("@type of a term must be a string")),
+            None => None,
+        };
+
+        let container = match value.get("@container") {
+            Some(Value::String(container)) => Some(container.clone()),
+            Some(&Value::Null) | None => None,
+            Some(_) => return Err(invalid("@container of a term must be a string")),
+        };
+
+        let language = match value.get("@language") {
+            Some(Value::String(language)) => Some(Some(language.clone())),
+            Some(&Value::Null) => Some(None),
+            Some(_) => return Err(invalid("@language of a term must be a string")),
+            None => None,
+        };
+
+        self.terms.insert(
+            term.to_owned(),
+            Some(TermDefinition {
+                iri,
+                type_mapping,
+                container,
+                language,
+            }),
+        );
+        defined.insert(term.to_owned(), true);
+
+        Ok(())
+    }
+
+    /// Expand an IRI while processing a local context, defining the terms and prefixes it uses
+    fn expand_iri_defining(
+        &mut self,
+        local: &Map<String, Value>,
+        value: &str,
+        defined: &mut HashMap<String, bool>,
+    ) -> Result<String, JsonLdError> {
+        if local.contains_key(value) && !value.starts_with('@') {
+            self.define(local, value, defined)?;
+        }
+
+        if let Some(index) = value.find(':') {
+            let prefix = &value[..index];
+            if local.contains_key(prefix) {
+                self.define(local, prefix, defined)?;
+            }
+        }
+
+        Ok(self.expand_iri(value, true))
+    }
+
+    /// Look up the definition of a term
+    pub(super) fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.terms.get(term).and_then(Option::as_ref)
+    }
+
+    /// Expand a term, compact IRI or keyword into an absolute IRI
+    ///
+    /// When `vocab` is true, the value is treated as a property or type, so terms and `@vocab`
+    /// apply. Otherwise it is treated as an identifier, and only compact IRIs are expanded.
+    pub(super) fn expand_iri(&self, value: &str, vocab: bool) -> String {
+        if value.starts_with('@') {
+            return value.to_owned();
+        }
+
+        if vocab {
+            match self.terms.get(value) {
+                Some(Some(definition)) => return definition.iri.clone(),
+                Some(&None) => return String::new(),
+                None => (),
+            }
+        }
+
+        if let Some(index) = value.find(':') {
+            let (prefix, suffix) = (&value[..index], &value[index + 1..]);
+
+            if prefix == "_" || suffix.starts_with("//") {
+                return value.to_owned();
+            }
+
+            if let Some(definition) = self.term(prefix) {
+                return format!("{}{}", definition.iri, suffix);
+            }
+
+            return value.to_owned();
+        }
+
+        match self.vocab {
+            Some(ref base) if vocab => format!("{}{}", base, value),
+            _ => value.to_owned(),
+        }
+    }
+}
+
+fn invalid(message: &str) -> JsonLdError {
+    JsonLdError::InvalidContext(message.to_owned())
+}
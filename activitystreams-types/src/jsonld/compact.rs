@@ -0,0 +1,453 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Compaction of expanded documents into the terms used by this crate
+//!
+//! This follows the JSON-LD compaction algorithm for the ActivityStreams and Security contexts,
+//! with two differences: identifiers are always left as absolute IRIs, so that they can be
+//! compared and dereferenced without knowing the context, and a number is compacted into a term
+//! expecting any numeric XML Schema datatype, so that `1.5e1` in Turtle can fill an `xsd:float`.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use super::{
+    active_context::{ActiveContext, TermDefinition},
+    ACTIVITYSTREAMS_CONTEXT, SECURITY_CONTEXT,
+};
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+pub(super) struct Compactor<'a> {
+    ctx: &'a ActiveContext,
+
+    /// The terms defined by the ActivityStreams context alone
+    base: &'a ActiveContext,
+
+    /// The terms that map to each IRI, shortest first
+    inverse: HashMap<&'a str, Vec<(&'a str, &'a TermDefinition)>>,
+
+    /// Whether a term from the Security context has been used
+    used_security: bool,
+}
+
+impl<'a> Compactor<'a> {
+    pub(super) fn new(ctx: &'a ActiveContext, base: &'a ActiveContext) -> Self {
+        let mut inverse: HashMap<&str, Vec<(&str, &TermDefinition)>> = HashMap::new();
+
+        for (term, definition) in &ctx.terms {
+            if let Some(ref definition) = *definition {
+                inverse
+                    .entry(definition.iri.as_str())
+                    .or_default()
+                    .push((term.as_str(), definition));
+            }
+        }
+
+        for terms in inverse.values_mut() {
+            terms.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then(a.0.cmp(b.0)));
+        }
+
+        Compactor {
+            ctx,
+            base,
+            inverse,
+            used_security: false,
+        }
+    }
+
+    /// Compact an expanded document, adding the `@context` it was compacted with
+    pub(super) fn compact(mut self, expanded: &Value) -> Value {
+        let nodes: Vec<Value> = match *expanded {
+            Value::Array(ref nodes) => nodes
+                .iter()
+                .map(|node| self.compact_item(None, node))
+                .collect(),
+            ref node => vec![self.compact_item(None, node)],
+        };
+
+        let mut result = match nodes.len() {
+            1 => match nodes.into_iter().next() {
+                Some(Value::Object(node)) => node,
+                _ => Map::new(),
+            },
+            _ => {
+                let mut result = Map::new();
+                result.insert("@graph".to_owned(), Value::Array(nodes));
+                result
+            }
+        };
+
+        let context = if self.used_security {
+            Value::Array(vec![
+                Value::String(ACTIVITYSTREAMS_CONTEXT.to_owned()),
+                Value::String(SECURITY_CONTEXT.to_owned()),
+            ])
+        } else {
+            Value::String(ACTIVITYSTREAMS_CONTEXT.to_owned())
+        };
+        result.insert("@context".to_owned(), context);
+
+        Value::Object(result)
+    }
+
+    fn compact_node(&mut self, node: &Map<String, Value>) -> Map<String, Value> {
+        let mut result = Map::new();
+
+        for (key, value) in node {
+            match key.as_str() {
+                "@id" => {
+                    result.insert(self.keyword("@id"), value.clone());
+                }
+                "@type" => {
+                    let kinds: Vec<Value> = as_array(value)
+                        .iter()
+                        .filter_map(|kind| kind.as_str())
+                        .map(|kind| Value::String(self.compact_iri(kind)))
+                        .collect();
+                    result.insert(self.keyword("@type"), single_or_array(kinds));
+                }
+                "@graph" | "@list" | "@set" => {
+                    let items = as_array(value)
+                        .iter()
+                        .map(|item| self.compact_item(None, item))
+                        .collect();
+                    result.insert(key.clone(), Value::Array(items));
+                }
+                "@index" => {
+                    result.insert(key.clone(), value.clone());
+                }
+                _ if key.starts_with('@') => (),
+                _ => self.compact_property(&mut result, key, as_array(value)),
+            }
+        }
+
+        result
+    }
+
+    /// Compact the values of a property, choosing the best term for each value
+    fn compact_property(&mut self, result: &mut Map<String, Value>, iri: &str, values: &[Value]) {
+        let mut groups: Vec<(String, Option<&'a TermDefinition>, Vec<&Value>)> = Vec::new();
+
+        for value in values {
+            let (key, definition) = match self.select_term(iri, value) {
+                Some((term, definition)) => (term.to_owned(), Some(definition)),
+                None => (self.compact_iri(iri), None),
+            };
+
+            match groups.iter_mut().find(|group| group.0 == key) {
+                Some(group) => group.2.push(value),
+                None => groups.push((key, definition, vec![value])),
+            }
+        }
+
+        for (key, definition, values) in groups {
+            if !self.base.terms.contains_key(&key) && self.ctx.terms.contains_key(&key) {
+                self.used_security = true;
+            }
+
+            let container = definition.and_then(|d| d.container.as_deref());
+
+            let compacted = match container {
+                Some("@language") => {
+                    let mut map = Map::new();
+                    for value in values {
+                        let language = value
+                            .get("@language")
+                            .and_then(Value::as_str)
+                            .unwrap_or("@none");
+                        let string = value.get("@value").cloned().unwrap_or(Value::Null);
+
+                        let existing = map.remove(language);
+                        let entry = match existing {
+                            None => string,
+                            Some(Value::Array(mut strings)) => {
+                                strings.push(string);
+                                Value::Array(strings)
+                            }
+                            Some(other) => Value::Array(vec![other, string]),
+                        };
+                        map.insert(language.to_owned(), entry);
+                    }
+                    Value::Object(map)
+                }
+                Some("@list") => {
+                    let items = values
+                        .iter()
+                        .flat_map(|value| as_array(value.get("@list").unwrap_or(&Value::Null)))
+                        .map(|item| self.compact_item(definition, item))
+                        .collect();
+                    Value::Array(items)
+                }
+                Some("@set") => Value::Array(
+                    values
+                        .iter()
+                        .map(|value| self.compact_item(definition, value))
+                        .collect(),
+                ),
+                _ => single_or_array(
+                    values
+                        .iter()
+                        .map(|value| self.compact_item(definition, value))
+                        .collect(),
+                ),
+            };
+
+            result.insert(key, compacted);
+        }
+    }
+
+    /// Compact a single expanded value, as the value of a term with the given definition
+    fn compact_item(&mut self, definition: Option<&TermDefinition>, value: &Value) -> Value {
+        let object = match *value {
+            Value::Object(ref object) => object,
+            Value::Array(ref items) => {
+                return Value::Array(
+                    items
+                        .iter()
+                        .map(|item| self.compact_item(definition, item))
+                        .collect(),
+                )
+            }
+            ref scalar => return scalar.clone(),
+        };
+
+        let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+
+        if let Some(list) = object.get("@list") {
+            let items = as_array(list)
+                .iter()
+                .map(|item| self.compact_item(definition, item))
+                .collect();
+
+            let mut result = Map::new();
+            result.insert("@list".to_owned(), Value::Array(items));
+            return Value::Object(result);
+        }
+
+        if let Some(inner) = object.get("@value") {
+            let kind = object.get("@type").and_then(Value::as_str);
+            let language = object.get("@language").and_then(Value::as_str);
+            let term_language = definition.and_then(|d| d.language.as_ref());
+
+            let numeric = inner.is_number()
+                && kind.is_none_or(is_numeric_type)
+                && type_mapping.is_some_and(is_numeric_type);
+
+            let plain = numeric
+                || match (kind, language) {
+                    (Some(kind), _) => type_mapping == Some(kind),
+                    (None, Some(language)) => {
+                        term_language.map(|l| l.as_deref()) == Some(Some(language))
+                    }
+                    (None, None) => {
+                        !matches!(type_mapping, Some(t) if t != "@id" && t != "@vocab")
+                            && !matches!(term_language, Some(&Some(_)))
+                    }
+                };
+
+            if plain {
+                return inner.clone();
+            }
+
+            let mut result = Map::new();
+            result.insert("@value".to_owned(), inner.clone());
+            if let Some(kind) = kind {
+                result.insert(self.keyword("@type"), Value::String(self.compact_iri(kind)));
+            }
+            if let Some(language) = language {
+                result.insert("@language".to_owned(), Value::String(language.to_owned()));
+            }
+            return Value::Object(result);
+        }
+
+        if object.len() == 1 {
+            if let Some(Value::String(id)) = object.get("@id") {
+                match type_mapping {
+                    Some("@id") => return Value::String(id.clone()),
+                    Some("@vocab") => return Value::String(self.compact_iri(id)),
+                    _ => (),
+                }
+            }
+        }
+
+        Value::Object(self.compact_node(object))
+    }
+
+    /// Choose the term that best represents a value of a property
+    fn select_term(&self, iri: &str, value: &Value) -> Option<(&'a str, &'a TermDefinition)> {
+        let is_list = value.get("@list").is_some();
+        let language = value.get("@language").and_then(Value::as_str);
+        let kind = value.get("@type").and_then(Value::as_str);
+        let is_value = value.get("@value").is_some();
+        let is_reference =
+            value.as_object().map(|o| o.len() == 1).unwrap_or(false) && value.get("@id").is_some();
+
+        self.inverse
+            .get(iri)?
+            .iter()
+            .filter_map(|&(term, definition)| {
+                let container = definition.container.as_deref();
+                let type_mapping = definition.type_mapping.as_deref();
+
+                // Lower scores are better, and terms without a score cannot hold the value
+                let score = match container {
+                    Some("@list") if is_list => 0,
+                    Some("@list") => return None,
+                    _ if is_list => 3,
+                    Some("@language") if language.is_some() && kind.is_none() => 0,
+                    Some("@language") => return None,
+                    _ if is_value => match (kind, language) {
+                        (Some(kind), _) if type_mapping == Some(kind) => 0,
+                        (None, Some(language))
+                            if definition.language.as_ref().map(|l| l.as_deref())
+                                == Some(Some(language)) =>
+                        {
+                            0
+                        }
+                        (None, None) if type_mapping.is_none() => 0,
+                        _ if type_mapping.is_none() => 1,
+                        _ => 2,
+                    },
+                    _ if is_reference && type_mapping == Some("@id") => 0,
+                    _ if is_reference => 1,
+                    _ => 0,
+                };
+
+                Some((score, term, definition))
+            })
+            .min_by_key(|&(score, _, _)| score)
+            .map(|(_, term, definition)| (term, definition))
+    }
+
+    /// Compact an IRI used as a property or type into a term, compact IRI, or vocabulary-relative
+    /// IRI
+    fn compact_iri(&mut self, iri: &str) -> String {
+        if let Some(&(term, _)) = self.inverse.get(iri).and_then(|terms| {
+            terms
+                .iter()
+                .find(|&&(_, definition)| definition.container.is_none())
+        }) {
+            if !self.base.terms.contains_key(term) {
+                self.used_security = true;
+            }
+            return term.to_owned();
+        }
+
+        if let Some(ref vocab) = self.ctx.vocab {
+            if iri.len() > vocab.len() && iri.starts_with(vocab.as_str()) {
+                let suffix = &iri[vocab.len()..];
+                if !self.ctx.terms.contains_key(suffix) {
+                    return suffix.to_owned();
+                }
+            }
+        }
+
+        let mut best: Option<(String, &str)> = None;
+        for (term, definition) in &self.ctx.terms {
+            let definition = match *definition {
+                Some(ref definition) => definition,
+                None => continue,
+            };
+
+            if term.contains(':')
+                || definition.iri.starts_with('@')
+                || iri.len() <= definition.iri.len()
+                || !iri.starts_with(definition.iri.as_str())
+            {
+                continue;
+            }
+
+            let compact = format!("{}:{}", term, &iri[definition.iri.len()..]);
+            if self.ctx.terms.contains_key(&compact) {
+                continue;
+            }
+
+            let better = match best {
+                Some((ref current, _)) => {
+                    compact.len() < current.len()
+                        || (compact.len() == current.len() && compact < *current)
+                }
+                None => true,
+            };
+            if better {
+                best = Some((compact, term.as_str()));
+            }
+        }
+
+        match best {
+            Some((compact, prefix)) => {
+                if !self.base.terms.contains_key(prefix) {
+                    self.used_security = true;
+                }
+                compact
+            }
+            None => iri.to_owned(),
+        }
+    }
+
+    /// Find the alias of a keyword, such as `id` for `@id`
+    fn keyword(&self, keyword: &str) -> String {
+        self.inverse
+            .get(keyword)
+            .and_then(|terms| terms.first())
+            .map(|&(term, _)| term.to_owned())
+            .unwrap_or_else(|| keyword.to_owned())
+    }
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    match *value {
+        Value::Array(ref values) => values,
+        ref value => ::std::slice::from_ref(value),
+    }
+}
+
+fn single_or_array(mut values: Vec<Value>) -> Value {
+    if values.len() == 1 {
+        values.remove(0)
+    } else {
+        Value::Array(values)
+    }
+}
+
+/// Check whether a datatype is one of the XML Schema number types
+fn is_numeric_type(kind: &str) -> bool {
+    const NUMERIC_TYPES: &[&str] = &[
+        "byte",
+        "decimal",
+        "double",
+        "float",
+        "int",
+        "integer",
+        "long",
+        "negativeInteger",
+        "nonNegativeInteger",
+        "nonPositiveInteger",
+        "positiveInteger",
+        "short",
+        "unsignedByte",
+        "unsignedInt",
+        "unsignedLong",
+        "unsignedShort",
+    ];
+
+    kind.strip_prefix(XSD)
+        .is_some_and(|local| NUMERIC_TYPES.contains(&local))
+}
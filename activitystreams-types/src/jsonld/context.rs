@@ -0,0 +1,529 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! A typed representation of `@context` values
+
+use activitystreams_traits::{properties::OneOrMany, Error, ErrorKind};
+use serde::{
+    de::{Deserialize, Deserializer, Error as DeError},
+    ser::{Serialize, Serializer},
+};
+use serde_json::{self, Map, Value};
+use std::collections::BTreeMap;
+
+use super::{ACTIVITYSTREAMS_CONTEXT, SECURITY_CONTEXT};
+
+/// The `@context` of a document
+///
+/// A context is a list of entries, each of which is either the IRI of a remote context or an
+/// inline context defining terms. A context with a single entry is written as that entry alone,
+/// rather than as an array.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::jsonld::{Context, TermDefinition};
+///
+/// # fn main() {
+/// let context = Context::builder()
+///     .activitystreams()
+///     .security()
+///     .term("toot", "http://joinmastodon.org/ns#")
+///     .term("manuallyApprovesFollowers", "as:manuallyApprovesFollowers")
+///     .term("featured", TermDefinition::new("toot:featured").kind("@id"))
+///     .language("en")
+///     .build();
+///
+/// assert_eq!(serde_json::to_value(&context).unwrap(), json!([
+///     "https://www.w3.org/ns/activitystreams",
+///     "https://w3id.org/security/v1",
+///     {
+///         "@language": "en",
+///         "featured": { "@id": "toot:featured", "@type": "@id" },
+///         "manuallyApprovesFollowers": "as:manuallyApprovesFollowers",
+///         "toot": "http://joinmastodon.org/ns#"
+///     }
+/// ]));
+///
+/// let featured = context.term("featured").unwrap();
+/// assert_eq!(featured.id.as_ref().map(String::as_str), Some("toot:featured"));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Context {
+    /// The entries of the context, in the order they are applied
+    pub entries: Vec<ContextEntry>,
+}
+
+impl Context {
+    /// Start building a context
+    pub fn builder() -> ContextBuilder {
+        ContextBuilder::default()
+    }
+
+    /// The context used by plain ActivityStreams documents
+    pub fn activitystreams() -> Self {
+        Context::builder().activitystreams().build()
+    }
+
+    /// Iterate over the IRIs of the remote contexts
+    pub fn remotes(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|entry| match *entry {
+            ContextEntry::Remote(ref iri) => Some(iri.as_str()),
+            ContextEntry::Inline(_) => None,
+        })
+    }
+
+    /// Check whether the context includes a remote context
+    pub fn has_remote(&self, iri: &str) -> bool {
+        self.remotes().any(|remote| remote == iri)
+    }
+
+    /// Look up a term defined inline, where later definitions override earlier ones
+    ///
+    /// Returns `None` if the term is not defined inline, or was last mapped to `null`.
+    pub fn term(&self, term: &str) -> Option<&TermDefinition> {
+        self.inline()
+            .filter_map(|inline| inline.terms.get(term))
+            .last()
+            .and_then(Option::as_ref)
+    }
+
+    /// The default language set inline, if any
+    pub fn language(&self) -> Option<&str> {
+        self.inline()
+            .filter_map(|inline| inline.language.as_ref())
+            .last()
+            .and_then(|language| language.as_deref())
+    }
+
+    /// The vocabulary IRI set inline, if any
+    pub fn vocab(&self) -> Option<&str> {
+        self.inline()
+            .filter_map(|inline| inline.vocab.as_ref())
+            .last()
+            .and_then(|vocab| vocab.as_deref())
+    }
+
+    fn inline(&self) -> impl Iterator<Item = &InlineContext> {
+        self.entries.iter().filter_map(|entry| match *entry {
+            ContextEntry::Inline(ref inline) => Some(inline),
+            ContextEntry::Remote(_) => None,
+        })
+    }
+}
+
+/// An entry in a `Context`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContextEntry {
+    /// The IRI of a remote context
+    Remote(String),
+
+    /// A context defined inline
+    Inline(InlineContext),
+}
+
+/// A context defined inline, as a JSON object
+///
+/// For `language` and `vocab`, `Some(None)` means the member was explicitly set to `null`, which
+/// clears the value set by an earlier context.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InlineContext {
+    /// The default language of strings, written as `@language`
+    pub language: Option<Option<String>>,
+
+    /// The IRI that undefined terms are expanded against, written as `@vocab`
+    pub vocab: Option<Option<String>>,
+
+    /// The terms defined by this context, where `None` means the term is mapped to `null`
+    pub terms: BTreeMap<String, Option<TermDefinition>>,
+
+    /// Members that are not read into the other fields, such as `@version` and `@protected`,
+    /// which are kept so the context is written back unchanged
+    pub other: Map<String, Value>,
+}
+
+impl InlineContext {
+    /// Check whether this context defines nothing
+    pub fn is_empty(&self) -> bool {
+        self.language.is_none()
+            && self.vocab.is_none()
+            && self.terms.is_empty()
+            && self.other.is_empty()
+    }
+}
+
+/// The definition of a term in an inline context
+///
+/// A definition with only an `id` is written as a plain string. Members that are not read into
+/// the other fields, such as `@reverse`, `@protected` or a `@container` array, are kept in
+/// `other`, so the definition is written back unchanged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TermDefinition {
+    /// The IRI or compact IRI the term stands for, written as `@id`
+    pub id: Option<String>,
+
+    /// The type values of the term are coerced to, such as `@id` or `xsd:dateTime`, written as
+    /// `@type`
+    pub kind: Option<String>,
+
+    /// How multiple values of the term are organised, such as `@list` or `@language`, written as
+    /// `@container`
+    pub container: Option<String>,
+
+    /// The language of strings using this term, written as `@language`, where `Some(None)` means
+    /// strings have no language
+    pub language: Option<Option<String>>,
+
+    /// Members that are not read into the other fields
+    pub other: Map<String, Value>,
+}
+
+impl TermDefinition {
+    /// Define a term that stands for the given IRI
+    pub fn new<T>(id: T) -> Self
+    where
+        T: Into<String>,
+    {
+        TermDefinition {
+            id: Some(id.into()),
+            ..TermDefinition::default()
+        }
+    }
+
+    /// Set the type values of the term are coerced to
+    pub fn kind<T>(mut self, kind: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    /// Set the container of the term
+    pub fn container<T>(mut self, container: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.container = Some(container.into());
+        self
+    }
+
+    /// Set the language of strings using the term
+    pub fn language<T>(mut self, language: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.language = Some(Some(language.into()));
+        self
+    }
+
+    fn is_simple(&self) -> bool {
+        self.id.is_some()
+            && self.kind.is_none()
+            && self.container.is_none()
+            && self.language.is_none()
+            && self.other.is_empty()
+    }
+}
+
+impl<'a> From<&'a str> for TermDefinition {
+    fn from(id: &'a str) -> Self {
+        TermDefinition::new(id)
+    }
+}
+
+impl From<String> for TermDefinition {
+    fn from(id: String) -> Self {
+        TermDefinition::new(id)
+    }
+}
+
+/// Builds a `Context` from remote contexts followed by a single inline context
+#[derive(Clone, Debug, Default)]
+pub struct ContextBuilder {
+    remotes: Vec<String>,
+    inline: InlineContext,
+}
+
+impl ContextBuilder {
+    /// Add the ActivityStreams context
+    pub fn activitystreams(self) -> Self {
+        self.remote(ACTIVITYSTREAMS_CONTEXT)
+    }
+
+    /// Add the Security Vocabulary context
+    pub fn security(self) -> Self {
+        self.remote(SECURITY_CONTEXT)
+    }
+
+    /// Add a remote context
+    pub fn remote<T>(mut self, iri: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.remotes.push(iri.into());
+        self
+    }
+
+    /// Define a term in the inline context
+    pub fn term<T, D>(mut self, term: T, definition: D) -> Self
+    where
+        T: Into<String>,
+        D: Into<TermDefinition>,
+    {
+        self.inline
+            .terms
+            .insert(term.into(), Some(definition.into()));
+        self
+    }
+
+    /// Set the default language of strings
+    pub fn language<T>(mut self, language: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.inline.language = Some(Some(language.into()));
+        self
+    }
+
+    /// Set the IRI that undefined terms are expanded against
+    pub fn vocab<T>(mut self, vocab: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.inline.vocab = Some(Some(vocab.into()));
+        self
+    }
+
+    /// Finish building the context
+    pub fn build(self) -> Context {
+        let mut entries: Vec<ContextEntry> =
+            self.remotes.into_iter().map(ContextEntry::Remote).collect();
+
+        if !self.inline.is_empty() {
+            entries.push(ContextEntry::Inline(self.inline));
+        }
+
+        Context { entries }
+    }
+}
+
+/// Read a `Context` out of the raw `@context` field of a properties struct
+pub(crate) fn from_field(
+    field: Option<&OneOrMany<Value>>,
+) -> ::activitystreams_traits::Result<Context> {
+    let field =
+        field.ok_or_else(|| Error::new(ErrorKind::NotFound).for_property("context", "Context"))?;
+
+    serde_json::to_value(field)
+        .and_then(serde_json::from_value)
+        .map_err(|e| Error::deserialize(e).for_property("context", "Context"))
+}
+
+/// Write a `Context` into the form stored in the `@context` field of a properties struct
+pub(crate) fn into_field(context: &Context) -> ::activitystreams_traits::Result<OneOrMany<Value>> {
+    serde_json::to_value(context)
+        .and_then(serde_json::from_value)
+        .map_err(|e| Error::serialize(e).for_property("context", "Context"))
+}
+
+impl Serialize for Context {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.entries.len() {
+            1 => self.entries[0].serialize(serializer),
+            _ => self.entries.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Context {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let entries = match Value::deserialize(deserializer)? {
+            Value::Null => Vec::new(),
+            Value::Array(entries) => entries
+                .into_iter()
+                .map(|entry| serde_json::from_value(entry).map_err(D::Error::custom))
+                .collect::<Result<_, _>>()?,
+            entry => vec![serde_json::from_value(entry).map_err(D::Error::custom)?],
+        };
+
+        Ok(Context { entries })
+    }
+}
+
+impl Serialize for ContextEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            ContextEntry::Remote(ref iri) => serializer.serialize_str(iri),
+            ContextEntry::Inline(ref inline) => inline.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContextEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(iri) => Ok(ContextEntry::Remote(iri)),
+            inline @ Value::Object(_) => serde_json::from_value(inline)
+                .map(ContextEntry::Inline)
+                .map_err(D::Error::custom),
+            _ => Err(D::Error::custom(
+                "a @context entry must be an IRI or an object",
+            )),
+        }
+    }
+}
+
+impl Serialize for InlineContext {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = self.other.clone();
+
+        if let Some(ref language) = self.language {
+            map.insert("@language".to_owned(), nullable(language));
+        }
+        if let Some(ref vocab) = self.vocab {
+            map.insert("@vocab".to_owned(), nullable(vocab));
+        }
+        for (term, definition) in &self.terms {
+            let definition =
+                serde_json::to_value(definition).map_err(::serde::ser::Error::custom)?;
+            map.insert(term.clone(), definition);
+        }
+
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InlineContext {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = Map::<String, Value>::deserialize(deserializer)?;
+        let mut inline = InlineContext::default();
+
+        for (key, value) in map {
+            match (key.as_str(), value) {
+                ("@language", Value::String(language)) => inline.language = Some(Some(language)),
+                ("@language", Value::Null) => inline.language = Some(None),
+                ("@vocab", Value::String(vocab)) => inline.vocab = Some(Some(vocab)),
+                ("@vocab", Value::Null) => inline.vocab = Some(None),
+                (_, value) if key.starts_with('@') => {
+                    // Keywords such as @version and @protected do not change how terms expand
+                    inline.other.insert(key, value);
+                }
+                (_, value) => {
+                    let definition = serde_json::from_value(value).map_err(D::Error::custom)?;
+                    inline.terms.insert(key, definition);
+                }
+            }
+        }
+
+        Ok(inline)
+    }
+}
+
+impl Serialize for TermDefinition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.is_simple() {
+            if let Some(ref id) = self.id {
+                return serializer.serialize_str(id);
+            }
+        }
+
+        let mut map = self.other.clone();
+        let fields = [
+            ("@id", &self.id),
+            ("@type", &self.kind),
+            ("@container", &self.container),
+        ];
+        for &(key, value) in &fields {
+            if let Some(ref value) = *value {
+                map.insert(key.to_owned(), Value::String(value.clone()));
+            }
+        }
+        if let Some(ref language) = self.language {
+            map.insert("@language".to_owned(), nullable(language));
+        }
+
+        map.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TermDefinition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Value::deserialize(deserializer)? {
+            Value::String(id) => Ok(TermDefinition::new(id)),
+            Value::Object(map) => {
+                let mut definition = TermDefinition::default();
+
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("@id", Value::String(id)) => definition.id = Some(id),
+                        ("@type", Value::String(kind)) => definition.kind = Some(kind),
+                        ("@container", Value::String(container)) => {
+                            definition.container = Some(container)
+                        }
+                        ("@language", Value::String(language)) => {
+                            definition.language = Some(Some(language))
+                        }
+                        ("@language", Value::Null) => definition.language = Some(None),
+                        (_, value) => {
+                            definition.other.insert(key, value);
+                        }
+                    }
+                }
+
+                Ok(definition)
+            }
+            _ => Err(D::Error::custom(
+                "a term definition must be an IRI or an object",
+            )),
+        }
+    }
+}
+
+/// Write a member that may be explicitly set to `null`
+fn nullable(value: &Option<String>) -> Value {
+    value.clone().map(Value::String).unwrap_or(Value::Null)
+}
@@ -0,0 +1,273 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! The JSON-LD expansion algorithm
+//!
+//! Expansion removes the `@context` of a document, replacing every term with the full IRI it
+//! stands for, and every value with an explicit `@value`, `@id` or `@list` object.
+
+use serde_json::{Map, Value};
+
+use super::{
+    active_context::{ActiveContext, TermDefinition},
+    JsonLd, JsonLdError,
+};
+
+/// Expand a document, always producing an array of node objects
+pub(super) fn expand(processor: &JsonLd, document: &Value) -> Result<Value, JsonLdError> {
+    let expanded = expand_element(processor, &ActiveContext::default(), None, document)?;
+
+    let nodes = match expanded {
+        Value::Null => Vec::new(),
+        Value::Array(nodes) => nodes,
+        Value::Object(ref node) if node.len() == 1 && node.contains_key("@graph") => {
+            match node["@graph"] {
+                Value::Array(ref nodes) => nodes.clone(),
+                ref node => vec![node.clone()],
+            }
+        }
+        node => vec![node],
+    };
+
+    Ok(Value::Array(nodes))
+}
+
+fn expand_element(
+    processor: &JsonLd,
+    ctx: &ActiveContext,
+    property: Option<&str>,
+    element: &Value,
+) -> Result<Value, JsonLdError> {
+    match *element {
+        Value::Null => Ok(Value::Null),
+        Value::Array(ref elements) => {
+            let mut result = Vec::new();
+            for element in elements {
+                match expand_element(processor, ctx, property, element)? {
+                    Value::Null => (),
+                    Value::Array(items) => result.extend(items),
+                    item => result.push(item),
+                }
+            }
+            Ok(Value::Array(result))
+        }
+        Value::Object(ref object) => expand_object(processor, ctx, property, object),
+        ref scalar => match property {
+            // Values outside of any property do not belong to a node, and are dropped
+            None => Ok(Value::Null),
+            Some(property) => Ok(expand_value(ctx, property, scalar)),
+        },
+    }
+}
+
+fn expand_object(
+    processor: &JsonLd,
+    ctx: &ActiveContext,
+    property: Option<&str>,
+    object: &Map<String, Value>,
+) -> Result<Value, JsonLdError> {
+    let ctx = match object.get("@context") {
+        Some(local) => ctx.process(processor, local)?,
+        None => ctx.clone(),
+    };
+
+    let mut result = Map::new();
+
+    for (key, value) in object {
+        if key == "@context" {
+            continue;
+        }
+
+        let expanded_property = ctx.expand_iri(key, true);
+        if expanded_property.is_empty()
+            || !(expanded_property.contains(':') || expanded_property.starts_with('@'))
+        {
+            continue;
+        }
+
+        if expanded_property.starts_with('@') {
+            let expanded = match expanded_property.as_str() {
+                "@id" => match *value {
+                    Value::String(ref id) => Value::String(ctx.expand_iri(id, false)),
+                    _ => return Err(invalid("@id must be a string")),
+                },
+                "@type" => match *value {
+                    Value::String(ref kind) => {
+                        Value::Array(vec![Value::String(ctx.expand_iri(kind, true))])
+                    }
+                    Value::Array(ref kinds) => Value::Array(
+                        kinds
+                            .iter()
+                            .map(|kind| match *kind {
+                                Value::String(ref kind) => {
+                                    Ok(Value::String(ctx.expand_iri(kind, true)))
+                                }
+                                _ => Err(invalid("@type must be a string or array of strings")),
+                            })
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    _ => return Err(invalid("@type must be a string or array of strings")),
+                },
+                "@value" | "@index" => value.clone(),
+                "@language" => match *value {
+                    Value::String(ref language) => Value::String(language.clone()),
+                    _ => return Err(invalid("@language must be a string")),
+                },
+                "@list" | "@set" | "@graph" => {
+                    let items = expand_element(processor, &ctx, property, value)?;
+                    match items {
+                        Value::Array(items) => Value::Array(items),
+                        Value::Null => Value::Array(Vec::new()),
+                        item => Value::Array(vec![item]),
+                    }
+                }
+                // Other keywords, such as @reverse and @included, are not supported
+                _ => continue,
+            };
+
+            result.insert(expanded_property, expanded);
+            continue;
+        }
+
+        let definition = ctx.term(key);
+        let container = definition.and_then(|d| d.container.as_deref());
+
+        let expanded = match (value, container) {
+            (Value::Object(map), Some("@language")) => expand_language_map(map)?,
+            _ => expand_element(processor, &ctx, Some(key), value)?,
+        };
+
+        let mut items = match expanded {
+            Value::Null => continue,
+            Value::Array(items) => items,
+            item => vec![item],
+        };
+
+        if container == Some("@list") && !items.iter().any(|item| item.get("@list").is_some()) {
+            let mut list = Map::new();
+            list.insert("@list".to_owned(), Value::Array(items));
+            items = vec![Value::Object(list)];
+        }
+
+        match *result
+            .entry(expanded_property)
+            .or_insert_with(|| Value::Array(Vec::new()))
+        {
+            Value::Array(ref mut existing) => existing.extend(items),
+            _ => unreachable!("properties are always expanded to arrays"),
+        }
+    }
+
+    // A `@set` is only a way of writing an array
+    if let Some(set) = result.remove("@set") {
+        return Ok(set);
+    }
+
+    if result.contains_key("@value") {
+        // The type of a value is a single IRI, rather than an array
+        if let Some(Value::Array(mut kinds)) = result.remove("@type") {
+            if let Some(kind) = kinds.pop() {
+                result.insert("@type".to_owned(), kind);
+            }
+        }
+
+        return Ok(match result.get("@value") {
+            Some(&Value::Null) => Value::Null,
+            _ => Value::Object(result),
+        });
+    }
+
+    // Objects that only hold a language do not express anything
+    if result.len() == 1 && result.contains_key("@language") {
+        return Ok(Value::Null);
+    }
+
+    Ok(Value::Object(result))
+}
+
+/// Expand a scalar value of a property into a value object or node reference
+fn expand_value(ctx: &ActiveContext, property: &str, value: &Value) -> Value {
+    let definition: Option<&TermDefinition> = ctx.term(property);
+    let type_mapping = definition.and_then(|d| d.type_mapping.as_deref());
+
+    let mut result = Map::new();
+
+    match (type_mapping, value) {
+        (Some("@id"), Value::String(id)) => {
+            result.insert("@id".to_owned(), Value::String(ctx.expand_iri(id, false)));
+        }
+        (Some("@vocab"), Value::String(id)) => {
+            result.insert("@id".to_owned(), Value::String(ctx.expand_iri(id, true)));
+        }
+        (Some(datatype), _) if datatype != "@id" && datatype != "@vocab" => {
+            result.insert("@value".to_owned(), value.clone());
+            result.insert("@type".to_owned(), Value::String(datatype.to_owned()));
+        }
+        (_, &Value::String(_)) => {
+            result.insert("@value".to_owned(), value.clone());
+
+            let language = match definition.and_then(|d| d.language.as_ref()) {
+                Some(language) => language.as_ref(),
+                None => ctx.language.as_ref(),
+            };
+
+            if let Some(language) = language {
+                result.insert("@language".to_owned(), Value::String(language.clone()));
+            }
+        }
+        _ => {
+            result.insert("@value".to_owned(), value.clone());
+        }
+    }
+
+    Value::Object(result)
+}
+
+/// Expand the value of a property with a `@language` container
+fn expand_language_map(map: &Map<String, Value>) -> Result<Value, JsonLdError> {
+    let mut result = Vec::new();
+
+    for (language, values) in map {
+        let values = match *values {
+            Value::Array(ref values) => values.iter().collect(),
+            ref value => vec![value],
+        };
+
+        for value in values {
+            match *value {
+                Value::Null => (),
+                Value::String(_) => {
+                    let mut object = Map::new();
+                    object.insert("@value".to_owned(), value.clone());
+                    if language != "@none" {
+                        object.insert("@language".to_owned(), Value::String(language.clone()));
+                    }
+                    result.push(Value::Object(object));
+                }
+                _ => return Err(invalid("values of a language map must be strings")),
+            }
+        }
+    }
+
+    Ok(Value::Array(result))
+}
+
+fn invalid(message: &str) -> JsonLdError {
+    JsonLdError::InvalidDocument(message.to_owned())
+}
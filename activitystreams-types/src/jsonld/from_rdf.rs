@@ -0,0 +1,308 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Conversion of RDF back into a JSON-LD node
+//!
+//! This follows the JSON-LD Serialize RDF as JSON-LD algorithm for the default graph, using
+//! native JSON numbers and booleans where the datatype allows it. Since the crate's types are
+//! trees rather than graphs, the node that no other node refers to is taken as the root, and the
+//! nodes it refers to are embedded in it.
+
+use serde_json::{Map, Number, Value};
+use std::collections::{HashMap, HashSet};
+
+use super::JsonLdError;
+use rdf::{
+    Literal, Quad, Term, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE,
+    XSD_INTEGER, XSD_STRING,
+};
+
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// The XML Schema datatypes whose values are whole numbers
+const INTEGER_TYPES: &[&str] = &[
+    "byte",
+    "int",
+    "long",
+    "negativeInteger",
+    "nonNegativeInteger",
+    "nonPositiveInteger",
+    "positiveInteger",
+    "short",
+    "unsignedByte",
+    "unsignedInt",
+    "unsignedLong",
+    "unsignedShort",
+];
+
+/// The XML Schema datatypes whose values are decimal or floating point numbers
+const DECIMAL_TYPES: &[&str] = &["decimal", "float"];
+
+/// Convert the default graph of a dataset into a single expanded node object
+pub(super) fn from_rdf(quads: &[Quad]) -> Result<Value, JsonLdError> {
+    let mut graph = Graph::default();
+    for quad in quads.iter().filter(|quad| quad.graph.is_none()) {
+        graph.add(quad);
+    }
+
+    let root = graph.root()?;
+    let mut stack = vec![root.clone()];
+    let node = graph.embed_node(&root, &mut stack);
+
+    Ok(Value::Array(vec![Value::Object(node)]))
+}
+
+#[derive(Default)]
+struct Graph {
+    /// The subjects of the dataset, in the order they first appear
+    order: Vec<String>,
+    nodes: HashMap<String, Map<String, Value>>,
+
+    /// How many times each node is used as an object
+    references: HashMap<String, usize>,
+}
+
+impl Graph {
+    fn add(&mut self, quad: &Quad) {
+        let subject = match node_id(&quad.subject) {
+            Some(subject) => subject,
+            None => return,
+        };
+        let predicate = match quad.predicate.as_iri() {
+            Some(predicate) => predicate,
+            None => return,
+        };
+
+        if !self.nodes.contains_key(&subject) {
+            let mut node = Map::new();
+            node.insert("@id".to_owned(), Value::String(subject.clone()));
+            self.nodes.insert(subject.clone(), node);
+            self.order.push(subject.clone());
+        }
+
+        let (key, value) = match (predicate, node_id(&quad.object)) {
+            (RDF_TYPE, Some(kind)) => ("@type", Value::String(kind)),
+            (_, Some(id)) => {
+                *self.references.entry(id.clone()).or_insert(0) += 1;
+
+                let mut reference = Map::new();
+                reference.insert("@id".to_owned(), Value::String(id));
+                (predicate, Value::Object(reference))
+            }
+            (_, None) => match quad.object {
+                Term::Literal(ref literal) => (predicate, literal_value(literal)),
+                _ => return,
+            },
+        };
+
+        if let Some(node) = self.nodes.get_mut(&subject) {
+            if let Value::Array(ref mut values) = *node
+                .entry(key.to_owned())
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                if !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+        }
+    }
+
+    /// Find the one node that no other node refers to
+    fn root(&self) -> Result<String, JsonLdError> {
+        let mut roots = self
+            .order
+            .iter()
+            .filter(|id| !self.references.contains_key(*id) && !self.is_list_node(id));
+
+        match (roots.next(), roots.next()) {
+            (Some(root), None) => Ok(root.clone()),
+            (Some(_), Some(_)) => Err(JsonLdError::InvalidDocument(
+                "the RDF describes more than one top-level node".to_owned(),
+            )),
+            // Every node is referred to by another, so fall back to the first subject
+            (None, _) => self.order.first().cloned().ok_or_else(|| {
+                JsonLdError::InvalidDocument("the RDF does not describe any nodes".to_owned())
+            }),
+        }
+    }
+
+    /// Copy a node, embedding the nodes and lists its properties refer to
+    fn embed_node(&self, id: &str, stack: &mut Vec<String>) -> Map<String, Value> {
+        let mut node = Map::new();
+
+        for (key, value) in &self.nodes[id] {
+            match key.as_str() {
+                "@id" if id.starts_with("_:") => (),
+                "@id" | "@type" => {
+                    node.insert(key.clone(), value.clone());
+                }
+                _ => {
+                    let values = match *value {
+                        Value::Array(ref values) => values
+                            .iter()
+                            .map(|value| self.embed_value(value, stack))
+                            .collect(),
+                        _ => vec![],
+                    };
+                    node.insert(key.clone(), Value::Array(values));
+                }
+            }
+        }
+
+        node
+    }
+
+    fn embed_value(&self, value: &Value, stack: &mut Vec<String>) -> Value {
+        let id = match value.get("@id").and_then(Value::as_str) {
+            Some(id) => id,
+            None => return value.clone(),
+        };
+
+        if let Some(items) = self.list(id) {
+            let items = items
+                .iter()
+                .map(|item| self.embed_value(item, stack))
+                .collect();
+
+            let mut list = Map::new();
+            list.insert("@list".to_owned(), Value::Array(items));
+            return Value::Object(list);
+        }
+
+        let embeddable = matches!(self.nodes.get(id), Some(node) if node.len() > 1)
+            && !stack.iter().any(|visited| visited == id)
+            && (!id.starts_with("_:") || self.references.get(id) == Some(&1));
+
+        if !embeddable {
+            return value.clone();
+        }
+
+        stack.push(id.to_owned());
+        let node = self.embed_node(id, stack);
+        stack.pop();
+
+        Value::Object(node)
+    }
+
+    /// Collect the items of a well-formed list starting at a node
+    fn list(&self, head: &str) -> Option<Vec<Value>> {
+        let mut items = Vec::new();
+        let mut visited = HashSet::new();
+
+        let mut current = head;
+        while current != RDF_NIL {
+            if !self.is_list_node(current) || !visited.insert(current) {
+                return None;
+            }
+
+            let node = &self.nodes[current];
+            items.push(node[RDF_FIRST][0].clone());
+            current = node[RDF_REST][0].get("@id").and_then(Value::as_str)?;
+        }
+
+        Some(items)
+    }
+
+    /// Check whether a node is a blank node that holds one item of one list
+    fn is_list_node(&self, id: &str) -> bool {
+        let node = match self.nodes.get(id) {
+            Some(node) if id.starts_with("_:") => node,
+            _ => return false,
+        };
+
+        let single = |key: &str| node.get(key).and_then(Value::as_array).map(Vec::len) == Some(1);
+
+        self.references.get(id) == Some(&1)
+            && single(RDF_FIRST)
+            && single(RDF_REST)
+            && node.len() == 3
+    }
+}
+
+fn node_id(term: &Term) -> Option<String> {
+    match *term {
+        Term::Iri(ref iri) => Some(iri.clone()),
+        Term::BlankNode(ref label) => Some(format!("_:{}", label)),
+        Term::Literal(_) => None,
+    }
+}
+
+/// Convert a literal into a value object, using native JSON values for numbers and booleans
+fn literal_value(literal: &Literal) -> Value {
+    let mut object = Map::new();
+
+    if let Some(ref language) = literal.language {
+        object.insert("@value".to_owned(), Value::String(literal.value.clone()));
+        object.insert("@language".to_owned(), Value::String(language.clone()));
+        return Value::Object(object);
+    }
+
+    let datatype = literal.datatype.as_str();
+    let local = datatype.strip_prefix(XSD).unwrap_or("");
+
+    let native = match datatype {
+        XSD_STRING => None,
+        XSD_BOOLEAN => match literal.value.as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        XSD_INTEGER => integer(&literal.value),
+        XSD_DOUBLE => double(&literal.value),
+        _ if INTEGER_TYPES.contains(&local) => integer(&literal.value),
+        _ if DECIMAL_TYPES.contains(&local) => double(&literal.value),
+        _ => None,
+    };
+
+    // The datatypes JSON has native values for are implied, the rest are kept for compaction
+    let implied = match datatype {
+        XSD_STRING => true,
+        XSD_BOOLEAN | XSD_INTEGER | XSD_DOUBLE => native.is_some(),
+        _ => false,
+    };
+
+    object.insert(
+        "@value".to_owned(),
+        native.unwrap_or_else(|| Value::String(literal.value.clone())),
+    );
+    if !implied {
+        object.insert("@type".to_owned(), Value::String(literal.datatype.clone()));
+    }
+
+    Value::Object(object)
+}
+
+fn integer(lexical: &str) -> Option<Value> {
+    let lexical = lexical.trim_start_matches('+');
+
+    lexical
+        .parse::<u64>()
+        .map(Number::from)
+        .or_else(|_| lexical.parse::<i64>().map(Number::from))
+        .ok()
+        .map(Value::Number)
+}
+
+fn double(lexical: &str) -> Option<Value> {
+    lexical
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+}
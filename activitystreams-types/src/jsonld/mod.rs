@@ -0,0 +1,509 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! JSON-LD processing for documents received from other servers
+//!
+//! ActivityStreams documents are JSON-LD, so the same document can be written in many ways. A
+//! server may use compact IRIs such as `as:Note`, full IRIs, or its own `@context` with different
+//! term names. The types in this crate only understand the terms defined by the ActivityStreams
+//! context, so documents should be normalized with this module before they are deserialized.
+//!
+//! Normalizing a document expands it, removing its `@context`, and then compacts it again with
+//! the ActivityStreams and Security contexts. Copies of those contexts, and of the Identity context
+//! used by Linked Data Signatures, are bundled with the crate, so no network access is needed.
+//! Other remote contexts can be provided with `JsonLd::with_context`.
+//!
+//! Documents can also be converted into RDF with `JsonLd::to_rdf`, and with the `urdna2015`
+//! feature, into canonical N-Quads with `JsonLd::canonicalize`. Any of the crate's types can be
+//! written as N-Triples or Turtle with `to_ntriples` and `to_turtle`, and read back with
+//! `from_ntriples` and `from_turtle`.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{jsonld, object::Note};
+//!
+//! # fn main() {
+//! let document = serde_json::json!({
+//!     "@context": [
+//!         "https://www.w3.org/ns/activitystreams",
+//!         { "texte": "as:content" }
+//!     ],
+//!     "@type": "as:Note",
+//!     "@id": "https://example.com/notes/1",
+//!     "texte": "Bonjour",
+//!     "https://www.w3.org/ns/activitystreams#published": {
+//!         "@value": "2018-06-01T12:00:00Z",
+//!         "@type": "http://www.w3.org/2001/XMLSchema#dateTime"
+//!     }
+//! });
+//!
+//! let normalized = jsonld::normalize(&document).unwrap();
+//! assert_eq!(normalized, serde_json::json!({
+//!     "@context": "https://www.w3.org/ns/activitystreams",
+//!     "type": "Note",
+//!     "id": "https://example.com/notes/1",
+//!     "content": "Bonjour",
+//!     "published": "2018-06-01T12:00:00Z"
+//! }));
+//!
+//! let note: Note = jsonld::from_value(&document).unwrap();
+//! assert_eq!(note.object_props.content_string().unwrap(), "Bonjour");
+//! # }
+//! ```
+
+mod active_context;
+mod compact;
+pub(crate) mod context;
+mod expand;
+mod from_rdf;
+mod to_rdf;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{self, Value};
+use std::{collections::HashMap, error::Error, fmt, sync::OnceLock};
+
+use self::{active_context::ActiveContext, compact::Compactor};
+use rdf::{Quad, RdfError};
+
+pub use self::context::{Context, ContextBuilder, ContextEntry, InlineContext, TermDefinition};
+
+/// The IRI of the ActivityStreams context
+pub const ACTIVITYSTREAMS_CONTEXT: &str = "https://www.w3.org/ns/activitystreams";
+
+/// The IRI of the Security Vocabulary context
+pub const SECURITY_CONTEXT: &str = "https://w3id.org/security/v1";
+
+/// The IRI of the Identity context, used for the options of Linked Data Signatures
+pub const IDENTITY_CONTEXT: &str = "https://w3id.org/identity/v1";
+
+const ACTIVITYSTREAMS_DOCUMENT: &str = include_str!("contexts/activitystreams.jsonld");
+const SECURITY_DOCUMENT: &str = include_str!("contexts/security-v1.jsonld");
+const IDENTITY_DOCUMENT: &str = include_str!("contexts/identity-v1.jsonld");
+
+/// The bundled contexts, parsed the first time a processor looks one up
+fn bundled_contexts() -> &'static HashMap<&'static str, Value> {
+    static BUNDLED: OnceLock<HashMap<&'static str, Value>> = OnceLock::new();
+
+    BUNDLED.get_or_init(|| {
+        let activitystreams: Value =
+            serde_json::from_str(ACTIVITYSTREAMS_DOCUMENT).expect("bundled context is valid");
+        let security: Value =
+            serde_json::from_str(SECURITY_DOCUMENT).expect("bundled context is valid");
+        let identity: Value =
+            serde_json::from_str(IDENTITY_DOCUMENT).expect("bundled context is valid");
+
+        let mut contexts = HashMap::new();
+        contexts.insert(ACTIVITYSTREAMS_CONTEXT, activitystreams.clone());
+        contexts.insert(
+            "https://www.w3.org/ns/activitystreams.jsonld",
+            activitystreams.clone(),
+        );
+        contexts.insert("http://www.w3.org/ns/activitystreams", activitystreams);
+        contexts.insert(SECURITY_CONTEXT, security.clone());
+        contexts.insert("https://w3id.org/security/v1.jsonld", security);
+        contexts.insert(IDENTITY_CONTEXT, identity);
+        contexts
+    })
+}
+
+/// A JSON-LD processor with a fixed set of known remote contexts
+///
+/// Remote contexts are never fetched. A document that refers to a context that is not known is
+/// rejected, unless `allow_unknown_contexts` is used, in which case the context is skipped and
+/// the terms it would define are kept as they are.
+#[derive(Clone, Debug)]
+pub struct JsonLd {
+    contexts: HashMap<String, Value>,
+    allow_unknown: bool,
+}
+
+impl JsonLd {
+    /// Create a processor that knows the bundled ActivityStreams, Security and Identity contexts
+    pub fn new() -> Self {
+        JsonLd {
+            contexts: HashMap::new(),
+            allow_unknown: false,
+        }
+    }
+
+    /// Provide the document for a remote context
+    ///
+    /// The document must be an object with a `@context` property, as it would be served from the
+    /// context's IRI. It takes the place of a bundled context with the same IRI.
+    pub fn with_context<T>(mut self, iri: T, document: Value) -> Self
+    where
+        T: Into<String>,
+    {
+        self.contexts.insert(iri.into(), document);
+        self
+    }
+
+    /// Skip remote contexts that are not known, rather than rejecting the document
+    pub fn allow_unknown_contexts(mut self) -> Self {
+        self.allow_unknown = true;
+        self
+    }
+
+    /// Expand a document, producing an array of node objects that use only absolute IRIs
+    pub fn expand(&self, document: &Value) -> Result<Value, JsonLdError> {
+        expand::expand(self, document)
+    }
+
+    /// Compact an expanded document with the ActivityStreams and Security contexts
+    ///
+    /// Identifiers are left as absolute IRIs, rather than being compacted into compact IRIs such
+    /// as `as:Public`.
+    pub fn compact(&self, expanded: &Value) -> Result<Value, JsonLdError> {
+        let base = ActiveContext::default()
+            .process(self, &Value::String(ACTIVITYSTREAMS_CONTEXT.to_owned()))?;
+        let ctx = base.process(self, &Value::String(SECURITY_CONTEXT.to_owned()))?;
+
+        Ok(Compactor::new(&ctx, &base).compact(expanded))
+    }
+
+    /// Rewrite a document to use the terms defined by the ActivityStreams and Security contexts
+    pub fn normalize(&self, document: &Value) -> Result<Value, JsonLdError> {
+        self.compact(&self.expand(document)?)
+    }
+
+    /// Normalize a document and deserialize it into one of this crate's types
+    pub fn from_value<T>(&self, document: &Value) -> Result<T, JsonLdError>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_value(self.normalize(document)?).map_err(JsonLdError::Deserialize)
+    }
+
+    /// Convert a document into the RDF statements it makes
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// #[macro_use]
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::{jsonld::JsonLd, rdf};
+    ///
+    /// # fn main() {
+    /// let quads = JsonLd::new()
+    ///     .to_rdf(&json!({
+    ///         "@context": "https://www.w3.org/ns/activitystreams",
+    ///         "id": "https://example.com/notes/1",
+    ///         "type": "Note",
+    ///         "published": "2018-06-01T12:00:00Z"
+    ///     }))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     rdf::to_nquads(&quads),
+    ///     "<https://example.com/notes/1> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+    ///      <https://www.w3.org/ns/activitystreams#Note> .\n\
+    ///      <https://example.com/notes/1> <https://www.w3.org/ns/activitystreams#published> \
+    ///      \"2018-06-01T12:00:00Z\"^^<http://www.w3.org/2001/XMLSchema#dateTime> .\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn to_rdf(&self, document: &Value) -> Result<Vec<Quad>, JsonLdError> {
+        Ok(to_rdf::to_rdf(&self.expand(document)?))
+    }
+
+    /// Convert a document into RDF and canonicalize it with URDNA2015, producing N-Quads
+    ///
+    /// This is only available with the `urdna2015` feature.
+    #[cfg(feature = "urdna2015")]
+    pub fn canonicalize(&self, document: &Value) -> Result<String, JsonLdError> {
+        ::rdf::urdna2015::canonicalize_to_nquads(&self.to_rdf(document)?).map_err(JsonLdError::Rdf)
+    }
+
+    /// Serialize an object or link into the RDF statements it makes
+    ///
+    /// The ActivityStreams and Security contexts are used if the serialized form has no
+    /// `@context` of its own.
+    pub fn serialize_rdf<T>(&self, object: &T) -> Result<Vec<Quad>, JsonLdError>
+    where
+        T: Serialize,
+    {
+        let mut document = serde_json::to_value(object).map_err(JsonLdError::Serialize)?;
+
+        if let Value::Object(ref mut map) = document {
+            if !map.contains_key("@context") {
+                map.insert(
+                    "@context".to_owned(),
+                    serde_json::json!([ACTIVITYSTREAMS_CONTEXT, SECURITY_CONTEXT]),
+                );
+            }
+        }
+
+        self.to_rdf(&document)
+    }
+
+    /// Serialize an object or link as N-Triples
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    ///
+    /// use activitystreams_types::{collection::OrderedCollection, jsonld::JsonLd};
+    ///
+    /// # fn main() {
+    /// let mut collection = OrderedCollection::default();
+    /// collection.object_props.set_id_string("https://example.com/outbox".to_owned()).unwrap();
+    /// collection.collection_props.set_total_items_u64(2).unwrap();
+    ///
+    /// assert_eq!(
+    ///     JsonLd::new().to_ntriples(&collection).unwrap(),
+    ///     "<https://example.com/outbox> <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> \
+    ///      <https://www.w3.org/ns/activitystreams#OrderedCollection> .\n\
+    ///      <https://example.com/outbox> <https://www.w3.org/ns/activitystreams#totalItems> \
+    ///      \"2\"^^<http://www.w3.org/2001/XMLSchema#nonNegativeInteger> .\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn to_ntriples<T>(&self, object: &T) -> Result<String, JsonLdError>
+    where
+        T: Serialize,
+    {
+        Ok(::rdf::to_ntriples(&self.serialize_rdf(object)?))
+    }
+
+    /// Serialize an object or link as Turtle
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    ///
+    /// use activitystreams_types::{jsonld::JsonLd, object::Note};
+    ///
+    /// # fn main() {
+    /// let mut note = Note::default();
+    /// note.object_props.set_id_string("https://example.com/notes/1".to_owned()).unwrap();
+    /// note.object_props.set_content_string("Hello".to_owned()).unwrap();
+    /// note.object_props.set_published_string("2018-06-01T12:00:00Z".to_owned()).unwrap();
+    ///
+    /// assert_eq!(
+    ///     JsonLd::new().to_turtle(&note).unwrap(),
+    ///     "@prefix as: <https://www.w3.org/ns/activitystreams#> .\n\
+    ///      @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+    ///      \n\
+    ///      <https://example.com/notes/1> a as:Note ;\n    \
+    ///      as:content \"Hello\" ;\n    \
+    ///      as:published \"2018-06-01T12:00:00Z\"^^xsd:dateTime .\n"
+    /// );
+    /// # }
+    /// ```
+    pub fn to_turtle<T>(&self, object: &T) -> Result<String, JsonLdError>
+    where
+        T: Serialize,
+    {
+        Ok(::rdf::to_turtle(&self.serialize_rdf(object)?))
+    }
+
+    /// Rebuild one of this crate's types from RDF statements
+    ///
+    /// The statements must describe a single top-level node, one that no other node refers to.
+    /// The nodes and lists it refers to are embedded in it before it is compacted and
+    /// deserialized.
+    pub fn from_rdf<T>(&self, quads: &[Quad]) -> Result<T, JsonLdError>
+    where
+        T: DeserializeOwned,
+    {
+        let compacted = self.compact(&from_rdf::from_rdf(quads)?)?;
+        serde_json::from_value(compacted).map_err(JsonLdError::Deserialize)
+    }
+
+    /// Parse an N-Triples document into one of this crate's types
+    pub fn from_ntriples<T>(&self, document: &str) -> Result<T, JsonLdError>
+    where
+        T: DeserializeOwned,
+    {
+        self.from_rdf(&::rdf::parse_nquads(document).map_err(JsonLdError::Rdf)?)
+    }
+
+    /// Parse a Turtle document into one of this crate's types
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    ///
+    /// use activitystreams_types::{collection::OrderedCollection, jsonld::JsonLd};
+    ///
+    /// # fn main() {
+    /// let collection: OrderedCollection = JsonLd::new()
+    ///     .from_turtle(
+    ///         r#"
+    ///         @prefix as: <https://www.w3.org/ns/activitystreams#> .
+    ///
+    ///         <https://example.com/outbox> a as:OrderedCollection ;
+    ///             as:totalItems 2 ;
+    ///             as:items <https://example.com/notes/2>, <https://example.com/notes/1> .
+    ///         "#,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// assert_eq!(*collection.collection_props.total_items_u64().unwrap(), 2);
+    /// # }
+    /// ```
+    pub fn from_turtle<T>(&self, document: &str) -> Result<T, JsonLdError>
+    where
+        T: DeserializeOwned,
+    {
+        self.from_rdf(&::rdf::parse_turtle(document).map_err(JsonLdError::Rdf)?)
+    }
+
+    /// Look up a remote context, returning `None` if it is unknown and may be skipped
+    fn remote_context(&self, iri: &str) -> Result<Option<&Value>, JsonLdError> {
+        match self
+            .contexts
+            .get(iri)
+            .or_else(|| bundled_contexts().get(iri))
+        {
+            Some(document) => Ok(Some(document)),
+            None if self.allow_unknown => Ok(None),
+            None => Err(JsonLdError::UnknownContext(iri.to_owned())),
+        }
+    }
+}
+
+impl Default for JsonLd {
+    fn default() -> Self {
+        JsonLd::new()
+    }
+}
+
+/// Expand a document with the default processor
+pub fn expand(document: &Value) -> Result<Value, JsonLdError> {
+    JsonLd::new().expand(document)
+}
+
+/// Compact an expanded document with the default processor
+pub fn compact(expanded: &Value) -> Result<Value, JsonLdError> {
+    JsonLd::new().compact(expanded)
+}
+
+/// Normalize a document with the default processor
+pub fn normalize(document: &Value) -> Result<Value, JsonLdError> {
+    JsonLd::new().normalize(document)
+}
+
+/// Normalize a document with the default processor and deserialize it
+pub fn from_value<T>(document: &Value) -> Result<T, JsonLdError>
+where
+    T: DeserializeOwned,
+{
+    JsonLd::new().from_value(document)
+}
+
+/// Convert a document into RDF with the default processor
+pub fn to_rdf(document: &Value) -> Result<Vec<Quad>, JsonLdError> {
+    JsonLd::new().to_rdf(document)
+}
+
+/// Canonicalize a document with the default processor
+///
+/// This is only available with the `urdna2015` feature.
+#[cfg(feature = "urdna2015")]
+pub fn canonicalize(document: &Value) -> Result<String, JsonLdError> {
+    JsonLd::new().canonicalize(document)
+}
+
+/// Serialize an object or link as N-Triples with the default processor
+pub fn to_ntriples<T>(object: &T) -> Result<String, JsonLdError>
+where
+    T: Serialize,
+{
+    JsonLd::new().to_ntriples(object)
+}
+
+/// Serialize an object or link as Turtle with the default processor
+pub fn to_turtle<T>(object: &T) -> Result<String, JsonLdError>
+where
+    T: Serialize,
+{
+    JsonLd::new().to_turtle(object)
+}
+
+/// Parse an N-Triples document into one of this crate's types with the default processor
+pub fn from_ntriples<T>(document: &str) -> Result<T, JsonLdError>
+where
+    T: DeserializeOwned,
+{
+    JsonLd::new().from_ntriples(document)
+}
+
+/// Parse a Turtle document into one of this crate's types with the default processor
+pub fn from_turtle<T>(document: &str) -> Result<T, JsonLdError>
+where
+    T: DeserializeOwned,
+{
+    JsonLd::new().from_turtle(document)
+}
+
+/// Parse, normalize and deserialize a document with the default processor
+pub fn from_str<T>(document: &str) -> Result<T, JsonLdError>
+where
+    T: DeserializeOwned,
+{
+    let document = serde_json::from_str(document).map_err(JsonLdError::Deserialize)?;
+    from_value(&document)
+}
+
+/// The errors that can occur while processing a JSON-LD document
+#[derive(Debug)]
+pub enum JsonLdError {
+    /// The document refers to a remote context that is not known
+    UnknownContext(String),
+
+    /// A `@context` is not valid
+    InvalidContext(String),
+
+    /// The document is not valid JSON-LD
+    InvalidDocument(String),
+
+    /// The normalized document could not be deserialized into the requested type
+    Deserialize(serde_json::Error),
+
+    /// A value could not be serialized into a document
+    Serialize(serde_json::Error),
+
+    /// The RDF form of the document could not be processed
+    Rdf(RdfError),
+}
+
+impl fmt::Display for JsonLdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonLdError::UnknownContext(ref iri) => write!(f, "Unknown remote context {}", iri),
+            JsonLdError::InvalidContext(ref message) => write!(f, "Invalid @context, {}", message),
+            JsonLdError::InvalidDocument(ref message) => {
+                write!(f, "Invalid JSON-LD document, {}", message)
+            }
+            JsonLdError::Deserialize(ref e) => write!(f, "Failed to deserialize document, {}", e),
+            JsonLdError::Serialize(ref e) => write!(f, "Failed to serialize document, {}", e),
+            JsonLdError::Rdf(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for JsonLdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            JsonLdError::Deserialize(ref e) | JsonLdError::Serialize(ref e) => Some(e),
+            JsonLdError::Rdf(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,248 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! The JSON-LD to RDF algorithm
+//!
+//! Each node object of an expanded document becomes a subject, and each of its property values
+//! becomes an object. Nodes without an `@id` are given blank node labels, and lists are written
+//! as chains of `rdf:first` and `rdf:rest` statements.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use rdf::{
+    Literal, Quad, Term, RDF_FIRST, RDF_LANG_STRING, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN,
+    XSD_DOUBLE, XSD_INTEGER, XSD_STRING,
+};
+
+/// Convert an expanded document into quads
+pub(super) fn to_rdf(expanded: &Value) -> Vec<Quad> {
+    let mut converter = Converter::default();
+
+    for node in as_items(expanded) {
+        if let Value::Object(ref node) = *node {
+            converter.node(node, None);
+        }
+    }
+
+    converter.quads
+}
+
+#[derive(Default)]
+struct Converter {
+    quads: Vec<Quad>,
+    blank_nodes: HashMap<String, String>,
+    counter: usize,
+}
+
+impl Converter {
+    /// Write the statements about a node object, returning the term naming it
+    fn node(&mut self, node: &Map<String, Value>, graph: Option<&Term>) -> Option<Term> {
+        let subject = match node.get("@id").and_then(Value::as_str) {
+            Some(id) => self.identifier(id),
+            None => Some(self.new_blank_node()),
+        };
+
+        if let Some(nodes) = node.get("@graph") {
+            // A node with an identifier names the graph it contains, otherwise the nodes belong
+            // to the graph the node is in
+            let inner = match subject {
+                Some(ref subject) if node.contains_key("@id") => Some(subject.clone()),
+                _ => graph.cloned(),
+            };
+
+            for item in as_items(nodes) {
+                if let Value::Object(ref item) = *item {
+                    self.node(item, inner.as_ref());
+                }
+            }
+        }
+
+        let subject = subject?;
+
+        for kind in node.get("@type").map(as_items).unwrap_or_default() {
+            if let Some(kind) = kind.as_str().and_then(|kind| self.identifier(kind)) {
+                self.push(subject.clone(), Term::iri(RDF_TYPE), kind, graph);
+            }
+        }
+
+        for (property, values) in node {
+            if property.starts_with('@') || property.starts_with("_:") || !is_absolute(property) {
+                continue;
+            }
+
+            for value in as_items(values) {
+                if let Some(object) = self.object(value, graph) {
+                    self.push(subject.clone(), Term::iri(property.as_str()), object, graph);
+                }
+            }
+        }
+
+        Some(subject)
+    }
+
+    /// Convert a property value into the term it stands for, writing any statements it contains
+    fn object(&mut self, value: &Value, graph: Option<&Term>) -> Option<Term> {
+        let object = value.as_object()?;
+
+        if let Some(items) = object.get("@list") {
+            return self.list(as_items(items), graph);
+        }
+
+        if let Some(value) = object.get("@value") {
+            let datatype = object.get("@type").and_then(Value::as_str);
+            let language = object.get("@language").and_then(Value::as_str);
+            return literal(value, datatype, language).map(Term::Literal);
+        }
+
+        self.node(object, graph)
+    }
+
+    fn list(&mut self, items: Vec<&Value>, graph: Option<&Term>) -> Option<Term> {
+        let items: Vec<Term> = items
+            .into_iter()
+            .filter_map(|item| self.object(item, graph))
+            .collect();
+
+        let nodes: Vec<Term> = items.iter().map(|_| self.new_blank_node()).collect();
+
+        for (index, item) in items.into_iter().enumerate() {
+            let rest = match nodes.get(index + 1) {
+                Some(next) => next.clone(),
+                None => Term::iri(RDF_NIL),
+            };
+
+            self.push(nodes[index].clone(), Term::iri(RDF_FIRST), item, graph);
+            self.push(nodes[index].clone(), Term::iri(RDF_REST), rest, graph);
+        }
+
+        Some(
+            nodes
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| Term::iri(RDF_NIL)),
+        )
+    }
+
+    /// Convert an identifier into a term, relabelling blank nodes and skipping relative IRIs
+    fn identifier(&mut self, id: &str) -> Option<Term> {
+        if id.starts_with("_:") {
+            if let Some(label) = self.blank_nodes.get(id) {
+                return Some(Term::BlankNode(label.clone()));
+            }
+
+            let term = self.new_blank_node();
+            if let Term::BlankNode(ref label) = term {
+                self.blank_nodes.insert(id.to_owned(), label.clone());
+            }
+            return Some(term);
+        }
+
+        if is_absolute(id) {
+            Some(Term::iri(id))
+        } else {
+            None
+        }
+    }
+
+    fn new_blank_node(&mut self) -> Term {
+        let term = Term::BlankNode(format!("b{}", self.counter));
+        self.counter += 1;
+        term
+    }
+
+    fn push(&mut self, subject: Term, predicate: Term, object: Term, graph: Option<&Term>) {
+        self.quads.push(Quad {
+            subject,
+            predicate,
+            object,
+            graph: graph.cloned(),
+        });
+    }
+}
+
+/// Convert a value object into a literal, using the canonical forms of numbers and booleans
+fn literal(value: &Value, datatype: Option<&str>, language: Option<&str>) -> Option<Literal> {
+    let (lexical, default_datatype) = match *value {
+        Value::String(ref value) => (value.clone(), XSD_STRING),
+        Value::Bool(value) => (value.to_string(), XSD_BOOLEAN),
+        Value::Number(ref number) => {
+            let is_double = number.is_f64() || datatype == Some(XSD_DOUBLE);
+
+            match (number.as_i64(), number.as_u64(), number.as_f64()) {
+                (Some(n), _, _) if !is_double => (n.to_string(), XSD_INTEGER),
+                (_, Some(n), _) if !is_double => (n.to_string(), XSD_INTEGER),
+                // Whole numbers written as floats, such as 5.0, are still integers in JSON-LD
+                (_, _, Some(n))
+                    if n.fract() == 0.0 && n.abs() < 1e21 && datatype != Some(XSD_DOUBLE) =>
+                {
+                    (format!("{}", n as i64), XSD_INTEGER)
+                }
+                (_, _, Some(n)) => (canonical_double(n), XSD_DOUBLE),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(match language {
+        Some(language) if datatype.is_none() && value.is_string() => Literal {
+            value: lexical,
+            datatype: RDF_LANG_STRING.to_owned(),
+            language: Some(language.to_owned()),
+        },
+        _ => Literal::typed(lexical, datatype.unwrap_or(default_datatype)),
+    })
+}
+
+/// Write a double in the canonical `xsd:double` form used by JSON-LD, such as `1.5E1`
+fn canonical_double(n: f64) -> String {
+    let formatted = format!("{:.15E}", n);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('E').unwrap_or(formatted.len()));
+
+    let mantissa = mantissa.trim_end_matches('0');
+    let mantissa = if mantissa.ends_with('.') {
+        format!("{}0", mantissa)
+    } else {
+        mantissa.to_owned()
+    };
+
+    format!("{}{}", mantissa, exponent)
+}
+
+fn as_items(value: &Value) -> Vec<&Value> {
+    match *value {
+        Value::Array(ref items) => items.iter().collect(),
+        ref item => vec![item],
+    }
+}
+
+fn is_absolute(iri: &str) -> bool {
+    match iri.find(':') {
+        Some(index) => {
+            let scheme = &iri[..index];
+            index > 0
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
@@ -50,12 +50,25 @@
 #[macro_use]
 extern crate activitystreams_derive;
 extern crate activitystreams_traits;
+#[cfg(any(feature = "http-signatures", feature = "ld-signatures"))]
+extern crate base64;
+#[cfg(feature = "object-integrity")]
+extern crate bs58;
 extern crate chrono;
+#[cfg(feature = "object-integrity")]
+extern crate ed25519_dalek;
 extern crate mime;
+#[cfg(feature = "rsa")]
+extern crate rsa;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
 extern crate serde;
 #[macro_use]
 extern crate serde_derive;
 extern crate serde_json;
+#[cfg(any(feature = "urdna2015", feature = "object-integrity"))]
+extern crate sha2;
+extern crate url;
 
 /// Define a simple wrapper around a string for this crate's main Context type
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -68,11 +81,30 @@ pub fn context() -> ContextObject {
     ContextObject("https://www.w3.org/ns/activitystreams".to_owned())
 }
 
+/// The context for the Security Vocabulary, which defines the `publicKey` property of actors.
+pub fn security_context() -> ContextObject {
+    ContextObject("https://w3id.org/security/v1".to_owned())
+}
+
+#[macro_use]
+mod macros;
+
 pub mod activity;
 pub mod actor;
+pub mod addressing;
 pub mod collection;
 mod custom_props;
+pub mod inbox;
+pub mod jsonld;
 pub mod link;
 pub mod object;
+pub mod origin;
+pub mod primitives;
+pub mod rdf;
+pub mod resolve;
+pub mod security;
+pub mod store;
+pub mod strict;
+pub mod validate;
 
-pub use self::custom_props::{CustomLink, CustomObject};
+pub use self::custom_props::{CustomLink, CustomObject, WithUnknown};
@@ -17,9 +17,10 @@
  * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Namespace for Unit Structs that serialize to strings
+//! Namespace for the structs that serialize to the `type` of each link, like those of
+//! `object::kind`
 
-/// A Unit Struct that represents the string "Mention"
+/// Represents the type "Mention", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Mention)]
-pub struct MentionType;
+pub struct MentionType(Vec<String>);
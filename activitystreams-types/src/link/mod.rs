@@ -56,3 +56,23 @@ impl LinkExt for Mention {
         &mut self.link_props
     }
 }
+
+vocabulary! {
+    Mention: "Mention" => [LinkProperties],
+}
+
+any_type! {
+    /// Any of the Link types defined in this crate
+    ///
+    /// When deserializing, the `type` field is used to decide which Link type to produce. Links
+    /// with a `type` that is not recognised, including plain `Link` objects, are kept as
+    /// `AnyLink::Unknown`.
+    pub enum AnyLink {
+        "Mention" => Mention,
+    }
+
+    /// Borrow the link properties of the contained Link
+    LinkExt::props -> LinkProperties as link_props, link_props_mut;
+}
+
+impl Link for AnyLink {}
@@ -49,8 +49,13 @@
 //! # fn main() {}
 //! ```
 
-use activitystreams_traits::{Error, Link, Object, Result};
+use activitystreams_traits::{
+    properties::{Either, OneOrMany},
+    Error, ErrorKind, Link, Object, Result,
+};
+use jsonld::{self, Context};
 use mime;
+use primitives::{for_language, parse_property, read_property, LanguageMap, XsdAnyUri};
 use serde_json;
 
 /// Define all the properties of the Object base type as described by the Activity Streams
@@ -72,17 +77,18 @@ use serde_json;
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
 #[serde(rename_all = "camelCase")]
 pub struct LinkProperties {
-    // TODO: IRI type
     /// Provides the globally unique identifier for an Object or Link.
     ///
-    /// The `id` property is expressed as an absolute IRI in the spec, but for now is represented
-    /// as a string.
+    /// The `id` property is expressed as an absolute IRI. A relative or malformed `id` in received
+    /// JSON is kept as it is, so that only `id_xsdanyuri` fails for it, while setting one with
+    /// `set_id_string` fails with an `ErrorKind::InvalidValue` error whose source explains why it
+    /// was rejected.
     ///
     /// - Range: `anyUri`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(concrete(String), functional)]
-    pub id: Option<serde_json::Value>,
+    #[activitystreams(concrete(XsdAnyUri), functional)]
+    pub id: Option<Either<XsdAnyUri, serde_json::Value>>,
 
     /// Identifies the context within which the object exists or an activity was performed.
     ///
@@ -94,9 +100,8 @@ pub struct LinkProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none", rename = "@context")]
     #[activitystreams(ab(Object, Link))]
-    pub context: Option<serde_json::Value>,
+    pub context: Option<OneOrMany<serde_json::Value>>,
 
-    // TODO: rdf:langString
     /// A simple, human-readable, plain-text name for the object.
     ///
     /// HTML markup MUST NOT be included. The name MAY be expressed using multiple language-tagged
@@ -106,15 +111,29 @@ pub struct LinkProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String))]
-    pub name: Option<serde_json::Value>,
+    pub name: Option<OneOrMany<Either<String, serde_json::Value>>>,
+
+    /// The name of the object, keyed by language.
+    ///
+    /// This is the language-tagged form of `name`, as it is sent by servers that support
+    /// multiple languages.
+    ///
+    /// - Range: `rdf:langString`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(LanguageMap), functional)]
+    pub name_map: Option<LanguageMap>,
 
     /// The target resource pointed to by a Link.
     ///
+    /// Like `id`, a relative or malformed `href` in received JSON is kept as it is, while setting
+    /// one with `set_href_string` fails with an `ErrorKind::InvalidValue` error.
+    ///
     /// - Range: `xsd:anyUri`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(concrete(String), functional)]
-    pub href: Option<serde_json::Value>,
+    #[activitystreams(concrete(XsdAnyUri), functional)]
+    pub href: Option<Either<XsdAnyUri, serde_json::Value>>,
 
     // TODO: lang enum
     /// Hints as to the language used by the target resource.
@@ -123,11 +142,11 @@ pub struct LinkProperties {
     /// Language-Tag.
     ///
     /// - Range: [[BCP47](https://tools.ietf.org/html/bcp47)] Language
-    /// Tag
+    ///   Tag
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String), functional)]
-    pub hreflang: Option<serde_json::Value>,
+    pub hreflang: Option<Either<String, serde_json::Value>>,
 
     /// When used on a `Link`, identifies the MIME media type of the referenced resource.
     ///
@@ -137,7 +156,7 @@ pub struct LinkProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String), functional)]
-    pub media_type: Option<serde_json::Value>,
+    pub media_type: Option<Either<String, serde_json::Value>>,
 
     /// A link relation associated with a Link.
     ///
@@ -150,12 +169,12 @@ pub struct LinkProperties {
     /// (U+000D) or "," (U+002C) characters can be used as a valid link relation.
     ///
     /// - Range:
-    ///     [[RFC5988](https://tools.ietf.org/html/rfc5988)] or
-    ///     [[HTML5](https://www.w3.org/TR/html5/)] Link Relation
+    ///   [[RFC5988](https://tools.ietf.org/html/rfc5988)] or
+    ///   [[HTML5](https://www.w3.org/TR/html5/)] Link Relation
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String))]
-    pub rel: Option<serde_json::Value>,
+    pub rel: Option<OneOrMany<Either<String, serde_json::Value>>>,
 
     /// On a `Link`, specifies a hint as to the rendering height in device-independent pixels of the
     /// linked resource.
@@ -164,7 +183,7 @@ pub struct LinkProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(u64), functional)]
-    pub height: Option<serde_json::Value>,
+    pub height: Option<Either<u64, serde_json::Value>>,
 
     /// On a `Link`, specifies a hint as to the rendering width in device-independent pixels of the
     /// linked resource.
@@ -173,7 +192,7 @@ pub struct LinkProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(u64), functional)]
-    pub width: Option<serde_json::Value>,
+    pub width: Option<Either<u64, serde_json::Value>>,
 
     /// Identifies an entity that provides a preview of this object.
     ///
@@ -181,13 +200,70 @@ pub struct LinkProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub preview: Option<serde_json::Value>,
+    pub preview: Option<OneOrMany<serde_json::Value>>,
 }
 
 impl LinkProperties {
+    /// Fetch the `id` as a string, even if it is not a valid IRI
+    ///
+    /// Possible errors from this method are of the kinds `ErrorKind::NotFound` and
+    /// `ErrorKind::Deserialize`
+    pub fn id_string(&self) -> Result<String> {
+        read_property(self.id.as_ref(), "id")
+    }
+
+    /// Set the `id` from a string, which must be an absolute IRI
+    ///
+    /// Possible errors from this method are of the kind `ErrorKind::InvalidValue`
+    pub fn set_id_string(&mut self, id: String) -> Result<()> {
+        self.id = Some(parse_property(&id, "id")?);
+        Ok(())
+    }
+
+    /// Fetch the `href` as a string, even if it is not a valid IRI
+    ///
+    /// Possible errors from this method are of the kinds `ErrorKind::NotFound` and
+    /// `ErrorKind::Deserialize`
+    pub fn href_string(&self) -> Result<String> {
+        read_property(self.href.as_ref(), "href")
+    }
+
+    /// Set the `href` from a string, which must be an absolute IRI
+    ///
+    /// Possible errors from this method are of the kind `ErrorKind::InvalidValue`
+    pub fn set_href_string(&mut self, href: String) -> Result<()> {
+        self.href = Some(parse_property(&href, "href")?);
+        Ok(())
+    }
+
+    /// Fetch the `@context` as a typed `Context`
+    pub fn json_ld_context(&self) -> Result<Context> {
+        jsonld::context::from_field(self.context.as_ref())
+    }
+
+    /// Set the `@context` from a typed `Context`
+    pub fn set_json_ld_context(&mut self, context: Context) -> Result<()> {
+        self.context = Some(jsonld::context::into_field(&context)?);
+        Ok(())
+    }
+
     /// Fetch a typed `Mime` struct from the `media_type` field.
     pub fn media_type(&self) -> Result<mime::Mime> {
-        self.media_type_string()
-            .and_then(|s| s.parse().map_err(|_| Error::Deserialize))
+        self.media_type_string().and_then(|s| {
+            s.parse()
+                .map_err(|_| Error::new(ErrorKind::Deserialize).for_property("media_type", "Mime"))
+        })
+    }
+
+    /// Fetch the `name` that best matches a list of languages, in order of preference.
+    ///
+    /// `nameMap` is searched first, falling back to the plain `name` and then to any language
+    /// in `nameMap`.
+    pub fn name_for_language<S>(&self, languages: &[S]) -> Result<&str>
+    where
+        S: AsRef<str>,
+    {
+        for_language(self.name.as_ref(), self.name_map.as_ref(), languages)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound).for_property("name", "str"))
     }
 }
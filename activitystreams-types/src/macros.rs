@@ -0,0 +1,199 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Macros used to generate repetitive parts of this crate
+
+/// Generate an enum that can hold any of the given types, dispatching on the `type` field when
+/// deserializing.
+///
+/// When `type` is an array, the first recognised entry picks the variant, and the typed value
+/// keeps the whole array. Values with an unrecognised `type` are kept in the `Unknown` variant.
+/// Any number of property accessors can be generated after the enum definition, each of which
+/// forwards to the given extension trait.
+macro_rules! any_type {
+    (@accessors $name:ident [$($variant:ident),*]) => {};
+
+    (
+        @accessors $name:ident [$($variant:ident),*]
+        $(#[$meta:meta])*
+        $ext:ident::props -> $props:ident as $get:ident, $get_mut:ident;
+
+        $($rest:tt)*
+    ) => {
+        impl $name {
+            $(#[$meta])*
+            ///
+            /// Returns `None` if the contained value has an unrecognised `type`
+            pub fn $get(&self) -> Option<&$props> {
+                match *self {
+                    $($name::$variant(ref item) => Some($ext::props(item)),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+
+            $(#[$meta])*
+            ///
+            /// Returns `None` if the contained value has an unrecognised `type`
+            pub fn $get_mut(&mut self) -> Option<&mut $props> {
+                match *self {
+                    $($name::$variant(ref mut item) => Some($ext::props_mut(item)),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+        }
+
+        any_type!(@accessors $name [$($variant),*] $($rest)*);
+    };
+
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($kind:literal => $variant:ident,)*
+        }
+
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum $name {
+            $($variant($variant),)*
+            /// Holds any value whose `type` is not recognised
+            Unknown(::serde_json::Value),
+        }
+
+        impl $name {
+            /// The `type`s that pick one of the variants
+            pub const KINDS: &'static [&'static str] = &[$($kind),*];
+
+            /// Fetch the `type` of the contained value, if one is present
+            pub fn kind(&self) -> Option<&str> {
+                match *self {
+                    $($name::$variant(_) => Some($kind),)*
+                    $name::Unknown(ref value) => match value.get("type") {
+                        Some(::serde_json::Value::Array(kinds)) => {
+                            kinds.iter().find_map(|kind| kind.as_str())
+                        }
+                        Some(kind) => kind.as_str(),
+                        None => None,
+                    },
+                }
+            }
+
+            /// Check whether the contained value has an unrecognised `type`
+            pub fn is_unknown(&self) -> bool {
+                match *self {
+                    $name::Unknown(_) => true,
+                    _ => false,
+                }
+            }
+        }
+
+        $(
+            impl From<$variant> for $name {
+                fn from(item: $variant) -> Self {
+                    $name::$variant(item)
+                }
+            }
+        )*
+
+        impl ::serde::ser::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::ser::Serializer,
+            {
+                match *self {
+                    $($name::$variant(ref item) => item.serialize(serializer),)*
+                    $name::Unknown(ref value) => value.serialize(serializer),
+                }
+            }
+        }
+
+        impl<'de> ::serde::de::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: ::serde::de::Deserializer<'de>,
+            {
+                let value: ::serde_json::Value =
+                    ::serde::de::Deserialize::deserialize(deserializer)?;
+
+                // JSON-LD allows several types, in which case the first recognised one is used
+                let kind = match value.get("type") {
+                    Some(::serde_json::Value::Array(kinds)) => kinds
+                        .iter()
+                        .filter_map(|kind| kind.as_str())
+                        .find(|kind| $name::KINDS.contains(kind)),
+                    Some(kind) => kind.as_str(),
+                    None => None,
+                }
+                .map(|kind| kind.to_owned());
+
+                match kind.as_ref().map(|kind| kind.as_str()) {
+                    $(
+                        Some($kind) => ::serde_json::from_value(value)
+                            .map($name::$variant)
+                            .map_err(::serde::de::Error::custom),
+                    )*
+                    _ => Ok($name::Unknown(value)),
+                }
+            }
+        }
+
+        impl ::activitystreams_traits::properties::Vocabulary for $name {
+            fn properties(
+                kinds: &[&str],
+            ) -> Option<Vec<&'static ::activitystreams_traits::properties::PropertyInfo>> {
+                match kinds.iter().find(|kind| $name::KINDS.contains(kind)) {
+                    $(
+                        Some(&$kind) => {
+                            <$variant as ::activitystreams_traits::properties::Vocabulary>
+                                ::properties(kinds)
+                        }
+                    )*
+                    _ => None,
+                }
+            }
+        }
+
+        any_type!(@accessors $name [$($variant),*] $($rest)*);
+    };
+}
+
+/// Implement `Vocabulary` for types with the given `type`, from the `PROPERTIES` tables of the
+/// structs they flatten
+macro_rules! vocabulary {
+    ($($name:ident: $kind:literal => [$($props:ty),*],)*) => {
+        $(
+            impl ::activitystreams_traits::properties::Vocabulary for $name {
+                const TYPE: &'static str = concat!("`", $kind, "`");
+
+                fn properties(
+                    _: &[&str],
+                ) -> Option<Vec<&'static ::activitystreams_traits::properties::PropertyInfo>> {
+                    Some(
+                        [$(<$props>::PROPERTIES),*]
+                            .iter()
+                            .flat_map(|properties| properties.iter())
+                            .collect(),
+                    )
+                }
+            }
+        )*
+    };
+}
@@ -17,64 +17,68 @@
  * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
  */
 
-//! Namespace for Unit Structs that serialize to strings
+//! Namespace for the structs that serialize to the `type` of each object
+//!
+//! Each struct serializes to its type as a string. When a value is given several types, such as
+//! `["Note", "toot:Thing"]`, the struct accepts the list as long as it includes its own type, and
+//! keeps the list so that it is serialized again as it was.
 
-/// A Unit Struct that represents the string "Article"
+/// Represents the type "Article", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Article)]
-pub struct ArticleType;
+pub struct ArticleType(Vec<String>);
 
-/// A Unit Struct that represents the string "Audio"
+/// Represents the type "Audio", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Audio)]
-pub struct AudioType;
+pub struct AudioType(Vec<String>);
 
-/// A Unit Struct that represents the string "Document"
+/// Represents the type "Document", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Document)]
-pub struct DocumentType;
+pub struct DocumentType(Vec<String>);
 
-/// A Unit Struct that represents the string "Event"
+/// Represents the type "Event", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Event)]
-pub struct EventType;
+pub struct EventType(Vec<String>);
 
-/// A Unit Struct that represents the string "Image"
+/// Represents the type "Image", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Image)]
-pub struct ImageType;
+pub struct ImageType(Vec<String>);
 
-/// A Unit Struct that represents the string "Note"
+/// Represents the type "Note", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Note)]
-pub struct NoteType;
+pub struct NoteType(Vec<String>);
 
-/// A Unit Struct that represents the string "Page"
+/// Represents the type "Page", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Page)]
-pub struct PageType;
+pub struct PageType(Vec<String>);
 
-/// A Unit Struct that represents the string "Place"
+/// Represents the type "Place", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Place)]
-pub struct PlaceType;
+pub struct PlaceType(Vec<String>);
 
-/// A Unit Struct that represents the string "Profile"
+/// Represents the type "Profile", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Profile)]
-pub struct ProfileType;
+pub struct ProfileType(Vec<String>);
 
-/// A Unit Struct that represents the string "Relationship"
+/// Represents the type "Relationship", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Relationship)]
-pub struct RelationshipType;
+pub struct RelationshipType(Vec<String>);
 
-/// A Unit Struct that represents the string "Tombstone"
+/// Represents the type "Tombstone", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Tombstone)]
-pub struct TombstoneType;
+pub struct TombstoneType(Vec<String>);
 
-/// A Unit Struct that represents the string "Video"
+/// Represents the type "Video", along with any other types it was given with
 #[derive(Clone, Debug, Default, UnitString)]
 #[activitystreams(Video)]
-pub struct VideoType;
+pub struct VideoType(Vec<String>);
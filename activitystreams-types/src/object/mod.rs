@@ -19,7 +19,7 @@
 
 //! Namespace for Object types
 
-use activitystreams_traits::Object;
+use activitystreams_traits::{Object, Result};
 
 pub mod kind;
 pub mod properties;
@@ -126,6 +126,35 @@ impl ObjectExt for Event {
     }
 }
 
+impl Event {
+    /// Fetch the time at which this event ends.
+    ///
+    /// If `end_time` is not present, it is computed by adding `duration` to `start_time`.
+    ///
+    /// ```rust
+    /// # extern crate activitystreams_types;
+    /// # extern crate serde_json;
+    /// # use activitystreams_types::object::Event;
+    /// # fn main() {
+    /// let event: Event = serde_json::from_str(r#"{
+    ///     "type": "Event",
+    ///     "startTime": "2018-04-12T18:00:00Z",
+    ///     "duration": "PT2H30M"
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(event.end_time().unwrap().to_rfc3339(), "2018-04-12T20:30:00+00:00");
+    /// # }
+    /// ```
+    pub fn end_time(&self) -> Result<UtcTime> {
+        self.object_props.computed_end_time()
+    }
+
+    /// Set `end_time` by adding `duration` to `start_time`.
+    pub fn set_end_time_from_duration(&mut self) -> Result<()> {
+        self.object_props.set_end_time_from_duration()
+    }
+}
+
 /// An image document of any kind
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -361,3 +390,90 @@ impl ObjectExt for Video {
         &mut self.object_props
     }
 }
+
+impl Video {
+    /// Fetch the time at which this video ends.
+    ///
+    /// If `end_time` is not present, it is computed by adding `duration` to `start_time`.
+    pub fn end_time(&self) -> Result<UtcTime> {
+        self.object_props.computed_end_time()
+    }
+
+    /// Set `end_time` by adding `duration` to `start_time`.
+    pub fn set_end_time_from_duration(&mut self) -> Result<()> {
+        self.object_props.set_end_time_from_duration()
+    }
+}
+
+vocabulary! {
+    Article: "Article" => [ObjectProperties],
+    Audio: "Audio" => [ObjectProperties],
+    Document: "Document" => [ObjectProperties],
+    Event: "Event" => [ObjectProperties],
+    Image: "Image" => [ObjectProperties],
+    Note: "Note" => [ObjectProperties],
+    Page: "Page" => [ObjectProperties],
+    Place: "Place" => [ObjectProperties, PlaceProperties],
+    Profile: "Profile" => [ObjectProperties, ProfileProperties],
+    Relationship: "Relationship" => [ObjectProperties, RelationshipProperties],
+    Tombstone: "Tombstone" => [ObjectProperties, TombstoneProperties],
+    Video: "Video" => [ObjectProperties],
+}
+
+any_type! {
+    /// Any of the Object types defined in this crate
+    ///
+    /// When deserializing, the `type` field is used to decide which Object type to produce.
+    /// Objects with a `type` that is not recognised are kept as `AnyObject::Unknown`.
+    ///
+    /// Only the types from the `object` namespace are recognised here. Activities, Actors, and
+    /// Collections have their own `AnyActivity`, `AnyActor`, and `AnyCollection` types.
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// #[macro_use]
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::object::AnyObject;
+    ///
+    /// # fn main() {
+    /// let object: AnyObject = serde_json::from_value(json!({
+    ///     "type": ["toot:Emoji", "Image"],
+    ///     "url": "https://example.com/emoji.png",
+    /// }))
+    /// .unwrap();
+    ///
+    /// assert_eq!(object.kind(), Some("Image"));
+    /// assert!(!object.is_unknown());
+    ///
+    /// // The other types are kept
+    /// let json = serde_json::to_value(&object).unwrap();
+    /// assert_eq!(json["type"], json!(["toot:Emoji", "Image"]));
+    ///
+    /// let object: AnyObject =
+    ///     serde_json::from_value(json!({ "type": ["toot:Emoji", "toot:Sticker"] })).unwrap();
+    ///
+    /// assert_eq!(object.kind(), Some("toot:Emoji"));
+    /// assert!(object.is_unknown());
+    /// # }
+    /// ```
+    pub enum AnyObject {
+        "Article" => Article,
+        "Audio" => Audio,
+        "Document" => Document,
+        "Event" => Event,
+        "Image" => Image,
+        "Note" => Note,
+        "Page" => Page,
+        "Place" => Place,
+        "Profile" => Profile,
+        "Relationship" => Relationship,
+        "Tombstone" => Tombstone,
+        "Video" => Video,
+    }
+
+    /// Borrow the object properties of the contained Object
+    ObjectExt::props -> ObjectProperties as object_props, object_props_mut;
+}
+
+impl Object for AnyObject {}
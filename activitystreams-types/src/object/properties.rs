@@ -49,12 +49,20 @@
 //! # fn main() {}
 //! ```
 
-use activitystreams_traits::{Collection, Error, Link, Object, Result};
+use activitystreams_traits::{
+    properties::{Either, OneOrMany},
+    Collection, Error, ErrorKind, Link, Object, Result,
+};
 use chrono::{offset::Utc, DateTime};
 use mime;
 use serde_json;
 
+use jsonld::{self, Context};
 use object::Image;
+use primitives::{
+    for_language, parse_property, read_property, LanguageMap, XsdAnyUri, XsdDuration,
+};
+use security::Proof;
 
 /// Alias chrono::DateTime<Utc> for use in derive macros
 pub type UtcTime = DateTime<Utc>;
@@ -70,21 +78,47 @@ pub type UtcTime = DateTime<Utc>;
 /// object type, you must supply your own type. This crate's provided object types all supply their
 /// own `type` properties as Unit Structs with custom serde behaviour.
 ///
-/// All properties are optional (including the id and type).
+/// All properties are optional (including the id and type). A property holding a value of the
+/// wrong type is kept as JSON, so only its getters fail.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::object::Note;
+///
+/// # fn main() {
+/// let note: Note = serde_json::from_value(json!({
+///     "type": "Note",
+///     "published": 5,
+///     "content": ["Hello", "Hallo"],
+/// }))
+/// .unwrap();
+///
+/// assert!(note.object_props.published_string().is_err());
+/// assert_eq!(serde_json::to_value(&note).unwrap()["published"], 5);
+///
+/// let content: Vec<String> = note.object_props.content_string_vec().unwrap();
+/// assert_eq!(content, vec!["Hello".to_owned(), "Hallo".to_owned()]);
+/// assert_eq!(note.object_props.content_string_refs().unwrap(), vec!["Hello", "Hallo"]);
+/// # }
+/// ```
 #[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectProperties {
-    // TODO: IRI type
     /// Provides the globally unique identifier for an Object or Link.
     ///
-    /// The `id` property is expressed as an absolute IRI in the spec, but for now is represented
-    /// as a string.
+    /// The `id` property is expressed as an absolute IRI. A relative or malformed `id` in received
+    /// JSON is kept as it is, so that only `id_xsdanyuri` fails for it, while setting one with
+    /// `set_id_string` fails with an `ErrorKind::InvalidValue` error whose source explains why it
+    /// was rejected.
     ///
     /// - Range: `anyUri`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(concrete(String), functional)]
-    pub id: Option<serde_json::Value>,
+    #[activitystreams(concrete(XsdAnyUri), functional)]
+    pub id: Option<Either<XsdAnyUri, serde_json::Value>>,
 
     /// Identifies a resource attached or related to an object that potentially requires special
     /// handling.
@@ -96,7 +130,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub attachment: Option<serde_json::Value>,
+    pub attachment: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies one or more entities to which this object is attributed.
     ///
@@ -107,7 +141,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub attributed_to: Option<serde_json::Value>,
+    pub attributed_to: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies one or more entities that represent the total population of entities for which
     /// the object can considered to be relevant.
@@ -115,10 +149,9 @@ pub struct ObjectProperties {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(ab(Object, Link))]
-    pub audience: Option<serde_json::Value>,
+    #[activitystreams(ab(Object, Link), concrete(XsdAnyUri))]
+    pub audience: Option<OneOrMany<serde_json::Value>>,
 
-    // TODO: rdf:langString
     /// The content or textual representation of the Object encoded as a JSON string.
     ///
     /// By default, the value of content is HTML. The mediaType property can be used in the object
@@ -130,7 +163,18 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String))]
-    pub content: Option<serde_json::Value>,
+    pub content: Option<OneOrMany<Either<String, serde_json::Value>>>,
+
+    /// The content of the object, keyed by language.
+    ///
+    /// This is the language-tagged form of `content`, as it is sent by servers that support
+    /// multiple languages.
+    ///
+    /// - Range: `rdf:langString`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(LanguageMap), functional)]
+    pub content_map: Option<LanguageMap>,
 
     /// Identifies the context within which the object exists or an activity was performed.
     ///
@@ -142,9 +186,8 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none", rename = "@context")]
     #[activitystreams(ab(Object, Link))]
-    pub context: Option<serde_json::Value>,
+    pub context: Option<OneOrMany<serde_json::Value>>,
 
-    // TODO: rdf:langString
     /// A simple, human-readable, plain-text name for the object.
     ///
     /// HTML markup MUST NOT be included. The name MAY be expressed using multiple language-tagged
@@ -154,7 +197,18 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String))]
-    pub name: Option<serde_json::Value>,
+    pub name: Option<OneOrMany<Either<String, serde_json::Value>>>,
+
+    /// The name of the object, keyed by language.
+    ///
+    /// This is the language-tagged form of `name`, as it is sent by servers that support
+    /// multiple languages.
+    ///
+    /// - Range: `rdf:langString`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(LanguageMap), functional)]
+    pub name_map: Option<LanguageMap>,
 
     /// The date and time describing the actual or expected ending time of the object.
     ///
@@ -165,7 +219,7 @@ pub struct ObjectProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String, UtcTime), functional)]
-    pub end_time: Option<serde_json::Value>,
+    pub end_time: Option<Either<String, serde_json::Value>>,
 
     /// Identifies the entity (e.g. an application) that generated the object.
     ///
@@ -173,7 +227,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub generator: Option<serde_json::Value>,
+    pub generator: Option<OneOrMany<serde_json::Value>>,
 
     /// Indicates an entity that describes an icon for this object.
     ///
@@ -184,7 +238,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link), concrete(Image))]
-    pub icon: Option<serde_json::Value>,
+    pub icon: Option<OneOrMany<serde_json::Value>>,
 
     /// Indicates an entity that describes an image for this object.
     ///
@@ -194,7 +248,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link), concrete(Image))]
-    pub image: Option<serde_json::Value>,
+    pub image: Option<OneOrMany<serde_json::Value>>,
 
     /// Indicates one or more entities for which this object is considered a response.
     ///
@@ -202,7 +256,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub in_reply_to: Option<serde_json::Value>,
+    pub in_reply_to: Option<OneOrMany<serde_json::Value>>,
 
     /// Indicates one or more physical or logical locations associated with the object.
     ///
@@ -210,7 +264,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub location: Option<serde_json::Value>,
+    pub location: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies an entity that provides a preview of this object.
     ///
@@ -218,7 +272,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub preview: Option<serde_json::Value>,
+    pub preview: Option<OneOrMany<serde_json::Value>>,
 
     /// The date and time at which the object was published.
     ///
@@ -226,12 +280,12 @@ pub struct ObjectProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String, UtcTime), functional)]
-    pub published: Option<serde_json::Value>,
+    pub published: Option<Either<String, serde_json::Value>>,
 
     /// Identifies a `Collection` containing objects considered to be responses to this object.
     ///
-    /// - Range: `Object` | `Link`
-    /// - Functional: false
+    /// - Range: `Collection`
+    /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Collection), functional)]
     pub replies: Option<serde_json::Value>,
@@ -241,13 +295,12 @@ pub struct ObjectProperties {
     /// When used with an `Activity` object, for instance, the `start_time` property specifies the
     /// moment the activity began or is scheduled to begin.
     ///
-    /// - Range: `Object` | `Link`
+    /// - Range: `xsd:dateTime`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String, UtcTime), functional)]
-    pub start_time: Option<serde_json::Value>,
+    pub start_time: Option<Either<String, serde_json::Value>>,
 
-    // TODO: rdf:langString
     /// A natural language summarization of the object encoded as HTML.
     ///
     /// Multiple language tagged summaries MAY be provided.
@@ -256,7 +309,18 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String))]
-    pub summary: Option<serde_json::Value>,
+    pub summary: Option<OneOrMany<Either<String, serde_json::Value>>>,
+
+    /// The summary of the object, keyed by language.
+    ///
+    /// This is the language-tagged form of `summary`, as it is sent by servers that support
+    /// multiple languages.
+    ///
+    /// - Range: `rdf:langString`
+    /// - Functional: true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(LanguageMap), functional)]
+    pub summary_map: Option<LanguageMap>,
 
     /// One or more "tags" that have been associated with an objects. A tag can be any kind of
     /// `Object`.
@@ -268,7 +332,7 @@ pub struct ObjectProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object, Link))]
-    pub tag: Option<serde_json::Value>,
+    pub tag: Option<OneOrMany<serde_json::Value>>,
 
     /// The date and time at which the object was updated,
     ///
@@ -276,39 +340,42 @@ pub struct ObjectProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String, UtcTime), functional)]
-    pub updated: Option<serde_json::Value>,
+    pub updated: Option<Either<String, serde_json::Value>>,
 
     /// Identifies one or more links to representations of the object.
     ///
+    /// Like `id`, invalid IRIs in received JSON are kept as they are, while setting one with
+    /// `set_url_string` fails with an `ErrorKind::InvalidValue` error.
+    ///
     /// - Range: `xsd:anyUri` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(concrete(String), ab(Link))]
-    pub url: Option<serde_json::Value>,
+    #[activitystreams(concrete(XsdAnyUri), ab(Link))]
+    pub url: Option<OneOrMany<Either<XsdAnyUri, serde_json::Value>>>,
 
     /// Identifies an entity considered to be part of the public primary audience of an `Object`.
     ///
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(ab(Object, Link))]
-    pub to: Option<serde_json::Value>,
+    #[activitystreams(ab(Object, Link), concrete(XsdAnyUri))]
+    pub to: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies an `Object` that is part of the private primary audience of this `Object`.
     ///
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(ab(Object, Link))]
-    pub bto: Option<serde_json::Value>,
+    #[activitystreams(ab(Object, Link), concrete(XsdAnyUri))]
+    pub bto: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies an `Object` that is part of the public secondary audience of this `Object`.
     ///
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(ab(Object, Link))]
-    pub cc: Option<serde_json::Value>,
+    #[activitystreams(ab(Object, Link), concrete(XsdAnyUri))]
+    pub cc: Option<OneOrMany<serde_json::Value>>,
 
     /// Identifies one or more `Objects` that are part of the private secondary audience of this
     /// `Object`.
@@ -316,8 +383,8 @@ pub struct ObjectProperties {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(ab(Object, Link))]
-    pub bcc: Option<serde_json::Value>,
+    #[activitystreams(ab(Object, Link), concrete(XsdAnyUri))]
+    pub bcc: Option<OneOrMany<serde_json::Value>>,
 
     /// When used on an `Object`, identifies the MIME media type of the value of the content
     /// property.
@@ -328,9 +395,8 @@ pub struct ObjectProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String), functional)]
-    pub media_type: Option<serde_json::Value>,
+    pub media_type: Option<Either<String, serde_json::Value>>,
 
-    // TODO: xsd:duration
     /// When the object describes a time-bound resource, such as an audio or video, a meeting, etc,
     /// the duration property indicates the object's approximate duration.
     ///
@@ -341,15 +407,262 @@ pub struct ObjectProperties {
     /// - Range: `xsd:duration`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[activitystreams(concrete(String), functional)]
-    pub duration: Option<serde_json::Value>,
+    #[activitystreams(concrete(String, XsdDuration), functional)]
+    pub duration: Option<Either<String, serde_json::Value>>,
+
+    /// The Data Integrity proofs attached to the object, which let it be verified without
+    /// trusting the server it was received from.
+    ///
+    /// This property is not part of the Activity Streams vocabulary, it is used by
+    /// [FEP-8b32](https://codeberg.org/fediverse/fep/src/branch/main/fep/8b32/fep-8b32.md).
+    ///
+    /// Proofs are kept as JSON, so a proof from another server that this crate does not understand
+    /// does not stop the rest of the object from being read.
+    ///
+    /// - Range: `DataIntegrityProof`
+    /// - Functional: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(Proof))]
+    pub proof: Option<OneOrMany<serde_json::Value>>,
 }
 
 impl ObjectProperties {
+    /// Fetch the `id` as a string, even if it is not a valid IRI
+    ///
+    /// Possible errors from this method are of the kinds `ErrorKind::NotFound` and
+    /// `ErrorKind::Deserialize`
+    pub fn id_string(&self) -> Result<String> {
+        read_property(self.id.as_ref(), "id")
+    }
+
+    /// Set the `id` from a string, which must be an absolute IRI
+    ///
+    /// Possible errors from this method are of the kind `ErrorKind::InvalidValue`
+    ///
+    /// ```rust
+    /// extern crate activitystreams_traits;
+    /// extern crate activitystreams_types;
+    /// #[macro_use]
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_traits::ErrorKind;
+    /// use activitystreams_types::{object::Note, primitives::XsdAnyUriError};
+    /// use std::error::Error;
+    ///
+    /// # fn main() {
+    /// let mut note: Note = serde_json::from_value(json!({
+    ///     "type": "Note",
+    ///     "id": "/notes/1",
+    /// }))
+    /// .unwrap();
+    /// assert_eq!(note.object_props.id_string().unwrap(), "/notes/1");
+    /// assert!(note.object_props.id_xsdanyuri().is_err());
+    ///
+    /// let error = note
+    ///     .object_props
+    ///     .set_id_string("/notes/1".to_owned())
+    ///     .unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    /// assert!(error
+    ///     .source()
+    ///     .and_then(|source| source.downcast_ref::<XsdAnyUriError>())
+    ///     .map_or(false, XsdAnyUriError::is_relative));
+    ///
+    /// note.object_props
+    ///     .set_id_string("https://example.com/notes/1".to_owned())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     note.object_props.id_xsdanyuri().unwrap().as_str(),
+    ///     "https://example.com/notes/1"
+    /// );
+    /// # }
+    /// ```
+    pub fn set_id_string(&mut self, id: String) -> Result<()> {
+        self.id = Some(parse_property(&id, "id")?);
+        Ok(())
+    }
+
+    /// Fetch the `url` as a string, even if it is not a valid IRI
+    ///
+    /// Possible errors from this method are of the kinds `ErrorKind::NotFound` and
+    /// `ErrorKind::Deserialize`
+    pub fn url_string(&self) -> Result<String> {
+        let url = match self.url {
+            Some(OneOrMany::Many(_)) => {
+                return Err(Error::new(ErrorKind::Deserialize).for_property("url", "String"));
+            }
+            Some(OneOrMany::One(ref url)) => Some(url),
+            None => None,
+        };
+
+        read_property(url, "url")
+    }
+
+    /// Fetch every `url` as a string, even if they are not valid IRIs
+    ///
+    /// Possible errors from this method are of the kinds `ErrorKind::NotFound` and
+    /// `ErrorKind::Deserialize`
+    pub fn url_string_vec(&self) -> Result<Vec<String>> {
+        match self.url {
+            Some(ref urls) => urls
+                .iter()
+                .map(|url| read_property(Some(url), "url"))
+                .collect(),
+            None => read_property(None, "url").map(|url| vec![url]),
+        }
+    }
+
+    /// Set the `url` from a string, which must be an absolute IRI
+    ///
+    /// Possible errors from this method are of the kind `ErrorKind::InvalidValue`
+    pub fn set_url_string(&mut self, url: String) -> Result<()> {
+        self.url = Some(OneOrMany::One(parse_property(&url, "url")?));
+        Ok(())
+    }
+
+    /// Set many `url`s from strings, which must all be absolute IRIs
+    ///
+    /// Possible errors from this method are of the kind `ErrorKind::InvalidValue`
+    pub fn set_url_string_vec(&mut self, urls: Vec<String>) -> Result<()> {
+        let urls = urls
+            .iter()
+            .map(|url| parse_property(url, "url"))
+            .collect::<Result<_>>()?;
+        self.url = Some(OneOrMany::Many(urls));
+        Ok(())
+    }
+
+    /// Fetch the `@context` as a typed `Context`
+    ///
+    /// ```rust
+    /// extern crate activitystreams_types;
+    /// #[macro_use]
+    /// extern crate serde_json;
+    ///
+    /// use activitystreams_types::{actor::Person, jsonld::Context};
+    ///
+    /// # fn main() {
+    /// let mut person = Person::default();
+    /// let context = Context::builder()
+    ///     .activitystreams()
+    ///     .security()
+    ///     .term("manuallyApprovesFollowers", "as:manuallyApprovesFollowers")
+    ///     .build();
+    /// person.object_props.set_json_ld_context(context).unwrap();
+    ///
+    /// let value = serde_json::to_value(&person).unwrap();
+    /// assert_eq!(value["@context"], json!([
+    ///     "https://www.w3.org/ns/activitystreams",
+    ///     "https://w3id.org/security/v1",
+    ///     { "manuallyApprovesFollowers": "as:manuallyApprovesFollowers" }
+    /// ]));
+    ///
+    /// let context = person.object_props.json_ld_context().unwrap();
+    /// assert!(context.has_remote("https://w3id.org/security/v1"));
+    /// assert!(context.term("manuallyApprovesFollowers").is_some());
+    /// # }
+    /// ```
+    pub fn json_ld_context(&self) -> Result<Context> {
+        jsonld::context::from_field(self.context.as_ref())
+    }
+
+    /// Set the `@context` from a typed `Context`
+    pub fn set_json_ld_context(&mut self, context: Context) -> Result<()> {
+        self.context = Some(jsonld::context::into_field(&context)?);
+        Ok(())
+    }
+
     /// Fetch a typed `Mime` struct from the `media_type` field.
     pub fn media_type(&self) -> Result<mime::Mime> {
-        self.media_type_string()
-            .and_then(|s| s.parse().map_err(|_| Error::Deserialize))
+        self.media_type_string().and_then(|s| {
+            s.parse()
+                .map_err(|_| Error::new(ErrorKind::Deserialize).for_property("media_type", "Mime"))
+        })
+    }
+
+    /// Fetch the `content` that best matches a list of languages, in order of preference.
+    ///
+    /// `contentMap` is searched first, falling back to the plain `content` and then to any language
+    /// in `contentMap`.
+    ///
+    /// ```rust
+    /// # extern crate activitystreams_types;
+    /// # extern crate serde_json;
+    /// # use activitystreams_types::object::Note;
+    /// # fn main() {
+    /// let note: Note = serde_json::from_str(r#"{
+    ///     "type": "Note",
+    ///     "content": "Hello",
+    ///     "contentMap": {
+    ///         "en": "Hello",
+    ///         "de": "Hallo"
+    ///     }
+    /// }"#).unwrap();
+    ///
+    /// let props = &note.object_props;
+    /// assert_eq!(props.content_for_language(&["de", "en"]).unwrap(), "Hallo");
+    /// assert_eq!(props.content_for_language(&["fr", "en"]).unwrap(), "Hello");
+    /// assert_eq!(props.content_for_language(&["fr"]).unwrap(), "Hello");
+    /// # }
+    /// ```
+    pub fn content_for_language<S>(&self, languages: &[S]) -> Result<&str>
+    where
+        S: AsRef<str>,
+    {
+        for_language(self.content.as_ref(), self.content_map.as_ref(), languages)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound).for_property("content", "str"))
+    }
+
+    /// Fetch the `name` that best matches a list of languages, in order of preference.
+    ///
+    /// `nameMap` is searched first, falling back to the plain `name` and then to any language
+    /// in `nameMap`.
+    pub fn name_for_language<S>(&self, languages: &[S]) -> Result<&str>
+    where
+        S: AsRef<str>,
+    {
+        for_language(self.name.as_ref(), self.name_map.as_ref(), languages)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound).for_property("name", "str"))
+    }
+
+    /// Fetch the `summary` that best matches a list of languages, in order of preference.
+    ///
+    /// `summaryMap` is searched first, falling back to the plain `summary` and then to any language
+    /// in `summaryMap`.
+    pub fn summary_for_language<S>(&self, languages: &[S]) -> Result<&str>
+    where
+        S: AsRef<str>,
+    {
+        for_language(self.summary.as_ref(), self.summary_map.as_ref(), languages)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound).for_property("summary", "str"))
+    }
+
+    /// Fetch the time at which the object ends.
+    ///
+    /// If `end_time` is not present, it is computed by adding `duration` to `start_time`.
+    pub fn computed_end_time(&self) -> Result<UtcTime> {
+        match self.end_time_utctime() {
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {
+                let start_time = self.start_time_utctime()?;
+                self.duration_xsdduration()?
+                    .checked_add_to(start_time)
+                    .ok_or_else(|| {
+                        Error::new(ErrorKind::Deserialize).for_property("end_time", "UtcTime")
+                    })
+            }
+            end_time => end_time,
+        }
+    }
+
+    /// Set `end_time` by adding `duration` to `start_time`.
+    pub fn set_end_time_from_duration(&mut self) -> Result<()> {
+        let start_time = self.start_time_utctime()?;
+        let end_time = self
+            .duration_xsdduration()?
+            .checked_add_to(start_time)
+            .ok_or_else(|| Error::new(ErrorKind::Serialize).for_property("end_time", "UtcTime"))?;
+
+        self.set_end_time_utctime(end_time)
     }
 }
 
@@ -365,7 +678,7 @@ pub struct PlaceProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(f64), functional)]
-    pub accuracy: Option<serde_json::Value>,
+    pub accuracy: Option<Either<f64, serde_json::Value>>,
 
     /// Indicates the altitude of a place. The measurement units is indicated using the units
     /// property.
@@ -376,7 +689,7 @@ pub struct PlaceProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(f64), functional)]
-    pub altitude: Option<serde_json::Value>,
+    pub altitude: Option<Either<f64, serde_json::Value>>,
 
     /// The latitude of a place.
     ///
@@ -384,7 +697,7 @@ pub struct PlaceProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(f64), functional)]
-    pub latitude: Option<serde_json::Value>,
+    pub latitude: Option<Either<f64, serde_json::Value>>,
 
     /// The longitude of a place.
     ///
@@ -392,7 +705,7 @@ pub struct PlaceProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(f64), functional)]
-    pub longitude: Option<serde_json::Value>,
+    pub longitude: Option<Either<f64, serde_json::Value>>,
 
     /// The radius from the given latitude and longitude for a Place.
     ///
@@ -403,17 +716,17 @@ pub struct PlaceProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(f64), functional)]
-    pub radius: Option<serde_json::Value>,
+    pub radius: Option<Either<f64, serde_json::Value>>,
 
     /// Specifies the measurement units for the radius and altitude properties on a `Place` object.
     ///
     /// If not specified, the default is assumed to be "m" for "meters".
     ///
-    /// - Range: `xsd:float`
+    /// - Range: `cm` | `feet` | `inches` | `km` | `m` | `miles` | `xsd:anyUri`
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String), functional)]
-    pub units: Option<serde_json::Value>,
+    pub units: Option<Either<String, serde_json::Value>>,
 }
 
 /// Define all the properties of the Profile type as described by the Activity Streams vocabulary.
@@ -450,7 +763,7 @@ pub struct RelationshipProperties {
     /// - Range: `Object` | `Link`
     /// - Functional: false
     #[activitystreams(ab(Object, Link))]
-    object: serde_json::Value,
+    object: OneOrMany<serde_json::Value>,
 
     /// On a `Relationship` object, the relationship property identifies the kind of relationship
     /// that exists between subject and object.
@@ -458,7 +771,7 @@ pub struct RelationshipProperties {
     /// - Range: `Object`
     /// - Functional: false
     #[activitystreams(ab(Object))]
-    relationship: serde_json::Value,
+    relationship: OneOrMany<serde_json::Value>,
 }
 
 /// Define all the properties of the Tombstone type as described by the Activity Streams vocabulary.
@@ -472,7 +785,7 @@ pub struct TombstoneProperties {
     /// - Functional: false
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(ab(Object))]
-    pub former_type: Option<serde_json::Value>,
+    pub former_type: Option<OneOrMany<serde_json::Value>>,
 
     /// On a `Tombstone` object, the deleted property is a timestamp for when the object was
     /// deleted.
@@ -481,5 +794,5 @@ pub struct TombstoneProperties {
     /// - Functional: true
     #[serde(skip_serializing_if = "Option::is_none")]
     #[activitystreams(concrete(String, UtcTime), functional)]
-    pub deleted: Option<serde_json::Value>,
+    pub deleted: Option<Either<String, serde_json::Value>>,
 }
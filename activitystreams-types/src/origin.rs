@@ -0,0 +1,270 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Same-origin checks, to defend against spoofed activities
+//!
+//! A signature only proves who sent an activity. Anything else the activity claims, such as the
+//! id of an object embedded in it, may be forged: a `Create` from `evil.example` can embed a
+//! `Note` whose `id` claims to be on `good.example`. Servers guard against this by checking that
+//! the activity, its actor and the owner of the signing key share an origin, and by only trusting
+//! embedded objects that come from that same origin. Other embedded objects must be fetched again
+//! by their id from their own server.
+//!
+//! Two ids share an origin when they have the same scheme, host and port.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     activity::{Announce, Create},
+//!     origin::{OriginExt, Trust},
+//! };
+//!
+//! # fn main() {
+//! let key_owner = "https://evil.example/users/mallory".parse().unwrap();
+//!
+//! let create: Create = serde_json::from_str(r#"{
+//!     "type": "Create",
+//!     "id": "https://evil.example/activities/1",
+//!     "actor": "https://evil.example/users/mallory",
+//!     "object": {
+//!         "type": "Note",
+//!         "id": "https://good.example/notes/1",
+//!         "attributedTo": "https://good.example/users/alice"
+//!     }
+//! }"#).unwrap();
+//!
+//! // The activity itself is authentic, but the Note it embeds is not from its origin
+//! let check = create.check_origins(&key_owner).unwrap();
+//! assert!(check.is_authentic());
+//! assert!(check.trusted().is_empty());
+//!
+//! let refetch: Vec<&str> = check.to_refetch().iter().map(|id| id.as_str()).collect();
+//! assert_eq!(refetch, vec!["https://good.example/notes/1"]);
+//!
+//! // An activity claiming to be from another server is not authentic at all
+//! let announce: Announce = serde_json::from_str(r#"{
+//!     "type": "Announce",
+//!     "actor": "https://good.example/users/alice",
+//!     "object": "https://good.example/notes/1"
+//! }"#).unwrap();
+//!
+//! let check = announce.check_origins(&key_owner).unwrap();
+//! assert!(!check.is_authentic());
+//! # }
+//! ```
+
+use activitystreams_traits::{properties::to_value, Result};
+use serde::ser::Serialize;
+use serde_json::Value;
+use std::fmt;
+use url::{Origin, Url};
+
+use activity::ActivityExt;
+use addressing::{address_id, values};
+use primitives::XsdAnyUri;
+
+/// The properties of an activity that may embed objects
+const EMBEDDING: &[&str] = &["object", "target"];
+
+/// The properties of an embedded object that name who published it
+const AUTHORS: &[&str] = &["attributedTo", "actor"];
+
+/// A way in which an activity does not come from the origin it was sent by
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum OriginMismatch {
+    /// An actor of the activity is not on the origin of the key owner
+    ///
+    /// The actor is `None` when it is an embedded object without an id, or when the activity has
+    /// no actor at all, as nothing then ties the activity to the key owner.
+    Actor(Option<String>),
+
+    /// The id of the activity is not on the origin of the key owner
+    Activity(String),
+}
+
+/// Whether an embedded object can be used as it is
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Trust {
+    /// The object comes from the origin of the activity, and can be used as it is
+    Trusted,
+
+    /// The object may be forged, and must be fetched by its id from its own server
+    Refetch(XsdAnyUri),
+
+    /// The object may be forged, and has no id it could be fetched by, so it must be discarded
+    Discard,
+}
+
+/// An object embedded in the `object` or `target` of an activity
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmbeddedObject {
+    /// The property the object is embedded in, either `object` or `target`
+    pub property: &'static str,
+
+    /// The object, as it was embedded
+    pub value: Value,
+
+    /// Whether the object can be used as it is
+    pub trust: Trust,
+}
+
+/// The outcome of checking the origins of an activity
+#[derive(Clone, Debug, PartialEq)]
+pub struct OriginCheck {
+    /// Every way in which the activity does not come from the origin of the key owner
+    pub mismatches: Vec<OriginMismatch>,
+
+    /// The objects embedded in the activity, in the order they appear in
+    pub embedded: Vec<EmbeddedObject>,
+}
+
+impl OriginCheck {
+    /// Check whether the activity comes from the origin of the key owner
+    pub fn is_authentic(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// The embedded objects that can be used as they are
+    pub fn trusted(&self) -> Vec<&EmbeddedObject> {
+        self.embedded
+            .iter()
+            .filter(|object| object.trust == Trust::Trusted)
+            .collect()
+    }
+
+    /// The ids of the embedded objects that must be fetched again from their own server
+    pub fn to_refetch(&self) -> Vec<&XsdAnyUri> {
+        self.embedded
+            .iter()
+            .filter_map(|object| match object.trust {
+                Trust::Refetch(ref id) => Some(id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for OriginMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OriginMismatch::Actor(Some(ref actor)) => {
+                write!(f, "Actor {} is not on the origin of the key owner", actor)
+            }
+            OriginMismatch::Actor(None) => write!(f, "Actor is missing or has no id"),
+            OriginMismatch::Activity(ref id) => {
+                write!(f, "Activity {} is not on the origin of the key owner", id)
+            }
+        }
+    }
+}
+
+/// The Origin Extension Trait
+///
+/// This trait checks the origins of activities received from other servers, given the owner of
+/// the key the activity was signed with, such as the `owner` of the `PublicKey` an HTTP signature
+/// was verified with.
+pub trait OriginExt: ActivityExt + Serialize {
+    /// Check the origins of this activity and of the objects embedded in it
+    ///
+    /// The activity must have an actor, and its actors and id must share the origin of the key
+    /// owner. An embedded object is only trusted when the activity is authentic, and the object's
+    /// id and the ids in its `attributedTo` and `actor` share that origin too. An embedded object
+    /// without an id is trusted when the activity is authentic, as it only exists within the
+    /// activity.
+    fn check_origins(&self, key_owner: &XsdAnyUri) -> Result<OriginCheck> {
+        let value = to_value(self)?;
+        let expected = origin(key_owner.as_str());
+
+        let mut mismatches = Vec::new();
+
+        let actors = value.get("actor").map(values).unwrap_or_default();
+        if actors.is_empty() {
+            mismatches.push(OriginMismatch::Actor(None));
+        }
+
+        for actor in actors {
+            let actor = address_id(actor);
+            if !actor.is_some_and(|actor| same_origin(actor, &expected)) {
+                mismatches.push(OriginMismatch::Actor(actor.map(str::to_owned)));
+            }
+        }
+
+        if let Some(id) = value.get("id").and_then(Value::as_str) {
+            if !same_origin(id, &expected) {
+                mismatches.push(OriginMismatch::Activity(id.to_owned()));
+            }
+        }
+
+        let mut embedded = Vec::new();
+
+        for property in EMBEDDING {
+            for object in value.get(*property).map(values).unwrap_or_default() {
+                if !object.is_object() {
+                    continue;
+                }
+
+                let authentic = mismatches.is_empty()
+                    && AUTHORS
+                        .iter()
+                        .filter_map(|author| object.get(*author))
+                        .flat_map(values)
+                        .all(|author| {
+                            address_id(author).is_some_and(|a| same_origin(a, &expected))
+                        });
+
+                let id = object.get("id").and_then(Value::as_str);
+                let trust = match id {
+                    Some(id) if authentic && same_origin(id, &expected) => Trust::Trusted,
+                    Some(id) => id.parse().map(Trust::Refetch).unwrap_or(Trust::Discard),
+                    None if authentic => Trust::Trusted,
+                    None => Trust::Discard,
+                };
+
+                embedded.push(EmbeddedObject {
+                    property,
+                    value: object.clone(),
+                    trust,
+                });
+            }
+        }
+
+        Ok(OriginCheck {
+            mismatches,
+            embedded,
+        })
+    }
+}
+
+impl<T> OriginExt for T where T: ActivityExt + Serialize {}
+
+/// Find the origin of an id
+///
+/// Ids that can not be parsed get a unique opaque origin, which matches no other origin.
+fn origin(id: &str) -> Origin {
+    Url::parse(id)
+        .map(|url| url.origin())
+        .unwrap_or_else(|_| Origin::new_opaque())
+}
+
+fn same_origin(id: &str, expected: &Origin) -> bool {
+    let origin = origin(id);
+    origin.is_tuple() && origin == *expected
+}
@@ -0,0 +1,366 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use activitystreams_traits::properties::{Either, OneOrMany};
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, Serializer},
+};
+use serde_json::Value;
+use std::{
+    collections::{btree_map, BTreeMap},
+    error::Error,
+    fmt,
+    iter::FromIterator,
+    str::FromStr,
+};
+
+/// A language tag, as described by [BCP47](https://tools.ietf.org/html/bcp47)
+///
+/// Tags are checked to be well-formed when they are parsed, but they are not checked against
+/// the IANA language subtag registry. Tags received in JSON are kept as they are, since forms such
+/// as `en_US` are common in the wild, so `is_well_formed` tells them apart. The case of the tag is
+/// preserved, but comparisons between tags made by `LanguageMap` ignore case, as required by
+/// BCP47.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// extern crate serde_json;
+///
+/// use activitystreams_types::primitives::LanguageTag;
+///
+/// # fn main() {
+/// let tag: LanguageTag = "de-AT".parse().unwrap();
+/// assert_eq!(tag.as_str(), "de-AT");
+///
+/// assert!("not a language".parse::<LanguageTag>().is_err());
+///
+/// let received: LanguageTag = serde_json::from_str(r#""en_US""#).unwrap();
+/// assert!(!received.is_well_formed());
+/// assert!(received.has_prefix("en"));
+/// # }
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LanguageTag(String);
+
+impl LanguageTag {
+    /// Borrow the tag as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check whether this tag is the same as another, ignoring case and whether subtags are
+    /// separated by `-` or `_`
+    pub fn matches(&self, other: &str) -> bool {
+        self.0.len() == other.len()
+            && self.0.bytes().zip(other.bytes()).all(|(a, b)| {
+                a.eq_ignore_ascii_case(&b) || (a == b'-' || a == b'_') && (b == b'-' || b == b'_')
+            })
+    }
+
+    /// Check whether this tag is the given language range, or a more specific form of it
+    ///
+    /// For example, `de-AT` and `de` both match the range `de`, but `den` does not. Subtags
+    /// separated by `_` are accepted too, as in `de_AT`.
+    pub fn has_prefix(&self, range: &str) -> bool {
+        self.0.len() > range.len()
+            && (self.0.as_bytes()[range.len()] == b'-' || self.0.as_bytes()[range.len()] == b'_')
+            && self.0[..range.len()].eq_ignore_ascii_case(range)
+            || self.matches(range)
+    }
+
+    /// Check whether this tag is well-formed, which is only unknown for tags received in JSON
+    pub fn is_well_formed(&self) -> bool {
+        well_formed(&self.0)
+    }
+}
+
+/// Check the syntax of a language tag
+fn well_formed(s: &str) -> bool {
+    let mut subtags = s.split('-');
+
+    let well_formed = match subtags.next() {
+        // Private use and grandfathered tags start with a singleton
+        Some("x") | Some("X") | Some("i") | Some("I") => true,
+        Some(language) => {
+            language.len() >= 2
+                && language.len() <= 8
+                && language.bytes().all(|b| b.is_ascii_alphabetic())
+        }
+        None => false,
+    } && subtags.all(|subtag| {
+        !subtag.is_empty() && subtag.len() <= 8 && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+    });
+
+    well_formed && s.len() > 1
+}
+
+impl FromStr for LanguageTag {
+    type Err = LanguageTagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if well_formed(s) {
+            Ok(LanguageTag(s.to_owned()))
+        } else {
+            Err(LanguageTagError(s.to_owned()))
+        }
+    }
+}
+
+impl AsRef<str> for LanguageTag {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for LanguageTag {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageTag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(LanguageTag)
+    }
+}
+
+/// The error produced when a string is not a well-formed language tag
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LanguageTagError(String);
+
+impl fmt::Display for LanguageTagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid language tag, {:?}", self.0)
+    }
+}
+
+impl Error for LanguageTagError {}
+
+/// A set of natural language values, keyed by their language
+///
+/// This is the JSON-LD language map used by properties such as `contentMap`, `nameMap` and
+/// `summaryMap`. Received entries whose value is not a string, such as `null`, are skipped rather
+/// than rejecting the whole document, as JSON-LD ignores `null` values in language maps.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// extern crate serde_json;
+///
+/// use activitystreams_types::primitives::LanguageMap;
+///
+/// # fn main() {
+/// let map: LanguageMap = serde_json::from_str(r#"{
+///     "en": "A cat",
+///     "de-AT": "Eine Katze"
+/// }"#).unwrap();
+///
+/// assert_eq!(map.get("EN"), Some("A cat"));
+/// assert_eq!(map.lookup(&["de", "en"]), Some("Eine Katze"));
+/// assert_eq!(map.lookup(&["fr"]), None);
+///
+/// let map: LanguageMap = serde_json::from_str(r#"{ "en_US": "hi", "de": null }"#).unwrap();
+/// assert_eq!(map.lookup(&["en"]), Some("hi"));
+/// assert_eq!(map.get("en-US"), Some("hi"));
+/// assert_eq!(map.len(), 1);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LanguageMap(BTreeMap<LanguageTag, String>);
+
+impl LanguageMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        LanguageMap(BTreeMap::new())
+    }
+
+    /// Add a value for the given language, returning the value it replaced
+    pub fn insert<T>(&mut self, language: LanguageTag, value: T) -> Option<String>
+    where
+        T: Into<String>,
+    {
+        let existing = self
+            .0
+            .keys()
+            .find(|tag| tag.matches(language.as_str()))
+            .cloned();
+
+        match existing {
+            Some(tag) => self.0.insert(tag, value.into()),
+            None => self.0.insert(language, value.into()),
+        }
+    }
+
+    /// Fetch the value for exactly the given language, ignoring case
+    pub fn get(&self, language: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|&(tag, _)| tag.matches(language))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Remove the value for exactly the given language, ignoring case
+    pub fn remove(&mut self, language: &str) -> Option<String> {
+        let tag = self.0.keys().find(|tag| tag.matches(language)).cloned()?;
+        self.0.remove(&tag)
+    }
+
+    /// Fetch the value that best matches a list of languages, in order of preference
+    ///
+    /// Each language is first matched exactly, then against more specific tags, and then with its
+    /// last subtag removed, so a preference for `de-AT` can be satisfied by `de-AT`, `de-AT-1996`,
+    /// `de` or `de-DE`, in that order. Only if no value matches the first language is the next one
+    /// tried.
+    pub fn lookup<S>(&self, languages: &[S]) -> Option<&str>
+    where
+        S: AsRef<str>,
+    {
+        languages.iter().find_map(|language| {
+            let mut range = language.as_ref();
+
+            loop {
+                if let Some(value) = self.get(range) {
+                    return Some(value);
+                }
+
+                let more_specific = self.0.iter().find(|&(tag, _)| tag.has_prefix(range));
+                if let Some((_, value)) = more_specific {
+                    return Some(value.as_str());
+                }
+
+                range = truncate(range)?;
+            }
+        })
+    }
+
+    /// Iterate over the languages and values in the map
+    pub fn iter(&self) -> btree_map::Iter<'_, LanguageTag, String> {
+        self.0.iter()
+    }
+
+    /// The number of languages in the map
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether the map has no values
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Remove the last subtag from a language range, along with any singleton left at the end
+fn truncate(range: &str) -> Option<&str> {
+    let mut range = &range[..range.rfind('-')?];
+
+    if let Some(index) = range.rfind('-') {
+        if range.len() - index == 2 {
+            range = &range[..index];
+        }
+    }
+
+    Some(range)
+}
+
+/// Pick a value from a plain property and its language map
+///
+/// The map is searched for the preferred languages first. When none of them match, the plain
+/// value is used, since its language is unknown. Failing that, the map's value for `und`
+/// (undetermined) is used, and then any value at all.
+pub(crate) fn for_language<'a, S>(
+    plain: Option<&'a OneOrMany<Either<String, Value>>>,
+    map: Option<&'a LanguageMap>,
+    languages: &[S],
+) -> Option<&'a str>
+where
+    S: AsRef<str>,
+{
+    map.and_then(|map| map.lookup(languages))
+        .or_else(|| {
+            plain
+                .and_then(|plain| plain.iter().find_map(Either::left))
+                .map(String::as_str)
+        })
+        .or_else(|| {
+            map.and_then(|map| {
+                map.get("und")
+                    .or_else(|| map.iter().next().map(|(_, value)| value.as_str()))
+            })
+        })
+}
+
+impl FromIterator<(LanguageTag, String)> for LanguageMap {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (LanguageTag, String)>,
+    {
+        let mut map = LanguageMap::new();
+        for (language, value) in iter {
+            map.insert(language, value);
+        }
+        map
+    }
+}
+
+impl<'a> IntoIterator for &'a LanguageMap {
+    type Item = (&'a LanguageTag, &'a String);
+    type IntoIter = btree_map::Iter<'a, LanguageTag, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Serialize for LanguageMap {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageMap {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = BTreeMap::<LanguageTag, Value>::deserialize(deserializer)?;
+
+        Ok(map
+            .into_iter()
+            .filter_map(|(language, value)| match value {
+                Value::String(value) => Some((language, value)),
+                _ => None,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,31 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Namespace for primitive types from the XML Schema and RDF vocabularies used by Activity
+//! Streams properties
+
+mod language_map;
+mod xsd_any_uri;
+mod xsd_duration;
+
+pub(crate) use self::language_map::for_language;
+pub use self::language_map::{LanguageMap, LanguageTag, LanguageTagError};
+pub(crate) use self::xsd_any_uri::{parse_property, read_property};
+pub use self::xsd_any_uri::{XsdAnyUri, XsdAnyUriError};
+pub use self::xsd_duration::{XsdDuration, XsdDurationError};
@@ -0,0 +1,236 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use activitystreams_traits::{self, properties::Either};
+use serde::{
+    de::{Deserialize, Deserializer, Error as DeError},
+    ser::{Serialize, Serializer},
+};
+use serde_json::Value;
+use std::{
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+use url::{ParseError, Url};
+
+/// An absolute IRI, as described by the `xsd:anyURI` datatype
+///
+/// Values are parsed and normalised when they are created, so relative or malformed IRIs are
+/// rejected before they can be sent to other servers. Normalisation lowercases the scheme and
+/// host, percent-encodes characters that are not allowed in a URI, and adds an empty path (`/`)
+/// to URIs with a host, such as `https://example.com`.
+///
+/// IRIs compare, hash and print in their normalised form, but serialize as they were written, so
+/// received documents are forwarded byte for byte and their signatures stay valid.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+///
+/// use activitystreams_types::primitives::XsdAnyUri;
+///
+/// # fn main() {
+/// let uri: XsdAnyUri = "HTTPS://Example.com/users/alice".parse().unwrap();
+/// assert_eq!(uri.as_str(), "https://example.com/users/alice");
+///
+/// assert_eq!(uri.original(), "HTTPS://Example.com/users/alice");
+///
+/// assert!("/users/alice".parse::<XsdAnyUri>().is_err());
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct XsdAnyUri {
+    url: Url,
+    original: Option<Box<str>>,
+}
+
+impl XsdAnyUri {
+    /// Borrow the IRI as a normalised string
+    pub fn as_str(&self) -> &str {
+        self.url.as_str()
+    }
+
+    /// Borrow the IRI as it was written, before it was normalised
+    pub fn original(&self) -> &str {
+        self.original
+            .as_ref()
+            .map_or(self.as_str(), |original| original)
+    }
+
+    /// Borrow the parsed `Url` backing this IRI
+    pub fn as_url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Take the parsed `Url` backing this IRI
+    pub fn into_url(self) -> Url {
+        self.url
+    }
+
+    /// Resolve a possibly-relative reference against this IRI
+    pub fn join(&self, reference: &str) -> Result<XsdAnyUri, XsdAnyUriError> {
+        self.url
+            .join(reference)
+            .map(XsdAnyUri::from)
+            .map_err(XsdAnyUriError)
+    }
+}
+
+impl FromStr for XsdAnyUri {
+    type Err = XsdAnyUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(s).map_err(XsdAnyUriError)?;
+        let original = if url.as_str() == s {
+            None
+        } else {
+            Some(s.into())
+        };
+
+        Ok(XsdAnyUri { url, original })
+    }
+}
+
+impl From<Url> for XsdAnyUri {
+    fn from(url: Url) -> Self {
+        XsdAnyUri {
+            url,
+            original: None,
+        }
+    }
+}
+
+impl PartialEq for XsdAnyUri {
+    fn eq(&self, other: &Self) -> bool {
+        self.url == other.url
+    }
+}
+
+impl Eq for XsdAnyUri {}
+
+impl Hash for XsdAnyUri {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.url.hash(state)
+    }
+}
+
+impl PartialOrd for XsdAnyUri {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for XsdAnyUri {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.url.cmp(&other.url)
+    }
+}
+
+impl AsRef<str> for XsdAnyUri {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for XsdAnyUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("XsdAnyUri").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for XsdAnyUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.url, f)
+    }
+}
+
+impl Serialize for XsdAnyUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.original())
+    }
+}
+
+impl<'de> Deserialize<'de> for XsdAnyUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+/// The error produced when a string is not a valid absolute IRI
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct XsdAnyUriError(ParseError);
+
+impl XsdAnyUriError {
+    /// Check whether the rejected value was a relative reference rather than an absolute IRI
+    pub fn is_relative(&self) -> bool {
+        self.0 == ParseError::RelativeUrlWithoutBase
+    }
+}
+
+impl fmt::Display for XsdAnyUriError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid xsd:anyURI, {}", self.0)
+    }
+}
+
+impl Error for XsdAnyUriError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Read an IRI property as a string, whether or not it holds a valid IRI
+pub(crate) fn read_property(
+    item: Option<&Either<XsdAnyUri, Value>>,
+    property: &'static str,
+) -> activitystreams_traits::Result<String> {
+    match item {
+        Some(Either::Left(uri)) => Ok(uri.original().to_owned()),
+        Some(Either::Right(Value::String(s))) => Ok(s.clone()),
+        Some(Either::Right(_)) => Err(activitystreams_traits::Error::new(
+            activitystreams_traits::ErrorKind::Deserialize,
+        )),
+        None => Err(activitystreams_traits::Error::new(
+            activitystreams_traits::ErrorKind::NotFound,
+        )),
+    }
+    .map_err(|e| e.for_property(property, "String"))
+}
+
+/// Parse a string being set on an IRI property
+pub(crate) fn parse_property(
+    s: &str,
+    property: &'static str,
+) -> activitystreams_traits::Result<Either<XsdAnyUri, Value>> {
+    s.parse().map(Either::Left).map_err(|e| {
+        activitystreams_traits::Error::invalid_value(e).for_property(property, "String")
+    })
+}
@@ -0,0 +1,470 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use chrono::{Duration, Months};
+use serde::{
+    de::{Deserialize, Deserializer, Error as DeError, Visitor},
+    ser::{Serialize, Serializer},
+};
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+use object::properties::UtcTime;
+
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// A span of time, as described by the `xsd:duration` datatype
+///
+/// Durations are written in the ISO 8601 form `PnYnMnDTnHnMnS`, for example `PT5S` for five
+/// seconds or `-P1DT12H` for a day and a half in the past. Each component is kept as it was
+/// parsed, so durations such as `PT90M` are not rewritten as `PT1H30M`.
+///
+/// Years and months do not have a fixed length. When a duration is added to a `UtcTime` they are
+/// applied as calendar months, but converting a duration into a `chrono::Duration` treats a year
+/// as 365 days and a month as 30 days.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// extern crate chrono;
+///
+/// use activitystreams_types::primitives::XsdDuration;
+/// use chrono::{DateTime, Duration, Utc};
+///
+/// # fn main() {
+/// let duration: XsdDuration = "PT1H30M".parse().unwrap();
+/// assert_eq!(duration.to_string(), "PT1H30M");
+///
+/// let start: DateTime<Utc> = "2018-01-31T12:00:00Z".parse().unwrap();
+/// assert_eq!((start + duration).to_rfc3339(), "2018-01-31T13:30:00+00:00");
+///
+/// let month: XsdDuration = "P1M".parse().unwrap();
+/// assert_eq!((start + month).to_rfc3339(), "2018-02-28T12:00:00+00:00");
+///
+/// let duration = XsdDuration::from(Duration::seconds(-90));
+/// assert_eq!(duration.to_string(), "-PT1M30S");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct XsdDuration {
+    /// Whether the duration points backwards in time
+    pub negative: bool,
+
+    /// The number of years in the duration
+    pub years: u64,
+
+    /// The number of months in the duration
+    pub months: u64,
+
+    /// The number of days in the duration
+    pub days: u64,
+
+    /// The number of hours in the duration
+    pub hours: u64,
+
+    /// The number of minutes in the duration
+    pub minutes: u64,
+
+    /// The number of whole seconds in the duration
+    pub seconds: u64,
+
+    /// The fractional part of the seconds in the duration, in nanoseconds
+    pub nanoseconds: u32,
+}
+
+impl XsdDuration {
+    /// Check whether every component of this duration is zero
+    pub fn is_zero(&self) -> bool {
+        self.years == 0
+            && self.months == 0
+            && self.days == 0
+            && self.hours == 0
+            && self.minutes == 0
+            && self.seconds == 0
+            && self.nanoseconds == 0
+    }
+
+    /// Add this duration to the given time
+    ///
+    /// Years and months are added as calendar months, and the remaining components are added as
+    /// an exact number of seconds. Returns `None` if the result is out of range.
+    pub fn checked_add_to(&self, time: UtcTime) -> Option<UtcTime> {
+        let months = self.years.checked_mul(12)?.checked_add(self.months)?;
+        let months = Months::new(u32::try_from(months).ok()?);
+        let exact = self.exact_duration()?;
+
+        if self.negative {
+            time.checked_sub_months(months)?.checked_sub_signed(exact)
+        } else {
+            time.checked_add_months(months)?.checked_add_signed(exact)
+        }
+    }
+
+    /// Subtract this duration from the given time
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn checked_sub_from(&self, time: UtcTime) -> Option<UtcTime> {
+        XsdDuration {
+            negative: !self.negative,
+            ..*self
+        }
+        .checked_add_to(time)
+    }
+
+    /// The days, hours, minutes and seconds of this duration, ignoring the sign
+    fn exact_duration(&self) -> Option<Duration> {
+        let seconds = self
+            .days
+            .checked_mul(SECONDS_PER_DAY)?
+            .checked_add(self.hours.checked_mul(SECONDS_PER_HOUR)?)?
+            .checked_add(self.minutes.checked_mul(SECONDS_PER_MINUTE)?)?
+            .checked_add(self.seconds)?;
+
+        Duration::new(i64::try_from(seconds).ok()?, self.nanoseconds)
+    }
+}
+
+impl From<Duration> for XsdDuration {
+    fn from(duration: Duration) -> Self {
+        let negative = duration < Duration::zero();
+        let duration = duration.abs();
+
+        let total = duration.num_seconds().unsigned_abs();
+
+        XsdDuration {
+            negative,
+            days: total / SECONDS_PER_DAY,
+            hours: total % SECONDS_PER_DAY / SECONDS_PER_HOUR,
+            minutes: total % SECONDS_PER_HOUR / SECONDS_PER_MINUTE,
+            seconds: total % SECONDS_PER_MINUTE,
+            nanoseconds: duration.subsec_nanos().unsigned_abs(),
+            ..XsdDuration::default()
+        }
+    }
+}
+
+impl TryFrom<XsdDuration> for Duration {
+    type Error = XsdDurationError;
+
+    fn try_from(duration: XsdDuration) -> Result<Self, Self::Error> {
+        let days = duration
+            .years
+            .checked_mul(365)
+            .and_then(|days| days.checked_add(duration.months.checked_mul(30)?))
+            .and_then(|days| days.checked_mul(SECONDS_PER_DAY))
+            .and_then(|seconds| i64::try_from(seconds).ok())
+            .and_then(Duration::try_seconds);
+
+        let total = days
+            .and_then(|days| days.checked_add(&duration.exact_duration()?))
+            .ok_or(XsdDurationError::OutOfRange)?;
+
+        if duration.negative {
+            Ok(-total)
+        } else {
+            Ok(total)
+        }
+    }
+}
+
+impl Add<XsdDuration> for UtcTime {
+    type Output = UtcTime;
+
+    /// Add a duration to a time
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is out of range, use `XsdDuration::checked_add_to` to handle this.
+    fn add(self, duration: XsdDuration) -> UtcTime {
+        duration
+            .checked_add_to(self)
+            .expect("`UtcTime + XsdDuration` out of range")
+    }
+}
+
+impl Sub<XsdDuration> for UtcTime {
+    type Output = UtcTime;
+
+    /// Subtract a duration from a time
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result is out of range, use `XsdDuration::checked_sub_from` to handle this.
+    fn sub(self, duration: XsdDuration) -> UtcTime {
+        duration
+            .checked_sub_from(self)
+            .expect("`UtcTime - XsdDuration` out of range")
+    }
+}
+
+impl FromStr for XsdDuration {
+    type Err = XsdDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        let s = s.strip_prefix('P').ok_or(XsdDurationError::Invalid)?;
+
+        let (date, time) = match s.find('T') {
+            Some(index) => (&s[..index], Some(&s[index + 1..])),
+            None => (s, None),
+        };
+
+        let mut duration = XsdDuration {
+            negative,
+            ..XsdDuration::default()
+        };
+
+        let mut found = false;
+
+        for (number, designator) in Components::new(date, "YMD") {
+            let number = number.and_then(parse_integer)?;
+            match designator {
+                'Y' => duration.years = number,
+                'M' => duration.months = number,
+                _ => duration.days = number,
+            }
+            found = true;
+        }
+
+        if let Some(time) = time {
+            let mut found_time = false;
+
+            for (number, designator) in Components::new(time, "HMS") {
+                let number = number?;
+                match designator {
+                    'H' => duration.hours = parse_integer(number)?,
+                    'M' => duration.minutes = parse_integer(number)?,
+                    _ => {
+                        let (seconds, nanoseconds) = parse_seconds(number)?;
+                        duration.seconds = seconds;
+                        duration.nanoseconds = nanoseconds;
+                    }
+                }
+                found_time = true;
+            }
+
+            if !found_time {
+                return Err(XsdDurationError::Invalid);
+            }
+
+            found = true;
+        }
+
+        if found {
+            Ok(duration)
+        } else {
+            Err(XsdDurationError::Invalid)
+        }
+    }
+}
+
+/// Iterates over the `nX` components of part of a duration, where `n` is a number and `X` is a
+/// designator
+struct Components<'a> {
+    rest: &'a str,
+    designators: &'static str,
+}
+
+impl<'a> Components<'a> {
+    fn new(s: &'a str, designators: &'static str) -> Self {
+        Components {
+            rest: s,
+            designators,
+        }
+    }
+}
+
+impl<'a> Iterator for Components<'a> {
+    type Item = (Result<&'a str, XsdDurationError>, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let index = match self.rest.find(|c: char| c.is_ascii_alphabetic()) {
+            Some(index) => index,
+            None => {
+                self.rest = "";
+                return Some((Err(XsdDurationError::Invalid), '\0'));
+            }
+        };
+
+        let number = &self.rest[..index];
+        let designator = self.rest[index..].chars().next()?;
+        self.rest = &self.rest[index + designator.len_utf8()..];
+
+        // Designators must appear in order, and only once
+        match self.designators.find(designator) {
+            Some(position) if !number.is_empty() => {
+                self.designators = &self.designators[position + 1..];
+                Some((Ok(number), designator))
+            }
+            _ => {
+                self.rest = "";
+                Some((Err(XsdDurationError::Invalid), designator))
+            }
+        }
+    }
+}
+
+fn parse_integer(number: &str) -> Result<u64, XsdDurationError> {
+    if !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(XsdDurationError::Invalid);
+    }
+
+    number.parse().map_err(|_| XsdDurationError::OutOfRange)
+}
+
+fn parse_seconds(number: &str) -> Result<(u64, u32), XsdDurationError> {
+    let (whole, fraction) = match number.find('.') {
+        Some(index) => (&number[..index], &number[index + 1..]),
+        None => return parse_integer(number).map(|seconds| (seconds, 0)),
+    };
+
+    // Either side of the point may be empty, as in `PT.5S` or `PT5.S`, but not both
+    if whole.is_empty() && fraction.is_empty() || fraction.len() > 9 {
+        return Err(XsdDurationError::Invalid);
+    }
+
+    let seconds = if whole.is_empty() {
+        0
+    } else {
+        parse_integer(whole)?
+    };
+    let nanoseconds = if fraction.is_empty() {
+        0
+    } else {
+        parse_integer(fraction)? * 10u64.pow(9 - fraction.len() as u32)
+    };
+
+    Ok((seconds, nanoseconds as u32))
+}
+
+impl fmt::Display for XsdDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+
+        if self.is_zero() {
+            return f.write_str("T0S");
+        }
+
+        if self.years > 0 {
+            write!(f, "{}Y", self.years)?;
+        }
+        if self.months > 0 {
+            write!(f, "{}M", self.months)?;
+        }
+        if self.days > 0 {
+            write!(f, "{}D", self.days)?;
+        }
+
+        if self.hours == 0 && self.minutes == 0 && self.seconds == 0 && self.nanoseconds == 0 {
+            return Ok(());
+        }
+
+        f.write_str("T")?;
+
+        if self.hours > 0 {
+            write!(f, "{}H", self.hours)?;
+        }
+        if self.minutes > 0 {
+            write!(f, "{}M", self.minutes)?;
+        }
+        if self.nanoseconds > 0 {
+            let fraction = format!("{:09}", self.nanoseconds);
+            write!(f, "{}.{}S", self.seconds, fraction.trim_end_matches('0'))?;
+        } else if self.seconds > 0 {
+            write!(f, "{}S", self.seconds)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for XsdDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for XsdDuration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(XsdDurationVisitor)
+    }
+}
+
+/// Parses durations from borrowed strings, so reading one does not allocate
+struct XsdDurationVisitor;
+
+impl<'de> Visitor<'de> for XsdDurationVisitor {
+    type Value = XsdDuration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an xsd:duration string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<XsdDuration, E>
+    where
+        E: DeError,
+    {
+        v.parse().map_err(E::custom)
+    }
+}
+
+/// The error produced when a string is not a valid `xsd:duration`, or a duration cannot be
+/// represented
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum XsdDurationError {
+    /// The string is not in the `PnYnMnDTnHnMnS` form
+    Invalid,
+
+    /// The duration is too large to be represented
+    OutOfRange,
+}
+
+impl fmt::Display for XsdDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            XsdDurationError::Invalid => f.write_str("Invalid xsd:duration"),
+            XsdDurationError::OutOfRange => f.write_str("xsd:duration is out of range"),
+        }
+    }
+}
+
+impl Error for XsdDurationError {}
@@ -0,0 +1,321 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! The RDF data model, and the N-Quads, N-Triples and Turtle formats for exchanging it
+//!
+//! JSON-LD documents describe an RDF dataset, a set of quads each made of a subject, predicate,
+//! object and an optional graph name. `JsonLd::to_rdf` converts a document into quads, which can
+//! be written as N-Quads with `to_nquads` and read back with `parse_nquads`. The default graph can
+//! also be written as N-Triples with `to_ntriples`, or as Turtle with `to_turtle` and read back
+//! with `parse_turtle`. `JsonLd::to_turtle` and `JsonLd::from_turtle` go directly between the
+//! crate's types and Turtle.
+//!
+//! With the `urdna2015` feature, the `urdna2015` module can relabel the blank nodes of a dataset
+//! in a canonical way, so that two documents describing the same data produce the same N-Quads.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//!
+//! use activitystreams_types::rdf::{self, Literal, Quad, Term};
+//!
+//! # fn main() {
+//! let quads = rdf::parse_nquads(
+//!     "<https://example.com/notes/1> <https://www.w3.org/ns/activitystreams#content> \"Hello\"@en .\n",
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(
+//!     quads,
+//!     vec![Quad::new(
+//!         Term::iri("https://example.com/notes/1"),
+//!         Term::iri("https://www.w3.org/ns/activitystreams#content"),
+//!         Term::Literal(Literal::language("Hello", "en")),
+//!     )]
+//! );
+//!
+//! assert_eq!(
+//!     rdf::to_nquads(&quads),
+//!     "<https://example.com/notes/1> <https://www.w3.org/ns/activitystreams#content> \"Hello\"@en .\n"
+//! );
+//! # }
+//! ```
+
+mod nquads;
+mod turtle;
+#[cfg(feature = "urdna2015")]
+pub mod urdna2015;
+
+use std::{error::Error, fmt};
+
+pub use self::{
+    nquads::{parse_nquads, to_nquads, to_ntriples},
+    turtle::{parse_turtle, to_turtle},
+};
+
+/// The IRI of `rdf:type`
+pub const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// The IRI of `rdf:first`, which links a list node to its item
+pub const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+
+/// The IRI of `rdf:rest`, which links a list node to the rest of the list
+pub const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+
+/// The IRI of `rdf:nil`, the empty list
+pub const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+
+/// The datatype of literals with a language tag
+pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+/// The datatype of plain string literals
+pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+/// The datatype of `true` and `false`
+pub const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+
+/// The datatype of whole numbers
+pub const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+
+/// The datatype of decimal numbers
+pub const XSD_DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
+
+/// The datatype of floating point numbers
+pub const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+/// A node or value in an RDF dataset
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Term {
+    /// A node named by an absolute IRI
+    Iri(String),
+
+    /// A node without a global name, identified by a label that is only meaningful within its
+    /// dataset
+    BlankNode(String),
+
+    /// A literal value
+    Literal(Literal),
+}
+
+impl Term {
+    /// Create a term for an IRI
+    pub fn iri<T>(iri: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Term::Iri(iri.into())
+    }
+
+    /// Create a term for a blank node, from its label without the `_:` prefix
+    pub fn blank_node<T>(label: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Term::BlankNode(label.into())
+    }
+
+    /// Fetch the IRI of the term, if it is an IRI
+    pub fn as_iri(&self) -> Option<&str> {
+        match *self {
+            Term::Iri(ref iri) => Some(iri),
+            _ => None,
+        }
+    }
+
+    /// Fetch the label of the term, if it is a blank node
+    pub fn as_blank_node(&self) -> Option<&str> {
+        match *self {
+            Term::BlankNode(ref label) => Some(label),
+            _ => None,
+        }
+    }
+
+    /// Fetch the literal value of the term, if it is a literal
+    pub fn as_literal(&self) -> Option<&Literal> {
+        match *self {
+            Term::Literal(ref literal) => Some(literal),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Term::Iri(ref iri) => write!(f, "<{}>", iri),
+            Term::BlankNode(ref label) => write!(f, "_:{}", label),
+            Term::Literal(ref literal) => fmt::Display::fmt(literal, f),
+        }
+    }
+}
+
+/// A literal value, such as a string, number or date
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Literal {
+    /// The lexical form of the value
+    pub value: String,
+
+    /// The IRI of the datatype of the value
+    ///
+    /// This is `rdf:langString` for literals with a language, and `xsd:string` for plain strings.
+    pub datatype: String,
+
+    /// The language of the value, if any
+    pub language: Option<String>,
+}
+
+impl Literal {
+    /// Create a plain string literal
+    pub fn string<T>(value: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Literal::typed(value, XSD_STRING)
+    }
+
+    /// Create a literal with a datatype
+    pub fn typed<T, D>(value: T, datatype: D) -> Self
+    where
+        T: Into<String>,
+        D: Into<String>,
+    {
+        Literal {
+            value: value.into(),
+            datatype: datatype.into(),
+            language: None,
+        }
+    }
+
+    /// Create a string literal in a language
+    pub fn language<T, L>(value: T, language: L) -> Self
+    where
+        T: Into<String>,
+        L: Into<String>,
+    {
+        Literal {
+            value: value.into(),
+            datatype: RDF_LANG_STRING.to_owned(),
+            language: Some(language.into()),
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.value.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                '\u{8}' => f.write_str("\\b")?,
+                '\u{c}' => f.write_str("\\f")?,
+                '\u{0}'..='\u{1f}' | '\u{7f}' => write!(f, "\\u{:04X}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        f.write_str("\"")?;
+
+        match self.language {
+            Some(ref language) => write!(f, "@{}", language),
+            None if self.datatype == XSD_STRING => Ok(()),
+            None => write!(f, "^^<{}>", self.datatype),
+        }
+    }
+}
+
+/// A statement in an RDF dataset
+///
+/// The `Display` implementation writes the quad as a line of N-Quads, without the trailing
+/// newline.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Quad {
+    /// The node the statement is about, an IRI or blank node
+    pub subject: Term,
+
+    /// The property, an IRI
+    pub predicate: Term,
+
+    /// The value of the property
+    pub object: Term,
+
+    /// The named graph the statement belongs to, or `None` for the default graph
+    pub graph: Option<Term>,
+}
+
+impl Quad {
+    /// Create a quad in the default graph
+    pub fn new(subject: Term, predicate: Term, object: Term) -> Self {
+        Quad {
+            subject,
+            predicate,
+            object,
+            graph: None,
+        }
+    }
+
+    /// Move the quad into a named graph
+    pub fn in_graph(mut self, graph: Term) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+}
+
+impl fmt::Display for Quad {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} ", self.subject, self.predicate, self.object)?;
+        if let Some(ref graph) = self.graph {
+            write!(f, "{} ", graph)?;
+        }
+        f.write_str(".")
+    }
+}
+
+/// The errors that can occur while reading or processing RDF
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RdfError {
+    /// A document in N-Quads, N-Triples or Turtle could not be parsed
+    Syntax {
+        /// The line the error was found on, starting at 1
+        line: usize,
+
+        /// What was wrong with the line
+        message: String,
+    },
+
+    /// Canonicalizing the dataset needed more work than is allowed
+    ///
+    /// Some datasets with many similar blank nodes take an exponential amount of time to
+    /// canonicalize, so the work done for untrusted input is limited.
+    TooComplex,
+}
+
+impl fmt::Display for RdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RdfError::Syntax { line, ref message } => {
+                write!(f, "Invalid RDF on line {}, {}", line, message)
+            }
+            RdfError::TooComplex => f.write_str("Dataset is too complex to canonicalize"),
+        }
+    }
+}
+
+impl Error for RdfError {}
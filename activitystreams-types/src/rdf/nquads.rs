@@ -0,0 +1,287 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Reading and writing the N-Quads and N-Triples formats
+
+use super::{Literal, Quad, RdfError, Term, RDF_LANG_STRING, XSD_STRING};
+
+/// Write quads as N-Quads, one quad per line
+pub fn to_nquads(quads: &[Quad]) -> String {
+    let mut output = String::new();
+    for quad in quads {
+        output.push_str(&quad.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+/// Write the quads in the default graph as N-Triples, one triple per line
+pub fn to_ntriples(quads: &[Quad]) -> String {
+    let triples: Vec<Quad> = quads
+        .iter()
+        .filter(|quad| quad.graph.is_none())
+        .cloned()
+        .collect();
+
+    to_nquads(&triples)
+}
+
+/// Read quads from an N-Quads document
+///
+/// N-Triples documents are also accepted, since they are N-Quads without graph names.
+pub fn parse_nquads(input: &str) -> Result<Vec<Quad>, RdfError> {
+    let mut quads = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let mut parser = Parser {
+            chars: line.chars().collect(),
+            position: 0,
+            line: index + 1,
+        };
+
+        if let Some(quad) = parser.quad()? {
+            quads.push(quad);
+        }
+    }
+
+    Ok(quads)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn quad(&mut self) -> Result<Option<Quad>, RdfError> {
+        self.skip_whitespace();
+        if self.at_end_of_statement() {
+            return Ok(None);
+        }
+
+        let subject = match self.term()? {
+            term @ Term::Iri(_) | term @ Term::BlankNode(_) => term,
+            Term::Literal(_) => return Err(self.error("a subject can not be a literal")),
+        };
+        self.skip_whitespace();
+
+        let predicate = match self.term()? {
+            term @ Term::Iri(_) => term,
+            _ => return Err(self.error("a predicate must be an IRI")),
+        };
+        self.skip_whitespace();
+
+        let object = self.term()?;
+        self.skip_whitespace();
+
+        let graph = if self.peek() == Some('.') {
+            None
+        } else {
+            match self.term()? {
+                term @ Term::Iri(_) | term @ Term::BlankNode(_) => Some(term),
+                Term::Literal(_) => return Err(self.error("a graph name can not be a literal")),
+            }
+        };
+        self.skip_whitespace();
+
+        if self.next() != Some('.') {
+            return Err(self.error("expected '.' at the end of the statement"));
+        }
+        self.skip_whitespace();
+        if !self.at_end_of_statement() {
+            return Err(self.error("unexpected content after '.'"));
+        }
+
+        Ok(Some(Quad {
+            subject,
+            predicate,
+            object,
+            graph,
+        }))
+    }
+
+    fn term(&mut self) -> Result<Term, RdfError> {
+        match self.peek() {
+            Some('<') => self.iri().map(Term::Iri),
+            Some('_') => self.blank_node(),
+            Some('"') => self.literal(),
+            _ => Err(self.error("expected an IRI, blank node or literal")),
+        }
+    }
+
+    fn iri(&mut self) -> Result<String, RdfError> {
+        self.expect('<')?;
+
+        let mut iri = String::new();
+        loop {
+            match self.next() {
+                Some('>') => return Ok(iri),
+                Some('\\') => match self.next() {
+                    Some('u') => iri.push(self.hex(4)?),
+                    Some('U') => iri.push(self.hex(8)?),
+                    _ => return Err(self.error("invalid escape in IRI")),
+                },
+                Some(c) if c <= ' ' || "<\"{}|^`".contains(c) => {
+                    return Err(self.error("invalid character in IRI"))
+                }
+                Some(c) => iri.push(c),
+                None => return Err(self.error("unterminated IRI")),
+            }
+        }
+    }
+
+    fn blank_node(&mut self) -> Result<Term, RdfError> {
+        self.expect('_')?;
+        self.expect(':')?;
+
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || "<\"".contains(c) {
+                break;
+            }
+            self.position += 1;
+        }
+
+        // A label may contain dots, but not end with one
+        while self.position > start && self.chars[self.position - 1] == '.' {
+            self.position -= 1;
+        }
+
+        if self.position == start {
+            return Err(self.error("empty blank node label"));
+        }
+
+        Ok(Term::BlankNode(
+            self.chars[start..self.position].iter().collect(),
+        ))
+    }
+
+    fn literal(&mut self) -> Result<Term, RdfError> {
+        self.expect('"')?;
+
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => break,
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('t') => '\t',
+                        Some('b') => '\u{8}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('f') => '\u{c}',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('\\') => '\\',
+                        Some('u') => self.hex(4)?,
+                        Some('U') => self.hex(8)?,
+                        _ => return Err(self.error("invalid escape in literal")),
+                    };
+                    value.push(c);
+                }
+                Some(c) => value.push(c),
+                None => return Err(self.error("unterminated literal")),
+            }
+        }
+
+        match self.peek() {
+            Some('@') => {
+                self.position += 1;
+                let start = self.position;
+                while let Some(c) = self.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '-') {
+                        break;
+                    }
+                    self.position += 1;
+                }
+                if self.position == start {
+                    return Err(self.error("empty language tag"));
+                }
+
+                Ok(Term::Literal(Literal {
+                    value,
+                    datatype: RDF_LANG_STRING.to_owned(),
+                    language: Some(self.chars[start..self.position].iter().collect()),
+                }))
+            }
+            Some('^') => {
+                self.expect('^')?;
+                self.expect('^')?;
+                let datatype = self.iri()?;
+
+                Ok(Term::Literal(Literal::typed(value, datatype)))
+            }
+            _ => Ok(Term::Literal(Literal::typed(value, XSD_STRING))),
+        }
+    }
+
+    fn hex(&mut self, digits: usize) -> Result<char, RdfError> {
+        if self.position + digits > self.chars.len() {
+            return Err(self.error("truncated unicode escape"));
+        }
+
+        let hex: String = self.chars[self.position..self.position + digits]
+            .iter()
+            .collect();
+        self.position += digits;
+
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(::std::char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RdfError> {
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ') | Some('\t') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn at_end_of_statement(&self) -> bool {
+        matches!(self.peek(), None | Some('#'))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.position += 1;
+        }
+        c
+    }
+
+    fn error(&self, message: &str) -> RdfError {
+        RdfError::Syntax {
+            line: self.line,
+            message: message.to_owned(),
+        }
+    }
+}
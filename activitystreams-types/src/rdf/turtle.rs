@@ -0,0 +1,716 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Reading and writing the Turtle format
+
+use std::collections::{hash_map::Entry, BTreeSet, HashMap};
+use url::Url;
+
+use super::{
+    Literal, Quad, RdfError, Term, RDF_FIRST, RDF_LANG_STRING, RDF_NIL, RDF_REST, RDF_TYPE,
+    XSD_BOOLEAN, XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER, XSD_STRING,
+};
+
+/// The prefixes used when writing Turtle, for the vocabularies used by ActivityPub
+const PREFIXES: &[(&str, &str)] = &[
+    ("as", "https://www.w3.org/ns/activitystreams#"),
+    ("ldp", "http://www.w3.org/ns/ldp#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("sec", "https://w3id.org/security#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// Write quads as Turtle
+///
+/// Statements are grouped by subject, and IRIs in the ActivityStreams, Security, RDF and XML
+/// Schema vocabularies are written with the `as:`, `sec:`, `rdf:` and `xsd:` prefixes. Turtle can
+/// not express named graphs, so only quads in the default graph are written.
+pub fn to_turtle(quads: &[Quad]) -> String {
+    let mut writer = Writer::default();
+
+    // Group statements by subject, then by predicate, keeping the order they first appear in.
+    // The indexes find the group of a subject, or of a subject and predicate, in the lists.
+    let mut subjects: Vec<(&Term, Vec<Statements>)> = Vec::new();
+    let mut subject_index: HashMap<&Term, usize> = HashMap::new();
+    let mut predicate_index: HashMap<(&Term, &Term), usize> = HashMap::new();
+    for quad in quads.iter().filter(|quad| quad.graph.is_none()) {
+        let index = *subject_index.entry(&quad.subject).or_insert_with(|| {
+            subjects.push((&quad.subject, Vec::new()));
+            subjects.len() - 1
+        });
+
+        let predicates = &mut subjects[index].1;
+        match predicate_index.entry((&quad.subject, &quad.predicate)) {
+            Entry::Occupied(entry) => predicates[*entry.get()].1.push(&quad.object),
+            Entry::Vacant(entry) => {
+                entry.insert(predicates.len());
+                predicates.push((&quad.predicate, vec![&quad.object]));
+            }
+        }
+    }
+
+    let mut body = String::new();
+    for (subject, mut predicates) in subjects {
+        // rdf:type is written first, as `a`
+        predicates.sort_by_key(|&(p, _)| p.as_iri() != Some(RDF_TYPE));
+
+        body.push_str(&writer.term(subject));
+        for (index, (predicate, objects)) in predicates.into_iter().enumerate() {
+            body.push_str(if index == 0 { " " } else { " ;\n    " });
+
+            if predicate.as_iri() == Some(RDF_TYPE) {
+                body.push('a');
+            } else {
+                body.push_str(&writer.term(predicate));
+            }
+
+            for (index, object) in objects.into_iter().enumerate() {
+                body.push_str(if index == 0 { " " } else { ", " });
+                body.push_str(&writer.term(object));
+            }
+        }
+        body.push_str(" .\n");
+    }
+
+    let mut output = String::new();
+    for &(prefix, namespace) in PREFIXES {
+        if writer.used.contains(prefix) {
+            output.push_str(&format!("@prefix {}: <{}> .\n", prefix, namespace));
+        }
+    }
+    if !output.is_empty() && !body.is_empty() {
+        output.push('\n');
+    }
+    output.push_str(&body);
+    output
+}
+
+/// A predicate and the objects a subject has for it
+type Statements<'a> = (&'a Term, Vec<&'a Term>);
+
+#[derive(Default)]
+struct Writer {
+    used: BTreeSet<&'static str>,
+}
+
+impl Writer {
+    fn term(&mut self, term: &Term) -> String {
+        match *term {
+            Term::Iri(ref iri) => self.iri(iri),
+            Term::BlankNode(ref label) => format!("_:{}", label),
+            Term::Literal(ref literal) => {
+                let mut output = Literal::string(literal.value.as_str()).to_string();
+
+                match literal.language {
+                    Some(ref language) => {
+                        output.push('@');
+                        output.push_str(language);
+                    }
+                    None if literal.datatype != XSD_STRING => {
+                        output.push_str("^^");
+                        output.push_str(&self.iri(&literal.datatype));
+                    }
+                    None => (),
+                }
+
+                output
+            }
+        }
+    }
+
+    fn iri(&mut self, iri: &str) -> String {
+        for &(prefix, namespace) in PREFIXES {
+            if iri.starts_with(namespace) && is_simple_local(&iri[namespace.len()..]) {
+                self.used.insert(prefix);
+                return format!("{}:{}", prefix, &iri[namespace.len()..]);
+            }
+        }
+
+        format!("<{}>", iri)
+    }
+}
+
+/// Check whether a local name can be written after a prefix without escaping
+fn is_simple_local(local: &str) -> bool {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+
+    !local.is_empty() && local.chars().all(valid) && !local.starts_with('-')
+}
+
+/// Read quads from a Turtle document
+///
+/// Every statement is placed in the default graph. Relative IRIs are resolved against the
+/// document's `@base`, and are kept as they are if it has none.
+pub fn parse_turtle(input: &str) -> Result<Vec<Quad>, RdfError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        position: 0,
+        line: 1,
+        prefixes: HashMap::new(),
+        base: None,
+        labels: HashMap::new(),
+        counter: 0,
+        quads: Vec::new(),
+    };
+
+    parser.document()?;
+    Ok(parser.quads)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    prefixes: HashMap<String, String>,
+    base: Option<Url>,
+    labels: HashMap<String, String>,
+    counter: usize,
+    quads: Vec<Quad>,
+}
+
+impl Parser {
+    fn document(&mut self) -> Result<(), RdfError> {
+        loop {
+            self.skip_whitespace();
+            if self.peek().is_none() {
+                return Ok(());
+            }
+
+            if self.keyword("@prefix") {
+                self.prefix()?;
+                self.expect_dot()?;
+            } else if self.keyword("@base") {
+                self.base()?;
+                self.expect_dot()?;
+            } else if self.keyword("PREFIX") {
+                self.prefix()?;
+            } else if self.keyword("BASE") {
+                self.base()?;
+            } else {
+                self.triples()?;
+                self.expect_dot()?;
+            }
+        }
+    }
+
+    fn prefix(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c == ':' {
+                break;
+            }
+            if !is_name_char(c) {
+                return Err(self.error("invalid prefix name"));
+            }
+            self.position += 1;
+        }
+        let prefix: String = self.chars[start..self.position].iter().collect();
+        self.expect(':')?;
+
+        self.skip_whitespace();
+        let namespace = self.iri_ref()?;
+        self.prefixes.insert(prefix, namespace);
+        Ok(())
+    }
+
+    fn base(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        let base = self.iri_ref()?;
+        self.base = Some(Url::parse(&base).map_err(|_| self.error("invalid base IRI"))?);
+        Ok(())
+    }
+
+    fn triples(&mut self) -> Result<(), RdfError> {
+        let subject = match self.peek() {
+            Some('[') => {
+                let subject = self.blank_node_property_list()?;
+                self.skip_whitespace();
+                if self.peek() == Some('.') {
+                    return Ok(());
+                }
+                subject
+            }
+            Some('(') => self.collection()?,
+            _ => match self.term()? {
+                term @ Term::Iri(_) | term @ Term::BlankNode(_) => term,
+                Term::Literal(_) => return Err(self.error("a subject can not be a literal")),
+            },
+        };
+
+        self.predicate_object_list(&subject)
+    }
+
+    fn predicate_object_list(&mut self, subject: &Term) -> Result<(), RdfError> {
+        loop {
+            self.skip_whitespace();
+            let predicate = self.verb()?;
+
+            loop {
+                self.skip_whitespace();
+                let object = self.object()?;
+                self.quads
+                    .push(Quad::new(subject.clone(), predicate.clone(), object));
+
+                self.skip_whitespace();
+                if self.peek() != Some(',') {
+                    break;
+                }
+                self.position += 1;
+            }
+
+            // Any number of semicolons may separate predicates, and one may end the list
+            let mut more = false;
+            while self.peek() == Some(';') {
+                self.position += 1;
+                self.skip_whitespace();
+                more = true;
+            }
+            match self.peek() {
+                Some('.') | Some(']') | None if more => return Ok(()),
+                _ if more => (),
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn verb(&mut self) -> Result<Term, RdfError> {
+        if self.peek() == Some('a') {
+            let next = self.chars.get(self.position + 1).cloned();
+            if !matches!(next, Some(c) if !(c.is_whitespace() || c == '<' || c == '[')) {
+                self.position += 1;
+                return Ok(Term::iri(RDF_TYPE));
+            }
+        }
+
+        match self.term()? {
+            term @ Term::Iri(_) => Ok(term),
+            _ => Err(self.error("a predicate must be an IRI")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Term, RdfError> {
+        match self.peek() {
+            Some('[') => self.blank_node_property_list(),
+            Some('(') => self.collection(),
+            _ => self.term(),
+        }
+    }
+
+    fn blank_node_property_list(&mut self) -> Result<Term, RdfError> {
+        self.expect('[')?;
+        let node = self.new_blank_node();
+
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            self.predicate_object_list(&node)?;
+            self.skip_whitespace();
+        }
+        self.expect(']')?;
+
+        Ok(node)
+    }
+
+    fn collection(&mut self) -> Result<Term, RdfError> {
+        self.expect('(')?;
+
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(')') {
+                self.position += 1;
+                break;
+            }
+            items.push(self.object()?);
+        }
+
+        let mut rest = Term::iri(RDF_NIL);
+        for item in items.into_iter().rev() {
+            let node = self.new_blank_node();
+            self.quads
+                .push(Quad::new(node.clone(), Term::iri(RDF_FIRST), item));
+            self.quads
+                .push(Quad::new(node.clone(), Term::iri(RDF_REST), rest));
+            rest = node;
+        }
+
+        Ok(rest)
+    }
+
+    fn term(&mut self) -> Result<Term, RdfError> {
+        match self.peek() {
+            Some('<') => {
+                let iri = self.iri_ref()?;
+                Ok(Term::Iri(self.resolve(iri)))
+            }
+            Some('_') if self.chars.get(self.position + 1) == Some(&':') => {
+                self.position += 2;
+                let label = self.name();
+                if label.is_empty() {
+                    return Err(self.error("empty blank node label"));
+                }
+                Ok(self.labelled_blank_node(&label))
+            }
+            Some('"') | Some('\'') => self.literal(),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => self.number(),
+            _ => {
+                if self.keyword("true") {
+                    return Ok(Term::Literal(Literal::typed("true", XSD_BOOLEAN)));
+                }
+                if self.keyword("false") {
+                    return Ok(Term::Literal(Literal::typed("false", XSD_BOOLEAN)));
+                }
+                self.prefixed_name().map(Term::Iri)
+            }
+        }
+    }
+
+    fn iri_ref(&mut self) -> Result<String, RdfError> {
+        self.expect('<')?;
+
+        let mut iri = String::new();
+        loop {
+            match self.next() {
+                Some('>') => return Ok(iri),
+                Some('\\') => match self.next() {
+                    Some('u') => iri.push(self.hex(4)?),
+                    Some('U') => iri.push(self.hex(8)?),
+                    _ => return Err(self.error("invalid escape in IRI")),
+                },
+                Some(c) if c <= ' ' || "<\"{}|^`".contains(c) => {
+                    return Err(self.error("invalid character in IRI"))
+                }
+                Some(c) => iri.push(c),
+                None => return Err(self.error("unterminated IRI")),
+            }
+        }
+    }
+
+    fn prefixed_name(&mut self) -> Result<String, RdfError> {
+        let prefix = self.name();
+        if self.next() != Some(':') {
+            return Err(self.error("expected an IRI, blank node or literal"));
+        }
+
+        let namespace = match self.prefixes.get(&prefix) {
+            Some(namespace) => namespace.clone(),
+            None => return Err(self.error(&format!("undefined prefix {:?}", prefix))),
+        };
+
+        let mut local = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\\' => {
+                    self.position += 1;
+                    match self.next() {
+                        Some(c) if "_~.-!$&'()*+,;=/?#@%".contains(c) => local.push(c),
+                        _ => return Err(self.error("invalid escape in local name")),
+                    }
+                }
+                '%' => {
+                    let encoded: String = self.chars.iter().skip(self.position).take(3).collect();
+                    if encoded.len() != 3 || !encoded[1..].chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(self.error("invalid percent encoding in local name"));
+                    }
+                    local.push_str(&encoded);
+                    self.position += 3;
+                }
+                c if is_name_char(c) || c == ':' => {
+                    local.push(c);
+                    self.position += 1;
+                }
+                _ => break,
+            }
+        }
+
+        // A local name may contain dots, but not end with one
+        while local.ends_with('.') {
+            local.pop();
+            self.position -= 1;
+        }
+
+        Ok(self.resolve(format!("{}{}", namespace, local)))
+    }
+
+    fn literal(&mut self) -> Result<Term, RdfError> {
+        let quote = self
+            .next()
+            .ok_or_else(|| self.error("expected a literal"))?;
+        let long = self.chars.get(self.position) == Some(&quote)
+            && self.chars.get(self.position + 1) == Some(&quote);
+        if long {
+            self.position += 2;
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote && !long => break,
+                Some(c)
+                    if c == quote
+                        && self.chars.get(self.position) == Some(&quote)
+                        && self.chars.get(self.position + 1) == Some(&quote)
+                        && self.chars.get(self.position + 2) != Some(&quote) =>
+                {
+                    self.position += 2;
+                    break;
+                }
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('t') => '\t',
+                        Some('b') => '\u{8}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('f') => '\u{c}',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('\\') => '\\',
+                        Some('u') => self.hex(4)?,
+                        Some('U') => self.hex(8)?,
+                        _ => return Err(self.error("invalid escape in literal")),
+                    };
+                    value.push(c);
+                }
+                Some('\n') if !long => return Err(self.error("unterminated literal")),
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    value.push(c);
+                }
+                None => return Err(self.error("unterminated literal")),
+            }
+        }
+
+        match self.peek() {
+            Some('@') => {
+                self.position += 1;
+                let start = self.position;
+                while let Some(c) = self.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '-') {
+                        break;
+                    }
+                    self.position += 1;
+                }
+                if self.position == start {
+                    return Err(self.error("empty language tag"));
+                }
+
+                Ok(Term::Literal(Literal {
+                    value,
+                    datatype: RDF_LANG_STRING.to_owned(),
+                    language: Some(self.chars[start..self.position].iter().collect()),
+                }))
+            }
+            Some('^') => {
+                self.expect('^')?;
+                self.expect('^')?;
+                let datatype = match self.term()? {
+                    Term::Iri(iri) => iri,
+                    _ => return Err(self.error("a datatype must be an IRI")),
+                };
+
+                Ok(Term::Literal(Literal::typed(value, datatype)))
+            }
+            _ => Ok(Term::Literal(Literal::string(value))),
+        }
+    }
+
+    fn number(&mut self) -> Result<Term, RdfError> {
+        let start = self.position;
+        if let Some('+') | Some('-') = self.peek() {
+            self.position += 1;
+        }
+
+        let mut datatype = XSD_INTEGER;
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='9' => (),
+                // A dot followed by something other than a digit ends the statement
+                '.' if matches!(self.chars.get(self.position + 1), Some(c) if c.is_ascii_digit()) => {
+                    if datatype == XSD_INTEGER {
+                        datatype = XSD_DECIMAL;
+                    }
+                }
+                'e' | 'E' => {
+                    datatype = XSD_DOUBLE;
+                    if let Some('+') | Some('-') = self.chars.get(self.position + 1) {
+                        self.position += 1;
+                    }
+                }
+                _ => break,
+            }
+            self.position += 1;
+        }
+
+        let lexical: String = self.chars[start..self.position].iter().collect();
+        if !lexical.chars().any(|c| c.is_ascii_digit()) {
+            return Err(self.error("invalid number"));
+        }
+
+        Ok(Term::Literal(Literal::typed(lexical, datatype)))
+    }
+
+    fn name(&mut self) -> String {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if !is_name_char(c) {
+                break;
+            }
+            self.position += 1;
+        }
+        while self.position > start && self.chars[self.position - 1] == '.' {
+            self.position -= 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn resolve(&self, iri: String) -> String {
+        match self.base {
+            Some(ref base) if !is_absolute(&iri) => {
+                base.join(&iri).map(String::from).unwrap_or(iri)
+            }
+            _ => iri,
+        }
+    }
+
+    fn new_blank_node(&mut self) -> Term {
+        let term = Term::BlankNode(format!("b{}", self.counter));
+        self.counter += 1;
+        term
+    }
+
+    /// Give a labelled blank node a fresh label, so it can not clash with generated ones
+    fn labelled_blank_node(&mut self, label: &str) -> Term {
+        if let Some(existing) = self.labels.get(label) {
+            return Term::BlankNode(existing.clone());
+        }
+
+        let term = self.new_blank_node();
+        if let Term::BlankNode(ref fresh) = term {
+            self.labels.insert(label.to_owned(), fresh.clone());
+        }
+        term
+    }
+
+    fn hex(&mut self, digits: usize) -> Result<char, RdfError> {
+        if self.position + digits > self.chars.len() {
+            return Err(self.error("truncated unicode escape"));
+        }
+
+        let hex: String = self.chars[self.position..self.position + digits]
+            .iter()
+            .collect();
+        self.position += digits;
+
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(::std::char::from_u32)
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    /// Consume a keyword if it is next, matching `PREFIX` and `BASE` without regard to case
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let end = self.position + keyword.len();
+        if end > self.chars.len() {
+            return false;
+        }
+
+        let found: String = self.chars[self.position..end].iter().collect();
+        let matches = if keyword.starts_with('@') || keyword == "true" || keyword == "false" {
+            found == keyword
+        } else {
+            found.eq_ignore_ascii_case(keyword)
+        };
+        let terminated = !matches!(self.chars.get(end), Some(&c) if is_name_char(c) || c == ':');
+
+        if matches && terminated {
+            self.position = end;
+        }
+        matches && terminated
+    }
+
+    fn expect_dot(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        self.expect('.')
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RdfError> {
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", expected)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while let Some(c) = self.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    self.position += 1;
+                }
+            } else if c.is_whitespace() {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.position += 1;
+        }
+        c
+    }
+
+    fn error(&self, message: &str) -> RdfError {
+        RdfError::Syntax {
+            line: self.line,
+            message: message.to_owned(),
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '\u{b7}'
+}
+
+fn is_absolute(iri: &str) -> bool {
+    match iri.find(':') {
+        Some(index) => {
+            index > 0
+                && iri[..index]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,419 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! The URDNA2015 RDF dataset canonicalization algorithm
+//!
+//! Blank nodes have labels that are only meaningful within a single dataset, so the same data
+//! converted from JSON-LD twice may label them differently. URDNA2015 relabels every blank node
+//! based on the statements it takes part in, producing labels `c14n0`, `c14n1` and so on that
+//! only depend on the data itself. This is the canonicalization used by Linked Data Signatures.
+//!
+//! This module is only available with the `urdna2015` feature. The algorithm is described in the
+//! [RDF Dataset Canonicalization](https://www.w3.org/TR/rdf-canon/) specification, where it is
+//! also known as RDFC-1.0.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//!
+//! use activitystreams_types::rdf::{self, urdna2015};
+//!
+//! # fn main() {
+//! let first = rdf::parse_nquads(
+//!     "_:x <http://example.org/vocab#p> \"a\" .\n_:x <http://example.org/vocab#q> _:y .\n",
+//! )
+//! .unwrap();
+//! let second = rdf::parse_nquads(
+//!     "_:b1 <http://example.org/vocab#q> _:b0 .\n_:b1 <http://example.org/vocab#p> \"a\" .\n",
+//! )
+//! .unwrap();
+//!
+//! let canonical = urdna2015::canonicalize_to_nquads(&first).unwrap();
+//! assert_eq!(canonical, urdna2015::canonicalize_to_nquads(&second).unwrap());
+//! assert_eq!(
+//!     canonical,
+//!     "_:c14n1 <http://example.org/vocab#p> \"a\" .\n_:c14n1 <http://example.org/vocab#q> _:c14n0 .\n"
+//! );
+//! # }
+//! ```
+
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::{to_nquads, Quad, RdfError, Term};
+
+/// How many times the N-degree hash may be computed for a dataset before giving up
+const MAX_DEEP_HASHES: usize = 10_000;
+
+/// Canonicalize a dataset, returning its quads with canonical blank node labels, sorted in their
+/// canonical order
+///
+/// Duplicate quads are removed, since a dataset is a set of statements.
+pub fn canonicalize(quads: &[Quad]) -> Result<Vec<Quad>, RdfError> {
+    let mut state = State::new(quads);
+    state.label()?;
+
+    let mut canonical: Vec<(String, Quad)> = state
+        .quads
+        .iter()
+        .map(|quad| {
+            let quad = relabel(quad, |label| state.canonical.get(label).unwrap_or(label));
+            (quad.to_string(), quad)
+        })
+        .collect();
+    canonical.sort_by(|a, b| a.0.cmp(&b.0));
+    canonical.dedup_by(|a, b| a.0 == b.0);
+
+    Ok(canonical.into_iter().map(|(_, quad)| quad).collect())
+}
+
+/// Canonicalize a dataset and write it as N-Quads
+pub fn canonicalize_to_nquads(quads: &[Quad]) -> Result<String, RdfError> {
+    canonicalize(quads).map(|quads| to_nquads(&quads))
+}
+
+/// Issues new blank node labels in order, remembering which label each node was given
+#[derive(Clone, Debug)]
+struct IdentifierIssuer {
+    prefix: &'static str,
+    issued: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+impl IdentifierIssuer {
+    fn new(prefix: &'static str) -> Self {
+        IdentifierIssuer {
+            prefix,
+            issued: HashMap::new(),
+            order: Vec::new(),
+        }
+    }
+
+    fn issue(&mut self, label: &str) -> String {
+        if let Some(issued) = self.issued.get(label) {
+            return issued.clone();
+        }
+
+        let issued = format!("{}{}", self.prefix, self.order.len());
+        self.issued.insert(label.to_owned(), issued.clone());
+        self.order.push(label.to_owned());
+        issued
+    }
+
+    fn get(&self, label: &str) -> Option<&str> {
+        self.issued.get(label).map(String::as_str)
+    }
+
+    fn contains(&self, label: &str) -> bool {
+        self.issued.contains_key(label)
+    }
+}
+
+struct State {
+    quads: Vec<Quad>,
+    /// The blank nodes of the dataset, in the order they first appear
+    blank_nodes: Vec<String>,
+    /// The indices of the quads each blank node appears in
+    blank_node_quads: HashMap<String, Vec<usize>>,
+    first_degree: HashMap<String, String>,
+    canonical: IdentifierIssuer,
+    deep_hashes: usize,
+}
+
+impl State {
+    fn new(quads: &[Quad]) -> Self {
+        let mut seen = BTreeSet::new();
+        let quads: Vec<Quad> = quads
+            .iter()
+            .filter(|quad| seen.insert(*quad))
+            .cloned()
+            .collect();
+
+        let mut blank_nodes = Vec::new();
+        let mut blank_node_quads: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (index, quad) in quads.iter().enumerate() {
+            for term in components(quad) {
+                if let Term::BlankNode(ref label) = *term {
+                    let indices = blank_node_quads.entry(label.clone()).or_insert_with(|| {
+                        blank_nodes.push(label.clone());
+                        Vec::new()
+                    });
+                    if indices.last() != Some(&index) {
+                        indices.push(index);
+                    }
+                }
+            }
+        }
+
+        State {
+            quads,
+            blank_nodes,
+            blank_node_quads,
+            first_degree: HashMap::new(),
+            canonical: IdentifierIssuer::new("c14n"),
+            deep_hashes: 0,
+        }
+    }
+
+    /// Issue a canonical label to every blank node
+    fn label(&mut self) -> Result<(), RdfError> {
+        let mut hash_to_blank_nodes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for label in self.blank_nodes.clone() {
+            let hash = self.hash_first_degree(&label);
+            hash_to_blank_nodes.entry(hash).or_default().push(label);
+        }
+
+        // Blank nodes with a unique first degree hash can be labelled directly
+        let mut shared = Vec::new();
+        for (_, labels) in hash_to_blank_nodes {
+            if labels.len() == 1 {
+                self.canonical.issue(&labels[0]);
+            } else {
+                shared.push(labels);
+            }
+        }
+
+        // Others are told apart by the blank nodes around them
+        for labels in shared {
+            let mut paths = Vec::new();
+            for label in labels {
+                if self.canonical.contains(&label) {
+                    continue;
+                }
+
+                let mut issuer = IdentifierIssuer::new("b");
+                issuer.issue(&label);
+                paths.push(self.hash_n_degree(&label, issuer)?);
+            }
+
+            paths.sort_by(|a, b| a.0.cmp(&b.0));
+            for (_, issuer) in paths {
+                for label in issuer.order {
+                    self.canonical.issue(&label);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hash_first_degree(&mut self, label: &str) -> String {
+        if let Some(hash) = self.first_degree.get(label) {
+            return hash.clone();
+        }
+
+        let mut lines: Vec<String> = self.blank_node_quads[label]
+            .iter()
+            .map(|&index| {
+                let quad = relabel(
+                    &self.quads[index],
+                    |other| {
+                        if other == label {
+                            "a"
+                        } else {
+                            "z"
+                        }
+                    },
+                );
+                format!("{}\n", quad)
+            })
+            .collect();
+        lines.sort();
+
+        let hash = sha256(&lines.concat());
+        self.first_degree.insert(label.to_owned(), hash.clone());
+        hash
+    }
+
+    fn hash_related(
+        &mut self,
+        related: &str,
+        quad: usize,
+        issuer: &IdentifierIssuer,
+        position: &str,
+    ) -> String {
+        let identifier = match self.canonical.get(related).or_else(|| issuer.get(related)) {
+            Some(issued) => format!("_:{}", issued),
+            None => self.hash_first_degree(related),
+        };
+
+        let mut input = position.to_owned();
+        if position != "g" {
+            input.push_str(&self.quads[quad].predicate.to_string());
+        }
+        input.push_str(&identifier);
+
+        sha256(&input)
+    }
+
+    fn hash_n_degree(
+        &mut self,
+        label: &str,
+        mut issuer: IdentifierIssuer,
+    ) -> Result<(String, IdentifierIssuer), RdfError> {
+        self.deep_hashes += 1;
+        if self.deep_hashes > MAX_DEEP_HASHES {
+            return Err(RdfError::TooComplex);
+        }
+
+        let mut hash_to_related: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for index in self.blank_node_quads[label].clone() {
+            let quad = self.quads[index].clone();
+            let positions = [
+                ("s", Some(&quad.subject)),
+                ("o", Some(&quad.object)),
+                ("g", quad.graph.as_ref()),
+            ];
+
+            for &(position, term) in &positions {
+                if let Some(Term::BlankNode(related)) = term {
+                    if related != label {
+                        let hash = self.hash_related(related, index, &issuer, position);
+                        hash_to_related
+                            .entry(hash)
+                            .or_default()
+                            .push(related.clone());
+                    }
+                }
+            }
+        }
+
+        let mut data_to_hash = String::new();
+
+        for (related_hash, mut related) in hash_to_related {
+            data_to_hash.push_str(&related_hash);
+
+            let mut chosen_path = String::new();
+            let mut chosen_issuer = None;
+
+            related.sort();
+            loop {
+                if let Some((path, issuer)) =
+                    self.permutation_path(&related, &issuer, &chosen_path)?
+                {
+                    if chosen_path.is_empty() || path < chosen_path {
+                        chosen_path = path;
+                        chosen_issuer = Some(issuer);
+                    }
+                }
+
+                if !next_permutation(&mut related) {
+                    break;
+                }
+            }
+
+            data_to_hash.push_str(&chosen_path);
+            if let Some(chosen_issuer) = chosen_issuer {
+                issuer = chosen_issuer;
+            }
+        }
+
+        Ok((sha256(&data_to_hash), issuer))
+    }
+
+    /// Build the path for one ordering of related blank nodes, or `None` if it can not be
+    /// smaller than the path already chosen
+    fn permutation_path(
+        &mut self,
+        permutation: &[String],
+        issuer: &IdentifierIssuer,
+        chosen_path: &str,
+    ) -> Result<Option<(String, IdentifierIssuer)>, RdfError> {
+        let mut issuer = issuer.clone();
+        let mut path = String::new();
+        let mut recursion = Vec::new();
+
+        for related in permutation {
+            match self.canonical.get(related) {
+                Some(canonical) => path.push_str(&format!("_:{}", canonical)),
+                None => {
+                    if !issuer.contains(related) {
+                        recursion.push(related.clone());
+                    }
+                    path.push_str(&format!("_:{}", issuer.issue(related)));
+                }
+            }
+
+            if !chosen_path.is_empty() && path.as_str() > chosen_path {
+                return Ok(None);
+            }
+        }
+
+        for related in recursion {
+            let (hash, result_issuer) = self.hash_n_degree(&related, issuer.clone())?;
+            path.push_str(&format!("_:{}<{}>", issuer.issue(&related), hash));
+            issuer = result_issuer;
+
+            if !chosen_path.is_empty() && path.as_str() > chosen_path {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some((path, issuer)))
+    }
+}
+
+/// The terms of a quad that may be blank nodes
+fn components(quad: &Quad) -> Vec<&Term> {
+    let mut terms = vec![&quad.subject, &quad.object];
+    terms.extend(quad.graph.as_ref());
+    terms
+}
+
+/// Copy a quad, replacing the label of every blank node
+fn relabel<'a, F>(quad: &'a Quad, mut label: F) -> Quad
+where
+    F: FnMut(&'a str) -> &'a str,
+{
+    let mut map = |term: &'a Term| match *term {
+        Term::BlankNode(ref old) => Term::BlankNode(label(old).to_owned()),
+        ref term => term.clone(),
+    };
+
+    Quad {
+        subject: map(&quad.subject),
+        predicate: quad.predicate.clone(),
+        object: map(&quad.object),
+        graph: quad.graph.as_ref().map(map),
+    }
+}
+
+/// Rearrange items into the next permutation in lexicographic order, returning false once the
+/// last permutation has been reached
+fn next_permutation(items: &mut [String]) -> bool {
+    let pivot = match (1..items.len()).rev().find(|&i| items[i - 1] < items[i]) {
+        Some(i) => i - 1,
+        None => return false,
+    };
+
+    let successor = (pivot + 1..items.len())
+        .rev()
+        .find(|&i| items[i] > items[pivot])
+        .expect("a larger item exists after the pivot");
+    items.swap(pivot, successor);
+    items[pivot + 1..].reverse();
+    true
+}
+
+fn sha256(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,87 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use primitives::XsdAnyUri;
+
+/// A cache of resolved values that forgets the least recently used value when it is full
+#[derive(Debug)]
+pub(crate) struct LruCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<XsdAnyUri, (u64, Value)>,
+    order: BTreeMap<u64, XsdAnyUri>,
+}
+
+impl LruCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn get(&mut self, id: &XsdAnyUri) -> Option<Value> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entry = self.entries.get_mut(id)?;
+        self.order.remove(&entry.0);
+        self.order.insert(tick, id.clone());
+        entry.0 = tick;
+
+        Some(entry.1.clone())
+    }
+
+    pub(crate) fn insert(&mut self, id: XsdAnyUri, value: Value) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        self.tick += 1;
+        if let Some((tick, _)) = self.entries.remove(&id) {
+            self.order.remove(&tick);
+        }
+
+        self.order.insert(self.tick, id.clone());
+        self.entries.insert(id, (self.tick, value));
+
+        while self.entries.len() > self.capacity {
+            match self.order.pop_first() {
+                Some((_, id)) => {
+                    self.entries.remove(&id);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
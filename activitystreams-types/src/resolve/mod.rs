@@ -0,0 +1,881 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Resolving references that are given only by id
+//!
+//! Properties such as `actor`, `object`, `inReplyTo` and `attributedTo` often hold only the id of
+//! the object they refer to, and the getters generated for them can only read objects that are
+//! embedded. A `Resolver` fetches the objects behind ids, and a `Dereferencer` uses one to follow
+//! references until it reaches an object.
+//!
+//! A reference may lead to another reference, such as a `Link` whose `href` names the object. The
+//! `Dereferencer` follows at most `max_depth` ids for each reference, fails when a reference leads
+//! back to an id it already followed, and keeps the values it fetched in a cache that forgets the
+//! least recently used values first.
+//!
+//! `Resolver` fetches values synchronously, and `AsyncResolver` returns futures. Both are
+//! implemented for a `HashMap` of ids to values, which is useful in tests.
+//!
+//! `ResolveExt` resolves the references in the properties of objects, and `PagingExt` walks
+//! through the items of collections across their pages.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! #[macro_use]
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     activity::Create,
+//!     actor::Person,
+//!     primitives::XsdAnyUri,
+//!     resolve::{Dereferencer, ResolveExt},
+//! };
+//! use std::collections::HashMap;
+//!
+//! # fn main() {
+//! let alice: XsdAnyUri = "https://example.com/users/alice".parse().unwrap();
+//!
+//! let mut objects = HashMap::new();
+//! objects.insert(alice.clone(), json!({
+//!     "type": "Person",
+//!     "id": "https://example.com/users/alice",
+//!     "name": "Alice"
+//! }));
+//!
+//! let dereferencer = Dereferencer::new(objects);
+//!
+//! let create: Create = serde_json::from_value(json!({
+//!     "type": "Create",
+//!     "actor": "https://example.com/users/alice",
+//!     "object": { "type": "Note", "content": "Hello" }
+//! })).unwrap();
+//!
+//! let actor = create.resolve_actor_object::<Person>(&dereferencer).unwrap().unwrap();
+//! assert_eq!(actor.object_props.name_string().unwrap(), "Alice");
+//!
+//! // The Person is now cached
+//! assert_eq!(dereferencer.cache_len(), 1);
+//! # }
+//! ```
+
+use activitystreams_traits::{
+    self,
+    properties::{from_value, to_value},
+};
+use serde::{de::DeserializeOwned, ser::Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    convert::Infallible,
+    error::Error,
+    fmt,
+    future::{self, Future},
+    pin::Pin,
+    sync::{Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use addressing::values;
+use primitives::XsdAnyUri;
+
+mod cache;
+mod paging;
+
+use self::cache::LruCache;
+pub use self::paging::{Items, ItemsAsync, NextItem, PagingExt, Stop, DEFAULT_MAX_PAGES};
+
+/// The number of values a `Dereferencer` caches, unless told otherwise
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+/// The number of ids a `Dereferencer` follows for a reference, unless told otherwise
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// The types of link whose `href` is followed
+const LINK_TYPES: &[&str] = &["Link", "Mention"];
+
+/// Fetch the value with a given id
+///
+/// Implementations usually fetch the value over HTTP, or read it from a store.
+pub trait Resolver {
+    /// The error produced when a value can not be fetched
+    type Error;
+
+    /// Fetch the value with a given id, or `None` if there is no such value
+    fn resolve(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error>;
+}
+
+/// The future returned by an `AsyncResolver`
+pub type ResolveFuture<'a, E> = Pin<Box<dyn Future<Output = Result<Option<Value>, E>> + Send + 'a>>;
+
+/// Fetch the value with a given id, asynchronously
+pub trait AsyncResolver {
+    /// The error produced when a value can not be fetched
+    type Error;
+
+    /// Fetch the value with a given id, or `None` if there is no such value
+    fn resolve(&self, id: XsdAnyUri) -> ResolveFuture<'_, Self::Error>;
+}
+
+impl Resolver for HashMap<XsdAnyUri, Value> {
+    type Error = Infallible;
+
+    fn resolve(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error> {
+        Ok(self.get(id).cloned())
+    }
+}
+
+impl AsyncResolver for HashMap<XsdAnyUri, Value> {
+    type Error = Infallible;
+
+    fn resolve(&self, id: XsdAnyUri) -> ResolveFuture<'_, Self::Error> {
+        Box::pin(future::ready(Ok(self.get(&id).cloned())))
+    }
+}
+
+/// The error produced when a reference can not be resolved
+#[derive(Debug)]
+pub enum ResolveError<E> {
+    /// A reference is not a valid IRI
+    InvalidId(String),
+
+    /// The resolver has no value for an id
+    NotFound(XsdAnyUri),
+
+    /// A reference leads back to an id that was already followed
+    Cycle(XsdAnyUri),
+
+    /// A reference leads to more ids than the dereferencer is allowed to follow
+    TooDeep(XsdAnyUri),
+
+    /// A value could not be serialized, or a resolved value could not be read as the requested
+    /// type
+    Serde(activitystreams_traits::Error),
+
+    /// The resolver failed
+    Resolver(E),
+}
+
+impl<E> fmt::Display for ResolveError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ResolveError::InvalidId(ref id) => write!(f, "Invalid reference {:?}", id),
+            ResolveError::NotFound(ref id) => write!(f, "Could not find {}", id),
+            ResolveError::Cycle(ref id) => write!(f, "Reference to {} forms a cycle", id),
+            ResolveError::TooDeep(ref id) => write!(f, "Reference to {} is nested too deeply", id),
+            ResolveError::Serde(ref e) => fmt::Display::fmt(e, f),
+            ResolveError::Resolver(ref e) => write!(f, "Resolver failed, {}", e),
+        }
+    }
+}
+
+impl<E> Error for ResolveError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ResolveError::Serde(ref e) => Some(e),
+            ResolveError::Resolver(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> From<activitystreams_traits::Error> for ResolveError<E> {
+    fn from(e: activitystreams_traits::Error) -> Self {
+        ResolveError::Serde(e)
+    }
+}
+
+/// Follows references through a resolver, caching the values it fetches
+///
+/// The same dereferencer can be shared between threads, and works with both `Resolver`s and
+/// `AsyncResolver`s.
+#[derive(Debug)]
+pub struct Dereferencer<R> {
+    resolver: R,
+    cache: Mutex<LruCache>,
+    max_depth: usize,
+}
+
+impl<R> Dereferencer<R> {
+    /// Create a dereferencer with the default cache capacity and depth limit
+    pub fn new(resolver: R) -> Self {
+        Dereferencer {
+            resolver,
+            cache: Mutex::new(LruCache::new(DEFAULT_CACHE_CAPACITY)),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Set how many values are cached, dropping the values cached so far
+    ///
+    /// A capacity of zero disables the cache.
+    pub fn cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = Mutex::new(LruCache::new(capacity));
+        self
+    }
+
+    /// Set how many ids are followed for a single reference
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Borrow the resolver
+    pub fn resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// The number of cached values
+    pub fn cache_len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Forget every cached value
+    pub fn clear_cache(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn chain(&self, origin: Option<&XsdAnyUri>) -> Chain {
+        Chain {
+            visited: origin.into_iter().cloned().collect(),
+            depth: 0,
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+impl<R> Dereferencer<R>
+where
+    R: Resolver,
+{
+    /// Follow a reference until it reaches an object
+    ///
+    /// The reference may be an id, a link, or an embedded object, which is returned as it is.
+    pub fn dereference(
+        &self,
+        reference: &Value,
+    ) -> Result<Value, ResolveError<<R as Resolver>::Error>> {
+        self.dereference_from(reference, None)
+    }
+
+    /// Follow a reference until it reaches an object, and read that object as a specific type
+    pub fn dereference_as<T>(
+        &self,
+        reference: &Value,
+    ) -> Result<T, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(from_value(&self.dereference(reference)?)?)
+    }
+
+    /// Follow a reference found in the object with the id `origin`, so a reference back to that
+    /// object is reported as a cycle
+    fn dereference_from(
+        &self,
+        reference: &Value,
+        origin: Option<&XsdAnyUri>,
+    ) -> Result<Value, ResolveError<<R as Resolver>::Error>> {
+        let mut chain = self.chain(origin);
+        let mut value = reference.clone();
+
+        loop {
+            let id = match chain.step(value)? {
+                Step::Done(value) => return Ok(value),
+                Step::Fetch(id) => id,
+            };
+
+            let cached = self.lock().get(&id);
+            value = match cached {
+                Some(value) => value,
+                None => {
+                    let value = self
+                        .resolver
+                        .resolve(&id)
+                        .map_err(ResolveError::Resolver)?
+                        .ok_or_else(|| ResolveError::NotFound(id.clone()))?;
+
+                    self.lock().insert(id, value.clone());
+                    value
+                }
+            };
+        }
+    }
+}
+
+impl<R> Dereferencer<R>
+where
+    R: AsyncResolver,
+{
+    /// Follow a reference until it reaches an object, asynchronously
+    pub fn dereference_async(&self, reference: &Value) -> Resolve<'_, R, Value> {
+        Resolve::new(self, vec![reference.clone()], None)
+            .map(|mut values| Ok(values.pop().expect("One reference leads to one value")))
+    }
+}
+
+/// The outcome of following one step of a reference
+enum Step {
+    /// The reference reached an object
+    Done(Value),
+
+    /// The reference leads to an id that must be fetched
+    Fetch(XsdAnyUri),
+}
+
+/// The state of following a single reference
+struct Chain {
+    visited: HashSet<XsdAnyUri>,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl Chain {
+    fn step<E>(&mut self, value: Value) -> Result<Step, ResolveError<E>> {
+        let id = match value {
+            Value::String(ref id) => id.as_str(),
+            Value::Object(ref link) if is_link(link) => {
+                link.get("href").and_then(Value::as_str).unwrap_or_default()
+            }
+            value => return Ok(Step::Done(value)),
+        };
+
+        let id: XsdAnyUri = id
+            .parse()
+            .map_err(|_| ResolveError::InvalidId(id.to_owned()))?;
+
+        if !self.visited.insert(id.clone()) {
+            return Err(ResolveError::Cycle(id));
+        }
+
+        if self.depth >= self.max_depth {
+            return Err(ResolveError::TooDeep(id));
+        }
+        self.depth += 1;
+
+        Ok(Step::Fetch(id))
+    }
+}
+
+/// Check whether an embedded value is a link to an object, rather than the object itself
+fn is_link(value: &Map<String, Value>) -> bool {
+    value.contains_key("href")
+        && match value.get("type") {
+            Some(Value::String(kind)) => LINK_TYPES.contains(&kind.as_str()),
+            None => true,
+            Some(_) => false,
+        }
+}
+
+/// Read the values a `Resolve` produces as its output
+type Finish<T, E> = fn(Vec<Value>) -> Result<T, ResolveError<E>>;
+
+/// A reference being fetched, with the state of following it and the id being fetched
+type Pending<'a, E> = (Chain, XsdAnyUri, ResolveFuture<'a, E>);
+
+/// A future that follows a list of references through an `AsyncResolver`
+///
+/// It produces the values the references lead to, in the same order.
+pub struct Resolve<'a, R, T = Vec<Value>>
+where
+    R: AsyncResolver + 'a,
+{
+    dereferencer: &'a Dereferencer<R>,
+    origin: Option<XsdAnyUri>,
+    references: VecDeque<Value>,
+    current: Option<Pending<'a, R::Error>>,
+    values: Vec<Value>,
+    error: Option<ResolveError<R::Error>>,
+    finish: Finish<T, R::Error>,
+}
+
+impl<'a, R> Resolve<'a, R>
+where
+    R: AsyncResolver + 'a,
+{
+    fn new(
+        dereferencer: &'a Dereferencer<R>,
+        references: Vec<Value>,
+        origin: Option<XsdAnyUri>,
+    ) -> Self {
+        Resolve {
+            dereferencer,
+            origin,
+            references: references.into(),
+            current: None,
+            values: Vec::new(),
+            error: None,
+            finish: Ok,
+        }
+    }
+
+    fn failed(dereferencer: &'a Dereferencer<R>, error: ResolveError<R::Error>) -> Self {
+        let mut resolve = Resolve::new(dereferencer, Vec::new(), None);
+        resolve.error = Some(error);
+        resolve
+    }
+
+    fn map<T>(self, finish: Finish<T, R::Error>) -> Resolve<'a, R, T> {
+        Resolve {
+            dereferencer: self.dereferencer,
+            origin: self.origin,
+            references: self.references,
+            current: self.current,
+            values: self.values,
+            error: self.error,
+            finish,
+        }
+    }
+}
+
+impl<'a, R, T> Resolve<'a, R, T>
+where
+    R: AsyncResolver + 'a,
+{
+    /// Follow a reference as far as the cache allows, and start fetching the next id if needed
+    fn advance(
+        &mut self,
+        mut chain: Chain,
+        mut value: Value,
+    ) -> Result<(), ResolveError<R::Error>> {
+        loop {
+            let id = match chain.step(value)? {
+                Step::Done(value) => {
+                    self.values.push(value);
+                    return Ok(());
+                }
+                Step::Fetch(id) => id,
+            };
+
+            let cached = self.dereferencer.lock().get(&id);
+            value = match cached {
+                Some(value) => value,
+                None => {
+                    let future = self.dereferencer.resolver.resolve(id.clone());
+                    self.current = Some((chain, id, future));
+                    return Ok(());
+                }
+            };
+        }
+    }
+}
+
+// The futures of the resolver are boxed, so nothing in a `Resolve` relies on being pinned
+impl<'a, R, T> Unpin for Resolve<'a, R, T> where R: AsyncResolver + 'a {}
+
+impl<'a, R, T> Future for Resolve<'a, R, T>
+where
+    R: AsyncResolver + 'a,
+{
+    type Output = Result<T, ResolveError<R::Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Err(error));
+        }
+
+        loop {
+            if let Some((chain, id, mut future)) = this.current.take() {
+                let value = match future.as_mut().poll(cx) {
+                    Poll::Pending => {
+                        this.current = Some((chain, id, future));
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Ok(Some(value))) => value,
+                    Poll::Ready(Ok(None)) => return Poll::Ready(Err(ResolveError::NotFound(id))),
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(ResolveError::Resolver(e))),
+                };
+
+                this.dereferencer.lock().insert(id, value.clone());
+                if let Err(e) = this.advance(chain, value) {
+                    return Poll::Ready(Err(e));
+                }
+                continue;
+            }
+
+            let reference = match this.references.pop_front() {
+                Some(reference) => reference,
+                None => {
+                    let values = ::std::mem::take(&mut this.values);
+                    return Poll::Ready((this.finish)(values));
+                }
+            };
+
+            let chain = this.dereferencer.chain(this.origin.as_ref());
+            if let Err(e) = this.advance(chain, reference) {
+                return Poll::Ready(Err(e));
+            }
+        }
+    }
+}
+
+/// The Resolve Extension Trait
+///
+/// This trait follows the references in the properties of objects and activities through a
+/// `Dereferencer`, and reads the objects they lead to as a specific type. It mirrors the getters
+/// generated for those properties, such as `actor_object`, which only read embedded objects.
+///
+/// A reference back to the object it is found in is reported as a cycle.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{
+///     object::Note,
+///     primitives::XsdAnyUri,
+///     resolve::{Dereferencer, ResolveError, ResolveExt},
+/// };
+/// use std::{
+///     collections::HashMap,
+///     future::Future,
+///     pin::pin,
+///     task::{Context, Poll, Waker},
+/// };
+///
+/// # fn main() {
+/// let mut objects = HashMap::new();
+/// objects.insert(
+///     "https://example.com/notes/1".parse::<XsdAnyUri>().unwrap(),
+///     json!({ "type": "Note", "id": "https://example.com/notes/1", "content": "Hello" }),
+/// );
+/// objects.insert(
+///     "https://example.com/links/1".parse::<XsdAnyUri>().unwrap(),
+///     json!({ "type": "Link", "href": "https://example.com/notes/1" }),
+/// );
+/// let dereferencer = Dereferencer::new(objects);
+///
+/// let reply: Note = serde_json::from_value(json!({
+///     "type": "Note",
+///     "id": "https://example.com/notes/2",
+///     "inReplyTo": "https://example.com/links/1",
+/// })).unwrap();
+///
+/// // The Link is followed to the Note it points to, asynchronously here
+/// let mut future = pin!(reply.resolve_in_reply_to_object_async::<Note>(&dereferencer));
+/// let parent = match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+///     Poll::Ready(parent) => parent.unwrap().unwrap(),
+///     Poll::Pending => unreachable!(),
+/// };
+/// assert_eq!(parent.object_props.content_string().unwrap(), "Hello");
+///
+/// // A Note replying to itself forms a cycle
+/// let loop_: Note = serde_json::from_value(json!({
+///     "type": "Note",
+///     "id": "https://example.com/notes/3",
+///     "inReplyTo": "https://example.com/notes/3",
+/// })).unwrap();
+///
+/// match loop_.resolve_in_reply_to_object::<Note>(&dereferencer) {
+///     Err(ResolveError::Cycle(id)) => assert_eq!(id.as_str(), "https://example.com/notes/3"),
+///     other => panic!("Expected a cycle, got {:?}", other),
+/// }
+/// # }
+/// ```
+pub trait ResolveExt<R>: Serialize {
+    /// Resolve the first reference in a property
+    fn resolve_property<T>(
+        &self,
+        property: &str,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Option<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        let (origin, references) = references(self, property, true)?;
+
+        match references.first() {
+            Some(reference) => Ok(Some(from_value(
+                &dereferencer.dereference_from(reference, origin.as_ref())?,
+            )?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Resolve every reference in a property
+    fn resolve_property_vec<T>(
+        &self,
+        property: &str,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Vec<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        let (origin, references) = references(self, property, false)?;
+
+        references
+            .iter()
+            .map(|reference| {
+                Ok(from_value(
+                    &dereferencer.dereference_from(reference, origin.as_ref())?,
+                )?)
+            })
+            .collect()
+    }
+
+    /// Resolve the first reference in a property, asynchronously
+    fn resolve_property_async<'a, T>(
+        &self,
+        property: &str,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Option<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        resolve_async(self, property, dereferencer, true).map(first)
+    }
+
+    /// Resolve every reference in a property, asynchronously
+    fn resolve_property_vec_async<'a, T>(
+        &self,
+        property: &str,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Vec<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        resolve_async(self, property, dereferencer, false).map(all)
+    }
+
+    /// Resolve the first `actor`
+    fn resolve_actor_object<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Option<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property("actor", dereferencer)
+    }
+
+    /// Resolve every `actor`
+    fn resolve_actor_object_vec<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Vec<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property_vec("actor", dereferencer)
+    }
+
+    /// Resolve the first `actor`, asynchronously
+    fn resolve_actor_object_async<'a, T>(
+        &self,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Option<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        self.resolve_property_async("actor", dereferencer)
+    }
+
+    /// Resolve the first `object`
+    fn resolve_object_object<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Option<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property("object", dereferencer)
+    }
+
+    /// Resolve every `object`
+    fn resolve_object_object_vec<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Vec<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property_vec("object", dereferencer)
+    }
+
+    /// Resolve the first `object`, asynchronously
+    fn resolve_object_object_async<'a, T>(
+        &self,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Option<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        self.resolve_property_async("object", dereferencer)
+    }
+
+    /// Resolve the first `inReplyTo`
+    fn resolve_in_reply_to_object<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Option<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property("inReplyTo", dereferencer)
+    }
+
+    /// Resolve every `inReplyTo`
+    fn resolve_in_reply_to_object_vec<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Vec<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property_vec("inReplyTo", dereferencer)
+    }
+
+    /// Resolve the first `inReplyTo`, asynchronously
+    fn resolve_in_reply_to_object_async<'a, T>(
+        &self,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Option<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        self.resolve_property_async("inReplyTo", dereferencer)
+    }
+
+    /// Resolve the first `attributedTo`
+    fn resolve_attributed_to_object<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Option<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property("attributedTo", dereferencer)
+    }
+
+    /// Resolve every `attributedTo`
+    fn resolve_attributed_to_object_vec<T>(
+        &self,
+        dereferencer: &Dereferencer<R>,
+    ) -> Result<Vec<T>, ResolveError<<R as Resolver>::Error>>
+    where
+        T: DeserializeOwned,
+        R: Resolver,
+    {
+        self.resolve_property_vec("attributedTo", dereferencer)
+    }
+
+    /// Resolve the first `attributedTo`, asynchronously
+    fn resolve_attributed_to_object_async<'a, T>(
+        &self,
+        dereferencer: &'a Dereferencer<R>,
+    ) -> Resolve<'a, R, Option<T>>
+    where
+        T: DeserializeOwned,
+        R: AsyncResolver + 'a,
+    {
+        self.resolve_property_async("attributedTo", dereferencer)
+    }
+}
+
+impl<T, R> ResolveExt<R> for T where T: Serialize {}
+
+/// Read the id of a value and the references in one of its properties
+fn references<T>(
+    value: &T,
+    property: &str,
+    only_first: bool,
+) -> activitystreams_traits::Result<(Option<XsdAnyUri>, Vec<Value>)>
+where
+    T: Serialize + ?Sized,
+{
+    let value = to_value(value)?;
+
+    let origin = value
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(|id| id.parse().ok());
+
+    let mut references: Vec<Value> = value
+        .get(property)
+        .map(values)
+        .unwrap_or_default()
+        .into_iter()
+        .cloned()
+        .collect();
+
+    if only_first {
+        references.truncate(1);
+    }
+
+    Ok((origin, references))
+}
+
+fn resolve_async<'a, T, R>(
+    value: &T,
+    property: &str,
+    dereferencer: &'a Dereferencer<R>,
+    only_first: bool,
+) -> Resolve<'a, R>
+where
+    T: Serialize + ?Sized,
+    R: AsyncResolver + 'a,
+{
+    match references(value, property, only_first) {
+        Ok((origin, references)) => Resolve::new(dereferencer, references, origin),
+        Err(e) => Resolve::failed(dereferencer, e.into()),
+    }
+}
+
+fn first<T, E>(values: Vec<Value>) -> Result<Option<T>, ResolveError<E>>
+where
+    T: DeserializeOwned,
+{
+    match values.first() {
+        Some(value) => Ok(Some(from_value(value)?)),
+        None => Ok(None),
+    }
+}
+
+fn all<T, E>(values: Vec<Value>) -> Result<Vec<T>, ResolveError<E>>
+where
+    T: DeserializeOwned,
+{
+    values.iter().map(|value| Ok(from_value(value)?)).collect()
+}
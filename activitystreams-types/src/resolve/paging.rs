@@ -0,0 +1,477 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Walking the pages of collections
+
+use activitystreams_traits::{properties::to_value, Collection};
+use serde::ser::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use addressing::{address_id, values};
+use primitives::XsdAnyUri;
+use resolve::{AsyncResolver, Dereferencer, Resolve, ResolveError, Resolver};
+
+/// The number of pages walked through, unless told otherwise
+pub const DEFAULT_MAX_PAGES: usize = 64;
+
+/// Why a walk through the pages of a collection stopped
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Stop {
+    /// The last page was reached
+    End,
+
+    /// A page links back to a page that was already walked through
+    Cycle(XsdAnyUri),
+
+    /// As many pages as allowed were walked through
+    Budget,
+
+    /// A page could not be resolved
+    Error,
+}
+
+/// The order in which the pages of a collection are walked through
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Direction {
+    /// From `first` through `next`
+    Forward,
+
+    /// From `last` through `prev`
+    Backward,
+}
+
+/// The state of a walk through the pages of a collection, shared by `Items` and `ItemsAsync`
+#[derive(Debug)]
+struct Walk<E> {
+    direction: Direction,
+    collection: Option<Value>,
+    items: VecDeque<Value>,
+    link: Option<Value>,
+    visited: HashSet<XsdAnyUri>,
+    pages: usize,
+    max_pages: usize,
+    error: Option<ResolveError<E>>,
+    stop: Option<Stop>,
+}
+
+impl<E> Walk<E> {
+    fn new<T>(collection: &T) -> Self
+    where
+        T: Serialize,
+    {
+        let mut walk = Walk {
+            direction: Direction::Forward,
+            collection: None,
+            items: VecDeque::new(),
+            link: None,
+            visited: HashSet::new(),
+            pages: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            error: None,
+            stop: None,
+        };
+
+        match to_value(collection) {
+            Ok(collection) => walk.collection = Some(collection),
+            Err(e) => walk.error = Some(e.into()),
+        }
+
+        walk
+    }
+
+    /// The properties linking to the first page, and from each page to the page after it
+    fn links(&self) -> (&'static str, &'static str) {
+        match self.direction {
+            Direction::Forward => ("first", "next"),
+            Direction::Backward => ("last", "prev"),
+        }
+    }
+
+    /// Take the items of the collection itself, and find the first page to walk through
+    ///
+    /// A walk starting from a page goes on from its `next` or `prev` only, since its `first` and
+    /// `last` lead back to the pages around it.
+    fn enter(&mut self, collection: Value) {
+        let (start, step) = self.links();
+
+        self.link = if is_page(&collection) {
+            collection.get(step).cloned()
+        } else {
+            collection
+                .get(start)
+                .or_else(|| collection.get(step))
+                .cloned()
+        };
+        self.take_items(&collection);
+    }
+
+    /// Take the items of a page, and remember it was walked through
+    fn take_items(&mut self, page: &Value) {
+        if let Some(id) = page.get("id").and_then(Value::as_str) {
+            if let Ok(id) = id.parse() {
+                self.visited.insert(id);
+            }
+        }
+
+        let mut items: Vec<Value> = page
+            .get("orderedItems")
+            .or_else(|| page.get("items"))
+            .map(values)
+            .unwrap_or_default()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        // Walking backward, the items of each page are read from last to first
+        if self.direction == Direction::Backward {
+            items.reverse();
+        }
+        self.items.extend(items);
+    }
+
+    /// Take the next item, or the error that stopped the walk
+    fn pop(&mut self) -> Option<Result<Value, ResolveError<E>>> {
+        if let Some(collection) = self.collection.take() {
+            self.enter(collection);
+        }
+
+        if let Some(e) = self.error.take() {
+            self.stop = Some(Stop::Error);
+            return Some(Err(e));
+        }
+
+        self.items.pop_front().map(Ok)
+    }
+
+    /// Find the page to walk through next, or stop the walk
+    fn next_link(&mut self) -> Option<Value> {
+        if self.stop.is_some() {
+            return None;
+        }
+
+        let link = match self.link.take() {
+            Some(link) => link,
+            None => {
+                self.stop = Some(Stop::End);
+                return None;
+            }
+        };
+
+        if let Some(id) = address_id(&link).and_then(|id| id.parse::<XsdAnyUri>().ok()) {
+            if self.visited.contains(&id) {
+                self.stop = Some(Stop::Cycle(id));
+                return None;
+            }
+        }
+
+        if self.pages >= self.max_pages {
+            self.stop = Some(Stop::Budget);
+            return None;
+        }
+        self.pages += 1;
+
+        Some(link)
+    }
+
+    /// Take a resolved page, unless it was already walked through
+    fn accept(&mut self, page: Value) {
+        if let Some(id) = page
+            .get("id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.parse::<XsdAnyUri>().ok())
+        {
+            if self.visited.contains(&id) {
+                self.stop = Some(Stop::Cycle(id));
+                return;
+            }
+        }
+
+        self.take_items(&page);
+        self.link = page.get(self.links().1).cloned();
+    }
+
+    fn fail(&mut self, error: ResolveError<E>) -> Option<Result<Value, ResolveError<E>>> {
+        self.stop = Some(Stop::Error);
+        Some(Err(error))
+    }
+}
+
+/// An iterator over the items of a collection, across its pages
+///
+/// Pages are resolved lazily, as the items of the pages before them run out. The walk stops at
+/// the last page, when a page links back to a page that was already walked through, or when as
+/// many pages as allowed were resolved. `stop` tells which of these happened.
+///
+/// Items are produced as they appear in the pages, which may be ids, links or embedded objects.
+/// They can be resolved with the same `Dereferencer`.
+#[derive(Debug)]
+pub struct Items<'a, R>
+where
+    R: Resolver + 'a,
+{
+    dereferencer: &'a Dereferencer<R>,
+    walk: Walk<R::Error>,
+}
+
+impl<'a, R> Items<'a, R>
+where
+    R: Resolver + 'a,
+{
+    /// Walk from `last` through `prev` instead, reading the items of each page from last to first
+    ///
+    /// This has no effect once the walk started.
+    pub fn backward(mut self) -> Self {
+        self.walk.direction = Direction::Backward;
+        self
+    }
+
+    /// Set how many pages are resolved at most
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.walk.max_pages = max_pages;
+        self
+    }
+
+    /// The number of pages resolved so far
+    pub fn pages(&self) -> usize {
+        self.walk.pages
+    }
+
+    /// Why the walk stopped, or `None` if it has not stopped yet
+    pub fn stop(&self) -> Option<&Stop> {
+        self.walk.stop.as_ref()
+    }
+}
+
+impl<'a, R> Iterator for Items<'a, R>
+where
+    R: Resolver + 'a,
+{
+    type Item = Result<Value, ResolveError<R::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.walk.pop() {
+                return Some(item);
+            }
+
+            let link = self.walk.next_link()?;
+            match self.dereferencer.dereference(&link) {
+                Ok(page) => self.walk.accept(page),
+                Err(e) => return self.walk.fail(e),
+            }
+        }
+    }
+}
+
+/// An asynchronous walk over the items of a collection, across its pages
+///
+/// This works like `Items`, with `next` returning a future instead.
+pub struct ItemsAsync<'a, R>
+where
+    R: AsyncResolver + 'a,
+{
+    dereferencer: &'a Dereferencer<R>,
+    walk: Walk<R::Error>,
+    page: Option<Resolve<'a, R, Value>>,
+}
+
+impl<'a, R> ItemsAsync<'a, R>
+where
+    R: AsyncResolver + 'a,
+{
+    /// Walk from `last` through `prev` instead, reading the items of each page from last to first
+    ///
+    /// This has no effect once the walk started.
+    pub fn backward(mut self) -> Self {
+        self.walk.direction = Direction::Backward;
+        self
+    }
+
+    /// Set how many pages are resolved at most
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.walk.max_pages = max_pages;
+        self
+    }
+
+    /// The number of pages resolved so far
+    pub fn pages(&self) -> usize {
+        self.walk.pages
+    }
+
+    /// Why the walk stopped, or `None` if it has not stopped yet
+    pub fn stop(&self) -> Option<&Stop> {
+        self.walk.stop.as_ref()
+    }
+
+    /// Produce the next item, or `None` once the walk stopped
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> NextItem<'_, 'a, R> {
+        NextItem { items: self }
+    }
+}
+
+/// The future returned by `ItemsAsync::next`
+pub struct NextItem<'b, 'a, R>
+where
+    R: AsyncResolver + 'a,
+{
+    items: &'b mut ItemsAsync<'a, R>,
+}
+
+impl<'b, 'a, R> Future for NextItem<'b, 'a, R>
+where
+    R: AsyncResolver + 'a,
+{
+    type Output = Option<Result<Value, ResolveError<R::Error>>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let items = &mut *self.items;
+
+        loop {
+            if let Some(mut page) = items.page.take() {
+                match Pin::new(&mut page).poll(cx) {
+                    Poll::Pending => {
+                        items.page = Some(page);
+                        return Poll::Pending;
+                    }
+                    Poll::Ready(Ok(page)) => items.walk.accept(page),
+                    Poll::Ready(Err(e)) => return Poll::Ready(items.walk.fail(e)),
+                }
+            }
+
+            if let Some(item) = items.walk.pop() {
+                return Poll::Ready(Some(item));
+            }
+
+            match items.walk.next_link() {
+                Some(link) => items.page = Some(items.dereferencer.dereference_async(&link)),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// The Paging Extension Trait
+///
+/// This trait walks through the items of collections and collection pages, resolving their pages
+/// through a `Dereferencer`. Walks start with the items of the collection itself, and go on from
+/// `first` through `next`, or from `last` through `prev` when walking backward. A walk that
+/// starts from a page goes on from its `next` or `prev`.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{
+///     collection::OrderedCollection,
+///     primitives::XsdAnyUri,
+///     resolve::{Dereferencer, PagingExt, Stop},
+/// };
+/// use std::collections::HashMap;
+///
+/// # fn main() {
+/// let mut pages = HashMap::new();
+/// for n in 1..4 {
+///     pages.insert(
+///         format!("https://example.com/outbox?page={}", n).parse::<XsdAnyUri>().unwrap(),
+///         json!({
+///             "type": "OrderedCollectionPage",
+///             "id": format!("https://example.com/outbox?page={}", n),
+///             "items": [format!("https://example.com/activities/{}", n)],
+///             "prev": format!("https://example.com/outbox?page={}", n - 1),
+///             // The last page wrongly links back to the first one
+///             "next": format!("https://example.com/outbox?page={}", n % 3 + 1),
+///         }),
+///     );
+/// }
+/// let dereferencer = Dereferencer::new(pages);
+///
+/// let outbox: OrderedCollection = serde_json::from_value(json!({
+///     "type": "OrderedCollection",
+///     "id": "https://example.com/outbox",
+///     "items": [],
+///     "first": "https://example.com/outbox?page=1",
+///     "last": "https://example.com/outbox?page=3",
+/// })).unwrap();
+///
+/// let mut items = outbox.items(&dereferencer);
+/// let ids: Vec<String> = items
+///     .by_ref()
+///     .map(|item| item.unwrap().as_str().unwrap().to_owned())
+///     .collect();
+/// assert_eq!(ids, vec![
+///     "https://example.com/activities/1",
+///     "https://example.com/activities/2",
+///     "https://example.com/activities/3",
+/// ]);
+///
+/// let first_page = "https://example.com/outbox?page=1".parse().unwrap();
+/// assert_eq!(items.stop(), Some(&Stop::Cycle(first_page)));
+///
+/// // Walking backward, with a budget of two pages
+/// let mut items = outbox.items(&dereferencer).backward().max_pages(2);
+/// assert_eq!(items.by_ref().count(), 2);
+/// assert_eq!(items.stop(), Some(&Stop::Budget));
+/// # }
+/// ```
+pub trait PagingExt: Collection + Serialize {
+    /// Walk through the items of this collection and its pages
+    fn items<'a, R>(&self, dereferencer: &'a Dereferencer<R>) -> Items<'a, R>
+    where
+        R: Resolver + 'a,
+    {
+        Items {
+            dereferencer,
+            walk: Walk::new(self),
+        }
+    }
+
+    /// Walk through the items of this collection and its pages, asynchronously
+    fn items_async<'a, R>(&self, dereferencer: &'a Dereferencer<R>) -> ItemsAsync<'a, R>
+    where
+        R: AsyncResolver + 'a,
+    {
+        ItemsAsync {
+            dereferencer,
+            walk: Walk::new(self),
+            page: None,
+        }
+    }
+}
+
+impl<T> PagingExt for T where T: Collection + Serialize {}
+
+/// Check whether a value is a collection page, rather than a collection
+fn is_page(value: &Value) -> bool {
+    value.get("partOf").is_some()
+        || value
+            .get("type")
+            .map(values)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(Value::as_str)
+            .any(|kind| kind.ends_with("CollectionPage"))
+}
@@ -0,0 +1,537 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Signing and verification of requests with HTTP Signatures
+//!
+//! ActivityPub servers authenticate the activities they deliver with
+//! [HTTP Signatures](https://tools.ietf.org/html/draft-cavage-http-signatures-10). The sender signs
+//! the request's method, path and a set of its headers with the private key of the sending actor,
+//! and includes a `Digest` of the body among the signed headers. The receiver fetches the actor's
+//! `PublicKey`, named by the `keyId` of the signature, and checks the signature and digest.
+//!
+//! This module is only available with the `http-signatures` feature. It does not depend on any
+//! HTTP library, so requests are described with the `HttpRequest` type, and the headers it adds
+//! should be copied onto the request that is actually sent.
+//!
+//! ```rust
+//! # extern crate activitystreams_types;
+//! # extern crate failure;
+//! # use activitystreams_types::{
+//! #     activity::Follow,
+//! #     security::{
+//! #         http_signatures::{verify, HttpRequest, SignatureHeader, Signer},
+//! #         PublicKey,
+//! #     },
+//! # };
+//! # use failure::Error;
+//! #
+//! # const PRIVATE_KEY: &str = include_str!("../../tests/keys/rsa-private.pem");
+//! # const PUBLIC_KEY: &str = include_str!("../../tests/keys/rsa-public.pem");
+//! #
+//! # fn run() -> Result<(), Error> {
+//! let key_id = "https://example.com/users/alice#main-key";
+//! let signer = Signer::from_pem(key_id, PRIVATE_KEY)?;
+//!
+//! let mut follow = Follow::default();
+//! follow.object_props.set_id_string("https://example.com/follows/1".to_owned())?;
+//!
+//! let mut request = HttpRequest::new("POST", "/users/bob/inbox")
+//!     .header("Host", "example.org")
+//!     .header("Date", "Tue, 07 Jun 2022 20:51:35 GMT")
+//!     .activity(&follow)?;
+//! signer.sign(&mut request)?;
+//!
+//! assert!(request.get_header("Digest").unwrap().starts_with("SHA-256="));
+//!
+//! // The receiving server reads the key id, fetches the key from the actor, and verifies
+//! let signature = SignatureHeader::from_request(&request)?;
+//! assert_eq!(signature.key_id, key_id);
+//!
+//! let public_key = PublicKey::new(
+//!     key_id.parse()?,
+//!     "https://example.com/users/alice".parse()?,
+//!     PUBLIC_KEY,
+//! );
+//! verify(&request, &public_key)?;
+//!
+//! // Changing the body invalidates the digest
+//! request.body = b"{}".to_vec();
+//! assert!(verify(&request, &public_key).is_err());
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use rsa::{
+    pkcs1v15::{Signature, SigningKey, VerifyingKey},
+    sha2::{Digest, Sha256},
+    signature::{SignatureEncoding, Signer as _, Verifier as _},
+    RsaPrivateKey,
+};
+use serde::ser::Serialize;
+use serde_json;
+use std::{convert::TryFrom, error::Error, fmt};
+
+use security::{private_key_from_pem, PublicKey, PublicKeyPemError};
+
+/// The headers that are signed by default, which are those Mastodon requires for a POST
+const DEFAULT_HEADERS: &[&str] = &["(request-target)", "host", "date", "digest"];
+
+/// The headers `verify` requires to be signed, so a signature can not be replayed against a
+/// different path or host
+pub const REQUIRED_HEADERS: &[&str] = &["(request-target)", "host", "date"];
+
+/// The media type used for the body of a request built with `HttpRequest::activity`
+const ACTIVITY_MEDIA_TYPE: &str = "application/activity+json";
+
+/// A description of an HTTP request that can be signed or verified
+///
+/// Header names are matched without regard to case, and headers that appear more than once are
+/// signed as a single header whose values are separated by `, `.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HttpRequest {
+    /// The method of the request, such as `POST`
+    pub method: String,
+
+    /// The path of the request, including the query string if there is one
+    pub path: String,
+
+    /// The headers of the request, in the order they are sent
+    pub headers: Vec<(String, String)>,
+
+    /// The body of the request
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    pub fn new<M, P>(method: M, path: P) -> Self
+    where
+        M: Into<String>,
+        P: Into<String>,
+    {
+        HttpRequest {
+            method: method.into(),
+            path: path.into(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Add a header to the request
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the body of the request
+    pub fn body<B>(mut self, body: B) -> Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.body = body.into();
+        self
+    }
+
+    /// Set the body of the request to an activity, and set its `Content-Type`
+    pub fn activity<T>(mut self, activity: &T) -> Result<Self, SignatureError>
+    where
+        T: Serialize,
+    {
+        self.body = serde_json::to_vec(activity).map_err(SignatureError::Serialize)?;
+        self.set_header("Content-Type", ACTIVITY_MEDIA_TYPE);
+        Ok(self)
+    }
+
+    /// Fetch the value of a header, joining the values of a repeated header with `, `
+    pub fn get_header(&self, name: &str) -> Option<String> {
+        let values: Vec<&str> = self
+            .headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+            .collect();
+
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.join(", "))
+        }
+    }
+
+    /// Replace every value of a header with a single value
+    pub fn set_header<N, V>(&mut self, name: N, value: V)
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        let name = name.into();
+        self.headers
+            .retain(|(header, _)| !header.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+    }
+
+    /// Build the string that is signed for the given list of headers
+    fn signing_string(&self, headers: &[String]) -> Result<String, SignatureError> {
+        let lines = headers
+            .iter()
+            .map(|name| {
+                let value = if name == "(request-target)" {
+                    format!("{} {}", self.method.to_lowercase(), self.path)
+                } else {
+                    self.get_header(name)
+                        .ok_or_else(|| SignatureError::MissingHeader(name.to_owned()))?
+                };
+
+                Ok(format!("{}: {}", name, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Produce the value of a `Digest` header for the given body
+///
+/// ```rust
+/// # extern crate activitystreams_types;
+/// # use activitystreams_types::security::http_signatures::digest;
+/// # fn main() {
+/// assert_eq!(digest(b"{}"), "SHA-256=RBNvo1WzZ4oRRq0W9+hknpT7T8If536DEMBg9hyq/4o=");
+/// # }
+/// ```
+pub fn digest(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+/// Signs requests on behalf of an actor
+///
+/// By default the `(request-target)`, `Host`, `Date` and `Digest` headers are signed.
+#[derive(Clone, Debug)]
+pub struct Signer {
+    key_id: String,
+    key: SigningKey<Sha256>,
+    headers: Vec<String>,
+}
+
+impl Signer {
+    /// Create a signer from the id of the actor's `PublicKey` and its matching private key
+    pub fn new<T>(key_id: T, private_key: RsaPrivateKey) -> Self
+    where
+        T: Into<String>,
+    {
+        Signer {
+            key_id: key_id.into(),
+            key: SigningKey::new(private_key),
+            headers: DEFAULT_HEADERS.iter().map(|&h| h.to_owned()).collect(),
+        }
+    }
+
+    /// Create a signer from a PEM encoded private key
+    ///
+    /// Both the PKCS#8 (`BEGIN PRIVATE KEY`) and PKCS#1 (`BEGIN RSA PRIVATE KEY`) encodings are
+    /// accepted.
+    pub fn from_pem<T>(key_id: T, private_key_pem: &str) -> Result<Self, SignatureError>
+    where
+        T: Into<String>,
+    {
+        let private_key =
+            private_key_from_pem(private_key_pem).ok_or(SignatureError::PrivateKey)?;
+
+        Ok(Signer::new(key_id, private_key))
+    }
+
+    /// Set which headers are signed
+    ///
+    /// `(request-target)` can be used to sign the method and path of the request.
+    pub fn headers(mut self, headers: &[&str]) -> Self {
+        self.headers = headers.iter().map(|h| h.to_lowercase()).collect();
+        self
+    }
+
+    /// Sign a request, adding a `Signature` header
+    ///
+    /// If the `Digest` header is signed, it is set from the body of the request, and if the
+    /// `Date` header is signed but missing, it is set to the current time. Any other signed
+    /// header must already be present.
+    pub fn sign(&self, request: &mut HttpRequest) -> Result<(), SignatureError> {
+        if self.headers.iter().any(|h| h == "digest") {
+            let digest = digest(&request.body);
+            request.set_header("Digest", digest);
+        }
+
+        if self.headers.iter().any(|h| h == "date") && request.get_header("date").is_none() {
+            let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+            request.set_header("Date", date);
+        }
+
+        let signing_string = request.signing_string(&self.headers)?;
+        let signature = self.key.sign(signing_string.as_bytes());
+
+        let header = SignatureHeader {
+            key_id: self.key_id.clone(),
+            algorithm: Some("rsa-sha256".to_owned()),
+            headers: self.headers.clone(),
+            signature: signature.to_vec(),
+        };
+
+        request.set_header("Signature", header.to_string());
+        Ok(())
+    }
+}
+
+/// The parsed value of a `Signature` header
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignatureHeader {
+    /// The id of the key that produced the signature, usually the id of an actor's `PublicKey`
+    pub key_id: String,
+
+    /// The algorithm used to produce the signature, if it was given
+    pub algorithm: Option<String>,
+
+    /// The lowercased names of the signed headers, in the order they were signed
+    pub headers: Vec<String>,
+
+    /// The signature itself, decoded from base64
+    pub signature: Vec<u8>,
+}
+
+impl SignatureHeader {
+    /// Read the signature from a request's `Signature` header, or from its `Authorization`
+    /// header if it uses the `Signature` scheme
+    pub fn from_request(request: &HttpRequest) -> Result<Self, SignatureError> {
+        let header = request
+            .get_header("signature")
+            .or_else(|| {
+                request.get_header("authorization").and_then(|value| {
+                    value
+                        .strip_prefix("Signature ")
+                        .map(|params| params.to_owned())
+                })
+            })
+            .ok_or_else(|| SignatureError::MissingHeader("signature".to_owned()))?;
+
+        SignatureHeader::parse(&header)
+    }
+
+    /// Parse the value of a `Signature` header
+    pub fn parse(header: &str) -> Result<Self, SignatureError> {
+        let mut key_id = None;
+        let mut algorithm = None;
+        let mut headers = None;
+        let mut signature = None;
+
+        let mut rest = header.trim();
+        while !rest.is_empty() {
+            let eq = rest.find('=').ok_or(SignatureError::InvalidHeader)?;
+            let name = rest[..eq].trim();
+
+            let value_start = rest[eq + 1..]
+                .strip_prefix('"')
+                .ok_or(SignatureError::InvalidHeader)?;
+            let end = value_start.find('"').ok_or(SignatureError::InvalidHeader)?;
+            let value = &value_start[..end];
+
+            match name {
+                "keyId" => key_id = Some(value.to_owned()),
+                "algorithm" => algorithm = Some(value.to_owned()),
+                "headers" => {
+                    headers = Some(value.split_whitespace().map(str::to_lowercase).collect())
+                }
+                "signature" => {
+                    signature = Some(
+                        BASE64
+                            .decode(value)
+                            .map_err(|_| SignatureError::InvalidHeader)?,
+                    )
+                }
+                // Parameters such as `created` and `expires` are not used
+                _ => (),
+            }
+
+            rest = value_start[end + 1..].trim_start();
+            if let Some(next) = rest.strip_prefix(',') {
+                rest = next.trim_start();
+            } else if !rest.is_empty() {
+                return Err(SignatureError::InvalidHeader);
+            }
+        }
+
+        Ok(SignatureHeader {
+            key_id: key_id.ok_or(SignatureError::InvalidHeader)?,
+            algorithm,
+            // When no headers are listed, only the Date header is signed
+            headers: headers.unwrap_or_else(|| vec!["date".to_owned()]),
+            signature: signature.ok_or(SignatureError::InvalidHeader)?,
+        })
+    }
+}
+
+impl fmt::Display for SignatureHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "keyId=\"{}\"", self.key_id)?;
+        if let Some(ref algorithm) = self.algorithm {
+            write!(f, ",algorithm=\"{}\"", algorithm)?;
+        }
+        write!(
+            f,
+            ",headers=\"{}\",signature=\"{}\"",
+            self.headers.join(" "),
+            BASE64.encode(&self.signature)
+        )
+    }
+}
+
+/// Verify the signature of a request against the public key of the actor that sent it
+///
+/// The `keyId` of the signature must match the id of the key, and the `(request-target)`, `Host`
+/// and `Date` headers must be signed. If the request has a body, the `Digest` header must be
+/// signed and must match the body. Only RSA keys with SHA-256 are supported, which covers the
+/// `rsa-sha256` and `hs2019` algorithms as used by ActivityPub servers.
+///
+/// The value of the `Date` header is not checked, so callers should reject requests whose date is
+/// too far from the current time to prevent signed requests from being replayed.
+pub fn verify(request: &HttpRequest, key: &PublicKey) -> Result<(), SignatureError> {
+    verify_headers(request, key, REQUIRED_HEADERS)
+}
+
+/// Verify the signature of a request, requiring the given headers to be signed
+///
+/// This is the same as `verify`, for servers that require a different set of signed headers.
+/// `Digest` is always required when the request has a body.
+pub fn verify_headers(
+    request: &HttpRequest,
+    key: &PublicKey,
+    required: &[&str],
+) -> Result<(), SignatureError> {
+    let header = SignatureHeader::from_request(request)?;
+
+    if header.key_id != key.id.as_str() {
+        return Err(SignatureError::KeyMismatch);
+    }
+
+    match header.algorithm.as_deref() {
+        None | Some("rsa-sha256") | Some("hs2019") => (),
+        Some(algorithm) => return Err(SignatureError::UnsupportedAlgorithm(algorithm.to_owned())),
+    }
+
+    let is_signed = |name: &str| header.headers.iter().any(|h| h.eq_ignore_ascii_case(name));
+
+    if let Some(name) = required.iter().find(|name| !is_signed(name)) {
+        return Err(SignatureError::NotSigned(name.to_lowercase()));
+    }
+
+    if !request.body.is_empty() || request.get_header("digest").is_some() {
+        if !is_signed("digest") {
+            return Err(SignatureError::NotSigned("digest".to_owned()));
+        }
+
+        let expected = digest(&request.body);
+        let matches = request
+            .get_header("digest")
+            .map(|value| value.split(',').any(|d| d.trim() == expected))
+            .unwrap_or(false);
+
+        if !matches {
+            return Err(SignatureError::DigestMismatch);
+        }
+    }
+
+    let signing_string = request.signing_string(&header.headers)?;
+    let signature = Signature::try_from(header.signature.as_slice())
+        .map_err(|_| SignatureError::BadSignature)?;
+
+    VerifyingKey::<Sha256>::new(key.to_rsa_public_key().map_err(SignatureError::PublicKey)?)
+        .verify(signing_string.as_bytes(), &signature)
+        .map_err(|_| SignatureError::BadSignature)
+}
+
+/// The errors that can occur while signing or verifying a request
+#[derive(Debug)]
+pub enum SignatureError {
+    /// A header that must be signed is missing from the request
+    MissingHeader(String),
+
+    /// A header that must be signed is not among the signed headers
+    NotSigned(String),
+
+    /// The `Signature` header could not be parsed
+    InvalidHeader,
+
+    /// The signature was made with an algorithm that is not supported
+    UnsupportedAlgorithm(String),
+
+    /// The signature was made with a different key than the one it was checked against
+    KeyMismatch,
+
+    /// The `Digest` header does not match the body of the request
+    DigestMismatch,
+
+    /// The signature does not match the request
+    BadSignature,
+
+    /// The public key could not be parsed
+    PublicKey(PublicKeyPemError),
+
+    /// The private key could not be parsed
+    PrivateKey,
+
+    /// The activity could not be serialized into the body of the request
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SignatureError::MissingHeader(ref name) => {
+                write!(f, "Header {:?} is missing from the request", name)
+            }
+            SignatureError::NotSigned(ref name) => write!(f, "Header {:?} is not signed", name),
+            SignatureError::InvalidHeader => f.write_str("Invalid Signature header"),
+            SignatureError::UnsupportedAlgorithm(ref algorithm) => {
+                write!(f, "Unsupported signature algorithm {:?}", algorithm)
+            }
+            SignatureError::KeyMismatch => f.write_str("Signature was made with a different key"),
+            SignatureError::DigestMismatch => f.write_str("Digest does not match the body"),
+            SignatureError::BadSignature => f.write_str("Signature does not match the request"),
+            SignatureError::PublicKey(ref e) => fmt::Display::fmt(e, f),
+            SignatureError::PrivateKey => f.write_str("Invalid RSA private key"),
+            SignatureError::Serialize(ref e) => write!(f, "Failed to serialize activity, {}", e),
+        }
+    }
+}
+
+impl Error for SignatureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SignatureError::PublicKey(ref e) => Some(e),
+            SignatureError::Serialize(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,599 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Object integrity proofs, as described by
+//! [FEP-8b32](https://codeberg.org/fediverse/fep/src/branch/main/fep/8b32/fep-8b32.md)
+//!
+//! An integrity proof is a signature attached to an object as its `proof` property. Like a Linked
+//! Data Signature it stays valid when the object is forwarded, so a relay or any other server can
+//! check that the object was published by the owner of the key, without relying on the HTTP
+//! Signature of the request that delivered it.
+//!
+//! Proofs are made with the `eddsa-jcs-2022` cryptosuite from
+//! [Data Integrity EdDSA Cryptosuites](https://www.w3.org/TR/vc-di-eddsa/): the object and the
+//! proof options are canonicalized with the JSON Canonicalization Scheme (JCS), hashed with
+//! SHA-256, and signed with an Ed25519 key. The key is published in the `assertionMethod` of the
+//! actor as a `Multikey`.
+//!
+//! This module is only available with the `object-integrity` feature. Objects that should carry
+//! proofs should include `DATA_INTEGRITY_CONTEXT` in their `@context`.
+//!
+//! ```rust
+//! # extern crate activitystreams_types;
+//! # extern crate failure;
+//! # #[macro_use]
+//! # extern crate serde_json;
+//! # use activitystreams_types::{
+//! #     object::Note,
+//! #     security::{
+//! #         integrity::{verify, ProofExt, Signer},
+//! #         Multikey,
+//! #     },
+//! # };
+//! # use failure::Error;
+//! #
+//! # fn run() -> Result<(), Error> {
+//! let key_id = "https://example.com/users/alice#ed25519-key";
+//! let signer = Signer::from_multibase(key_id, "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq")?;
+//!
+//! // The key as it is published in the actor's assertionMethod
+//! let key = Multikey::from_ed25519(
+//!     key_id.parse()?,
+//!     "https://example.com/users/alice".parse()?,
+//!     &signer.verifying_key(),
+//! );
+//! assert_eq!(key.public_key_multibase, "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2");
+//!
+//! let mut note = Note::default();
+//! note.object_props.set_id_string("https://example.com/notes/1".to_owned())?;
+//! note.object_props.set_content_string("Hello".to_owned())?;
+//! note.sign_proof_at(&signer, "2023-02-24T23:36:38Z")?;
+//!
+//! let proof = note.object_props.proof_proof()?;
+//! assert_eq!(proof.cryptosuite.as_ref().map(String::as_str), Some("eddsa-jcs-2022"));
+//! note.verify_proof(&key)?;
+//!
+//! // Servers receiving the note check the JSON exactly as it was sent
+//! let mut received = serde_json::to_value(&note)?;
+//! verify(&received, &key)?;
+//!
+//! received["content"] = json!("Goodbye");
+//! assert!(verify(&received, &key).is_err());
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+
+use chrono::{SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+use serde::ser::Serialize;
+use serde_json::{self, Map, Value};
+use sha2::{Digest, Sha256};
+use std::{error::Error, fmt};
+
+use activitystreams_traits::properties::OneOrMany;
+use object::ObjectExt;
+use security::{Multikey, MultikeyError, Proof};
+
+/// The type of proofs described by Data Integrity
+pub const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+
+/// The cryptosuite of proofs made over JCS canonicalized documents with an Ed25519 key
+pub const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+
+/// The IRI of the Data Integrity context, which defines the `proof` property
+pub const DATA_INTEGRITY_CONTEXT: &str = "https://w3id.org/security/data-integrity/v1";
+
+/// The proof purpose used for objects an actor publishes
+pub const ASSERTION_METHOD: &str = "assertionMethod";
+
+/// The prefix of an Ed25519 private key encoded as a multikey, the multicodec `ed25519-priv`
+const ED25519_PRIV_PREFIX: [u8; 2] = [0x80, 0x26];
+
+/// Signs objects on behalf of an actor
+#[derive(Clone, Debug)]
+pub struct Signer {
+    verification_method: String,
+    key: SigningKey,
+}
+
+impl Signer {
+    /// Create a signer from the id of the actor's `Multikey` and its matching private key
+    pub fn new<T>(verification_method: T, key: SigningKey) -> Self
+    where
+        T: Into<String>,
+    {
+        Signer {
+            verification_method: verification_method.into(),
+            key,
+        }
+    }
+
+    /// Create a signer from a private key encoded as a base58btc multibase string, the
+    /// `secretKeyMultibase` form of a `Multikey`
+    pub fn from_multibase<T>(
+        verification_method: T,
+        secret_key_multibase: &str,
+    ) -> Result<Self, IntegrityError>
+    where
+        T: Into<String>,
+    {
+        let encoded = secret_key_multibase
+            .strip_prefix('z')
+            .ok_or(IntegrityError::PrivateKey)?;
+        let bytes = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|_| IntegrityError::PrivateKey)?;
+
+        let mut key = [0; 32];
+        if !bytes.starts_with(&ED25519_PRIV_PREFIX)
+            || bytes.len() != ED25519_PRIV_PREFIX.len() + key.len()
+        {
+            return Err(IntegrityError::PrivateKey);
+        }
+        key.copy_from_slice(&bytes[ED25519_PRIV_PREFIX.len()..]);
+
+        Ok(Signer::new(
+            verification_method,
+            SigningKey::from_bytes(&key),
+        ))
+    }
+
+    /// The public half of the signing key, to be published as a `Multikey`
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.key.verifying_key()
+    }
+
+    /// Make a proof for a document
+    ///
+    /// The document must not contain the proof being made. Any existing `proof` property is
+    /// removed before signing.
+    pub fn proof<T>(&self, document: &T) -> Result<Proof, IntegrityError>
+    where
+        T: Serialize,
+    {
+        let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.proof_at(document, &created)
+    }
+
+    /// Make a proof for a document with the given `created` time
+    pub fn proof_at<T>(&self, document: &T, created: &str) -> Result<Proof, IntegrityError>
+    where
+        T: Serialize,
+    {
+        let mut document = serde_json::to_value(document).map_err(IntegrityError::Serialize)?;
+        document
+            .as_object_mut()
+            .ok_or(IntegrityError::InvalidDocument)?
+            .remove("proof");
+
+        let mut proof = Proof {
+            context: document.get("@context").cloned(),
+            kind: DATA_INTEGRITY_PROOF.to_owned(),
+            cryptosuite: Some(EDDSA_JCS_2022.to_owned()),
+            verification_method: self.verification_method.clone(),
+            proof_purpose: ASSERTION_METHOD.to_owned(),
+            created: Some(created.to_owned()),
+            proof_value: String::new(),
+        };
+
+        let options = match serde_json::to_value(&proof).map_err(IntegrityError::Serialize)? {
+            Value::Object(mut options) => {
+                options.remove("proofValue");
+                options
+            }
+            _ => unreachable!("proofs are serialized as objects"),
+        };
+
+        let signature = self.key.sign(&hash_data(&document, options));
+        proof.proof_value = format!("z{}", bs58::encode(signature.to_bytes()).into_string());
+
+        Ok(proof)
+    }
+
+    /// Sign a document, returning it as JSON with a `proof` property added
+    pub fn sign<T>(&self, document: &T) -> Result<Value, IntegrityError>
+    where
+        T: Serialize,
+    {
+        let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.sign_at(document, &created)
+    }
+
+    /// Sign a document with the given `created` time
+    pub fn sign_at<T>(&self, document: &T, created: &str) -> Result<Value, IntegrityError>
+    where
+        T: Serialize,
+    {
+        let proof = self.proof_at(document, created)?;
+
+        let mut document = serde_json::to_value(document).map_err(IntegrityError::Serialize)?;
+        let proof = serde_json::to_value(proof).map_err(IntegrityError::Serialize)?;
+        document
+            .as_object_mut()
+            .ok_or(IntegrityError::InvalidDocument)?
+            .insert("proof".to_owned(), proof);
+
+        Ok(document)
+    }
+}
+
+/// Verify the `eddsa-jcs-2022` proof of a document made with the given key
+///
+/// If the document has several proofs, the one whose `verificationMethod` is the id of `key` is
+/// checked. The proof must have been made for `assertionMethod`, use `verify_purpose` to check
+/// proofs made for another purpose.
+pub fn verify(document: &Value, key: &Multikey) -> Result<(), IntegrityError> {
+    verify_purpose(document, key, ASSERTION_METHOD)
+}
+
+/// Verify the `eddsa-jcs-2022` proof of a document made with the given key for the given
+/// `proofPurpose`
+///
+/// ```rust
+/// # extern crate activitystreams_types;
+/// # extern crate failure;
+/// # use activitystreams_types::{
+/// #     object::Note,
+/// #     security::{
+/// #         integrity::{verify, verify_purpose, IntegrityError, Signer, ASSERTION_METHOD},
+/// #         Multikey,
+/// #     },
+/// # };
+/// # use failure::Error;
+/// #
+/// # fn run() -> Result<(), Error> {
+/// # let key_id = "https://example.com/users/alice#ed25519-key";
+/// # let signer = Signer::from_multibase(key_id, "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq")?;
+/// # let key = Multikey::from_ed25519(
+/// #     key_id.parse()?,
+/// #     "https://example.com/users/alice".parse()?,
+/// #     &signer.verifying_key(),
+/// # );
+/// let mut note = Note::default();
+/// note.object_props.set_content_string("Hello".to_owned())?;
+///
+/// let signed = signer.sign_at(&note, "2023-02-24T23:36:38Z")?;
+/// verify_purpose(&signed, &key, ASSERTION_METHOD)?;
+///
+/// match verify_purpose(&signed, &key, "authentication") {
+///     Err(IntegrityError::PurposeMismatch(ref purpose)) => assert_eq!(purpose, ASSERTION_METHOD),
+///     _ => panic!("the proof was not made for authentication"),
+/// }
+/// #
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     run().unwrap();
+/// # }
+/// ```
+pub fn verify_purpose(
+    document: &Value,
+    key: &Multikey,
+    purpose: &str,
+) -> Result<(), IntegrityError> {
+    let mut unsecured = match *document {
+        Value::Object(ref object) => object.clone(),
+        _ => return Err(IntegrityError::InvalidDocument),
+    };
+
+    let proofs = match unsecured.remove("proof") {
+        Some(Value::Array(proofs)) => proofs,
+        Some(proof) => vec![proof],
+        None => return Err(IntegrityError::MissingProof),
+    };
+
+    let mut options = proofs
+        .into_iter()
+        .filter_map(|proof| match proof {
+            Value::Object(proof) => Some(proof),
+            _ => None,
+        })
+        .find(|proof| {
+            proof.get("verificationMethod").and_then(Value::as_str) == Some(key.id.as_str())
+        })
+        .ok_or(IntegrityError::KeyMismatch)?;
+
+    let proof: Proof = serde_json::from_value(Value::Object(options.clone()))
+        .map_err(|_| IntegrityError::InvalidProof)?;
+
+    if proof.kind != DATA_INTEGRITY_PROOF || proof.cryptosuite.as_deref() != Some(EDDSA_JCS_2022) {
+        return Err(IntegrityError::UnsupportedCryptosuite(
+            proof.cryptosuite.unwrap_or(proof.kind),
+        ));
+    }
+
+    if proof.proof_purpose != purpose {
+        return Err(IntegrityError::PurposeMismatch(proof.proof_purpose));
+    }
+
+    options.remove("proofValue");
+
+    // A proof with its own context must only extend the context of the document
+    if let Some(context) = options.get("@context") {
+        let proof_context = as_items(context);
+        let document_context = unsecured.get("@context").map(as_items).unwrap_or_default();
+
+        if !document_context.starts_with(&proof_context) {
+            return Err(IntegrityError::ContextMismatch);
+        }
+        unsecured.insert("@context".to_owned(), context.clone());
+    }
+
+    let signature = proof
+        .proof_value
+        .strip_prefix('z')
+        .and_then(|encoded| bs58::decode(encoded).into_vec().ok())
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or(IntegrityError::InvalidProof)?;
+
+    key.to_ed25519_public_key()
+        .map_err(IntegrityError::PublicKey)?
+        .verify(&hash_data(&Value::Object(unsecured), options), &signature)
+        .map_err(|_| IntegrityError::BadProof)
+}
+
+/// Sign and verify the integrity proofs of this crate's object types
+///
+/// Unlike the `Signer` methods, signing with this trait stores the proof in the object's `proof`
+/// property, so the object can still be used as its own type.
+pub trait ProofExt: ObjectExt + Serialize {
+    /// Sign the object, replacing any proofs it already has
+    fn sign_proof(&mut self, signer: &Signer) -> Result<(), IntegrityError> {
+        let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.sign_proof_at(signer, &created)
+    }
+
+    /// Sign the object with the given `created` time
+    fn sign_proof_at(&mut self, signer: &Signer, created: &str) -> Result<(), IntegrityError> {
+        ObjectExt::props_mut(self).proof = None;
+        let proof = signer.proof_at(self, created)?;
+        let proof = serde_json::to_value(proof).map_err(IntegrityError::Serialize)?;
+        ObjectExt::props_mut(self).proof = Some(OneOrMany::One(proof));
+        Ok(())
+    }
+
+    /// Verify the proof made with the given key
+    ///
+    /// Properties this crate does not know about are lost when an object is deserialized, so
+    /// objects received from other servers should be verified with `verify` before they are
+    /// deserialized.
+    fn verify_proof(&self, key: &Multikey) -> Result<(), IntegrityError> {
+        verify(
+            &serde_json::to_value(self).map_err(IntegrityError::Serialize)?,
+            key,
+        )
+    }
+}
+
+impl<T> ProofExt for T where T: ObjectExt + Serialize {}
+
+/// Canonicalize a JSON value with the JSON Canonicalization Scheme,
+/// [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785)
+///
+/// Object members are sorted by the UTF-16 code units of their names, numbers are written as
+/// ECMAScript would write them, and no whitespace is added.
+///
+/// ```rust
+/// # extern crate activitystreams_types;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # use activitystreams_types::security::integrity::jcs;
+/// # fn main() {
+/// let value = json!({ "b": [1.5, 1e21, 100], "a": "caf\u{e9}\n", "\u{ff61}": null, "\u{1f600}": true });
+///
+/// // U+1F600 is written as the surrogate pair D83D DE00 in UTF-16, so it sorts before U+FF61
+/// assert_eq!(
+///     jcs(&value),
+///     "{\"a\":\"caf\u{e9}\\n\",\"b\":[1.5,1e+21,100],\"\u{1f600}\":true,\"\u{ff61}\":null}"
+/// );
+/// # }
+/// ```
+pub fn jcs(value: &Value) -> String {
+    let mut output = String::new();
+    write_jcs(value, &mut output);
+    output
+}
+
+fn write_jcs(value: &Value, output: &mut String) {
+    match *value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(b) => output.push_str(if b { "true" } else { "false" }),
+        Value::Number(ref number) => {
+            output.push_str(&number.as_f64().map(es6_number).unwrap_or_default())
+        }
+        Value::String(ref s) => write_jcs_string(s, output),
+        Value::Array(ref items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_jcs(item, output);
+            }
+            output.push(']');
+        }
+        Value::Object(ref object) => {
+            let mut members: Vec<(&String, &Value)> = object.iter().collect();
+            members.sort_by_cached_key(|&(key, _)| key.encode_utf16().collect::<Vec<u16>>());
+
+            output.push('{');
+            for (index, (key, value)) in members.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_jcs_string(key, output);
+                output.push(':');
+                write_jcs(value, output);
+            }
+            output.push('}');
+        }
+    }
+}
+
+fn write_jcs_string(s: &str, output: &mut String) {
+    output.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{0}'..='\u{1f}' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+/// Write a number the way ECMAScript's `Number.prototype.toString` does
+fn es6_number(n: f64) -> String {
+    if n == 0.0 {
+        return "0".to_owned();
+    }
+    if n < 0.0 {
+        return format!("-{}", es6_number(-n));
+    }
+
+    // Find how many digits are needed to round trip, then round the exact value to that many
+    // digits, which breaks ties towards an even digit as ECMAScript does
+    let shortest = format!("{:e}", n);
+    let precision = shortest.find('e').unwrap_or(0).saturating_sub(2);
+    let formatted = format!("{:.*e}", precision, n);
+
+    let (mantissa, exponent) = formatted.split_at(formatted.find('e').unwrap_or(0));
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let exponent: i32 = exponent[1..].parse().unwrap_or(0);
+
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{}e{}{}", first, sign, (n - 1).abs())
+        } else {
+            format!("{}.{}e{}{}", first, rest, sign, (n - 1).abs())
+        }
+    }
+}
+
+/// Hash the proof configuration and the document, which is the data that is signed
+fn hash_data(document: &Value, mut options: Map<String, Value>) -> Vec<u8> {
+    if let Some(context) = document.get("@context") {
+        options.insert("@context".to_owned(), context.clone());
+    }
+
+    let mut data = Sha256::digest(jcs(&Value::Object(options)).as_bytes()).to_vec();
+    data.extend_from_slice(&Sha256::digest(jcs(document).as_bytes()));
+    data
+}
+
+fn as_items(value: &Value) -> Vec<Value> {
+    match *value {
+        Value::Array(ref items) => items.clone(),
+        ref item => vec![item.clone()],
+    }
+}
+
+/// The errors that can occur while making or verifying a proof
+#[derive(Debug)]
+pub enum IntegrityError {
+    /// The document has no `proof` property
+    MissingProof,
+
+    /// The document has no proof made with the key it was checked against
+    KeyMismatch,
+
+    /// The proof is not a valid Data Integrity proof
+    InvalidProof,
+
+    /// The proof was made with a cryptosuite that is not supported
+    UnsupportedCryptosuite(String),
+
+    /// The proof was made for another `proofPurpose`
+    PurposeMismatch(String),
+
+    /// The `@context` of the proof is not a prefix of the `@context` of the document
+    ContextMismatch,
+
+    /// The proof does not match the document
+    BadProof,
+
+    /// The document to sign or verify is not a JSON object
+    InvalidDocument,
+
+    /// The public key could not be parsed
+    PublicKey(MultikeyError),
+
+    /// The private key could not be parsed
+    PrivateKey,
+
+    /// The document could not be serialized
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IntegrityError::MissingProof => f.write_str("Document has no proof"),
+            IntegrityError::KeyMismatch => f.write_str("Document has no proof made with this key"),
+            IntegrityError::InvalidProof => f.write_str("Invalid proof property"),
+            IntegrityError::UnsupportedCryptosuite(ref suite) => {
+                write!(f, "Unsupported cryptosuite {:?}", suite)
+            }
+            IntegrityError::PurposeMismatch(ref purpose) => {
+                write!(f, "Proof was made for {:?}", purpose)
+            }
+            IntegrityError::ContextMismatch => {
+                f.write_str("Proof @context does not match the document")
+            }
+            IntegrityError::BadProof => f.write_str("Proof does not match the document"),
+            IntegrityError::InvalidDocument => f.write_str("Only JSON objects can be signed"),
+            IntegrityError::PublicKey(ref e) => fmt::Display::fmt(e, f),
+            IntegrityError::PrivateKey => f.write_str("Invalid Ed25519 private key"),
+            IntegrityError::Serialize(ref e) => write!(f, "Failed to serialize document, {}", e),
+        }
+    }
+}
+
+impl Error for IntegrityError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            IntegrityError::PublicKey(ref e) => Some(e),
+            IntegrityError::Serialize(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,383 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Signing and verification of documents with Linked Data Signatures
+//!
+//! Mastodon signs the public activities it sends with an `RsaSignature2017` Linked Data
+//! Signature, stored in the `signature` property of the activity. Unlike an HTTP Signature, it
+//! stays valid when the activity is forwarded by another server, such as a relay.
+//!
+//! The signature covers the URDNA2015 canonical form of the document and of the signature
+//! options, so it does not depend on how the JSON is written, only on the RDF statements it
+//! makes. Terms that are not defined by the document's `@context` are not part of the RDF, so they
+//! are not covered by the signature either.
+//!
+//! This module is only available with the `ld-signatures` feature. Documents are handled as
+//! `serde_json::Value`s, so that properties this crate does not know about are signed as well.
+//!
+//! ```rust
+//! # extern crate activitystreams_types;
+//! # extern crate failure;
+//! # #[macro_use]
+//! # extern crate serde_json;
+//! # use activitystreams_types::security::{
+//! #     ld_signatures::{verify, LinkedDataSignature, Signer},
+//! #     PublicKey,
+//! # };
+//! # use failure::Error;
+//! #
+//! # const PRIVATE_KEY: &str = include_str!("../../tests/keys/rsa-private.pem");
+//! # const PUBLIC_KEY: &str = include_str!("../../tests/keys/rsa-public.pem");
+//! #
+//! # fn run() -> Result<(), Error> {
+//! let key_id = "https://example.com/users/alice#main-key";
+//! let signer = Signer::from_pem(key_id, PRIVATE_KEY)?;
+//!
+//! let activity = json!({
+//!     "@context": [
+//!         "https://www.w3.org/ns/activitystreams",
+//!         "https://w3id.org/security/v1"
+//!     ],
+//!     "id": "https://example.com/users/alice/statuses/1/activity",
+//!     "type": "Create",
+//!     "actor": "https://example.com/users/alice",
+//!     "to": ["https://www.w3.org/ns/activitystreams#Public"],
+//!     "object": {
+//!         "id": "https://example.com/users/alice/statuses/1",
+//!         "type": "Note",
+//!         "content": "Hello, relays"
+//!     }
+//! });
+//!
+//! let signed = signer.sign_at(&activity, "2018-06-01T12:00:00Z")?;
+//! let signature = LinkedDataSignature::from_document(&signed)?;
+//! assert_eq!(signature.kind, "RsaSignature2017");
+//! assert_eq!(signature.creator, key_id);
+//!
+//! let public_key = PublicKey::new(
+//!     key_id.parse()?,
+//!     "https://example.com/users/alice".parse()?,
+//!     PUBLIC_KEY,
+//! );
+//! verify(&signed, &public_key)?;
+//!
+//! // Changing what the activity says invalidates the signature
+//! let mut tampered = signed.clone();
+//! tampered["object"]["content"] = json!("Goodbye, relays");
+//! assert!(verify(&tampered, &public_key).is_err());
+//!
+//! // Changing only how it is written does not
+//! let mut rewritten = signed.clone();
+//! rewritten["to"] = json!("as:Public");
+//! verify(&rewritten, &public_key)?;
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{SecondsFormat, Utc};
+use rsa::{
+    pkcs1v15::{Signature, SigningKey, VerifyingKey},
+    sha2::{Digest, Sha256},
+    signature::{SignatureEncoding, Signer as _, Verifier as _},
+    RsaPrivateKey,
+};
+use serde::ser::Serialize;
+use serde_json::{self, Value};
+use std::{convert::TryFrom, error::Error, fmt};
+
+use jsonld::{JsonLd, JsonLdError, IDENTITY_CONTEXT};
+use security::{private_key_from_pem, PublicKey, PublicKeyPemError};
+
+/// The type of signatures made with an RSA key over URDNA2015 canonicalized documents
+pub const RSA_SIGNATURE_2017: &str = "RsaSignature2017";
+
+/// The `signature` property of a signed document
+///
+/// `creator` and `created` are kept as they were written, rather than parsed, since the
+/// signature covers their exact values.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedDataSignature {
+    /// The type of signature, such as `RsaSignature2017`
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// The id of the `PublicKey` that can verify the signature
+    pub creator: String,
+
+    /// When the signature was made, as an `xsd:dateTime`
+    pub created: String,
+
+    /// A value chosen by the signer to make the signature unique
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+
+    /// The base64 encoded signature
+    pub signature_value: String,
+}
+
+impl LinkedDataSignature {
+    /// Read the `signature` property of a document
+    pub fn from_document(document: &Value) -> Result<Self, LdSignatureError> {
+        let signature = document
+            .get("signature")
+            .ok_or(LdSignatureError::MissingSignature)?;
+
+        serde_json::from_value(signature.clone()).map_err(|_| LdSignatureError::InvalidSignature)
+    }
+}
+
+/// Signs documents on behalf of an actor
+#[derive(Clone, Debug)]
+pub struct Signer {
+    creator: String,
+    key: SigningKey<Sha256>,
+    processor: JsonLd,
+}
+
+impl Signer {
+    /// Create a signer from the id of the actor's `PublicKey` and its matching private key
+    pub fn new<T>(creator: T, private_key: RsaPrivateKey) -> Self
+    where
+        T: Into<String>,
+    {
+        Signer {
+            creator: creator.into(),
+            key: SigningKey::new(private_key),
+            processor: JsonLd::new(),
+        }
+    }
+
+    /// Create a signer from a PEM encoded private key
+    ///
+    /// Both the PKCS#8 (`BEGIN PRIVATE KEY`) and PKCS#1 (`BEGIN RSA PRIVATE KEY`) encodings are
+    /// accepted.
+    pub fn from_pem<T>(creator: T, private_key_pem: &str) -> Result<Self, LdSignatureError>
+    where
+        T: Into<String>,
+    {
+        let private_key =
+            private_key_from_pem(private_key_pem).ok_or(LdSignatureError::PrivateKey)?;
+
+        Ok(Signer::new(creator, private_key))
+    }
+
+    /// Use a processor that knows additional remote contexts
+    pub fn processor(mut self, processor: JsonLd) -> Self {
+        self.processor = processor;
+        self
+    }
+
+    /// Sign a document, returning it as JSON with a `signature` property added
+    ///
+    /// Any existing signature is replaced.
+    pub fn sign<T>(&self, document: &T) -> Result<Value, LdSignatureError>
+    where
+        T: Serialize,
+    {
+        let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        self.sign_at(document, &created)
+    }
+
+    /// Sign a document with the given `created` time
+    pub fn sign_at<T>(&self, document: &T, created: &str) -> Result<Value, LdSignatureError>
+    where
+        T: Serialize,
+    {
+        let mut document = serde_json::to_value(document).map_err(LdSignatureError::Serialize)?;
+        let object = document
+            .as_object_mut()
+            .ok_or(LdSignatureError::InvalidDocument)?;
+        object.remove("signature");
+
+        let mut options = serde_json::Map::new();
+        options.insert("creator".to_owned(), Value::String(self.creator.clone()));
+        options.insert("created".to_owned(), Value::String(created.to_owned()));
+
+        let to_be_signed = to_be_signed(&self.processor, &Value::Object(object.clone()), options)?;
+        let signature = self.key.sign(to_be_signed.as_bytes());
+
+        let signature = LinkedDataSignature {
+            kind: RSA_SIGNATURE_2017.to_owned(),
+            creator: self.creator.clone(),
+            created: created.to_owned(),
+            nonce: None,
+            signature_value: BASE64.encode(signature.to_vec()),
+        };
+        let signature = serde_json::to_value(signature).map_err(LdSignatureError::Serialize)?;
+        object.insert("signature".to_owned(), signature);
+
+        Ok(document)
+    }
+}
+
+/// Verify the `RsaSignature2017` signature of a document with the key of its creator
+///
+/// The `creator` of the signature must be the id of `key`. Documents that refer to remote
+/// contexts other than the bundled ones can not be verified with this function, use
+/// `verify_with` and a processor that knows those contexts.
+pub fn verify(document: &Value, key: &PublicKey) -> Result<(), LdSignatureError> {
+    verify_with(&JsonLd::new(), document, key)
+}
+
+/// Verify the signature of a document, using the given processor to canonicalize it
+pub fn verify_with(
+    processor: &JsonLd,
+    document: &Value,
+    key: &PublicKey,
+) -> Result<(), LdSignatureError> {
+    let signature = LinkedDataSignature::from_document(document)?;
+
+    if signature.kind != RSA_SIGNATURE_2017 {
+        return Err(LdSignatureError::UnsupportedType(signature.kind));
+    }
+
+    if signature.creator != key.id.as_str() {
+        return Err(LdSignatureError::KeyMismatch);
+    }
+
+    let mut options = match document["signature"] {
+        Value::Object(ref options) => options.clone(),
+        _ => return Err(LdSignatureError::InvalidSignature),
+    };
+    for key in &["type", "id", "signatureValue"] {
+        options.remove(*key);
+    }
+
+    let mut unsigned = document.clone();
+    if let Some(object) = unsigned.as_object_mut() {
+        object.remove("signature");
+    }
+
+    let to_be_verified = to_be_signed(processor, &unsigned, options)?;
+
+    let signature = BASE64
+        .decode(signature.signature_value.trim())
+        .map_err(|_| LdSignatureError::InvalidSignature)?;
+    let signature =
+        Signature::try_from(signature.as_slice()).map_err(|_| LdSignatureError::BadSignature)?;
+
+    VerifyingKey::<Sha256>::new(
+        key.to_rsa_public_key()
+            .map_err(LdSignatureError::PublicKey)?,
+    )
+    .verify(to_be_verified.as_bytes(), &signature)
+    .map_err(|_| LdSignatureError::BadSignature)
+}
+
+/// Build the string that is signed, the hash of the canonical options followed by the hash of
+/// the canonical document
+fn to_be_signed(
+    processor: &JsonLd,
+    document: &Value,
+    mut options: serde_json::Map<String, Value>,
+) -> Result<String, LdSignatureError> {
+    options.insert(
+        "@context".to_owned(),
+        Value::String(IDENTITY_CONTEXT.to_owned()),
+    );
+
+    let options = processor
+        .canonicalize(&Value::Object(options))
+        .map_err(LdSignatureError::Canonicalize)?;
+    let document = processor
+        .canonicalize(document)
+        .map_err(LdSignatureError::Canonicalize)?;
+
+    Ok(format!("{}{}", sha256(&options), sha256(&document)))
+}
+
+fn sha256(input: &str) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The errors that can occur while signing or verifying a document
+#[derive(Debug)]
+pub enum LdSignatureError {
+    /// The document has no `signature` property
+    MissingSignature,
+
+    /// The `signature` property is not a valid signature
+    InvalidSignature,
+
+    /// The signature is of a type that is not supported
+    UnsupportedType(String),
+
+    /// The signature was made with a different key than the one it was checked against
+    KeyMismatch,
+
+    /// The signature does not match the document
+    BadSignature,
+
+    /// The document to sign is not a JSON object
+    InvalidDocument,
+
+    /// The document could not be canonicalized
+    Canonicalize(JsonLdError),
+
+    /// The public key could not be parsed
+    PublicKey(PublicKeyPemError),
+
+    /// The private key could not be parsed
+    PrivateKey,
+
+    /// The document could not be serialized
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for LdSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LdSignatureError::MissingSignature => f.write_str("Document is not signed"),
+            LdSignatureError::InvalidSignature => f.write_str("Invalid signature property"),
+            LdSignatureError::UnsupportedType(ref kind) => {
+                write!(f, "Unsupported signature type {:?}", kind)
+            }
+            LdSignatureError::KeyMismatch => f.write_str("Signature was made with a different key"),
+            LdSignatureError::BadSignature => f.write_str("Signature does not match the document"),
+            LdSignatureError::InvalidDocument => f.write_str("Only JSON objects can be signed"),
+            LdSignatureError::Canonicalize(ref e) => {
+                write!(f, "Failed to canonicalize document, {}", e)
+            }
+            LdSignatureError::PublicKey(ref e) => fmt::Display::fmt(e, f),
+            LdSignatureError::PrivateKey => f.write_str("Invalid RSA private key"),
+            LdSignatureError::Serialize(ref e) => write!(f, "Failed to serialize document, {}", e),
+        }
+    }
+}
+
+impl Error for LdSignatureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LdSignatureError::Canonicalize(ref e) => Some(e),
+            LdSignatureError::PublicKey(ref e) => Some(e),
+            LdSignatureError::Serialize(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Namespace for properties from the Security Vocabulary
+//!
+//! To use these properties in your own types, you can flatten them into your struct with serde:
+//!
+//! ```rust
+//! extern crate activitystreams_traits;
+//! extern crate activitystreams_types;
+//! extern crate serde;
+//! #[macro_use]
+//! extern crate serde_derive;
+//!
+//! use activitystreams_traits::{Actor, Object};
+//! use activitystreams_types::{
+//!   actor::properties::ActorProperties,
+//!   object::properties::ObjectProperties,
+//!   security::properties::SecurityProperties,
+//! };
+//!
+//! #[derive(Clone, Debug, Serialize, Deserialize)]
+//! #[serde(rename_all = "camelCase")]
+//! pub struct MyActor {
+//!     #[serde(rename = "type")]
+//!     pub kind: String,
+//!
+//!     #[serde(flatten)]
+//!     pub object_properties: ObjectProperties,
+//!
+//!     #[serde(flatten)]
+//!     pub actor_properties: ActorProperties,
+//!
+//!     #[serde(flatten)]
+//!     pub security_properties: SecurityProperties,
+//! }
+//!
+//! impl Object for MyActor {}
+//! impl Actor for MyActor {}
+//! #
+//! # fn main() {}
+//! ```
+
+use activitystreams_traits::properties::{Either, OneOrMany};
+use serde_json;
+
+use primitives::XsdAnyUri;
+use security::{Multikey, PublicKey};
+
+/// Define the properties from the Security Vocabulary that are used by ActivityPub actors.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Properties)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityProperties {
+    /// The public keys of the actor, used to verify the signatures it produces.
+    ///
+    /// Keys are usually embedded in the actor, but MAY be referenced by their id.
+    ///
+    /// - Range: `PublicKey` | `xsd:anyURI`
+    /// - Functional: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(PublicKey, XsdAnyUri))]
+    pub public_key: Option<OneOrMany<Either<PublicKey, serde_json::Value>>>,
+
+    /// The keys that can verify the integrity proofs of objects the actor publishes.
+    ///
+    /// Keys are usually embedded in the actor as `Multikey`s, but MAY be referenced by their id.
+    ///
+    /// - Range: `Multikey` | `xsd:anyURI`
+    /// - Functional: false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[activitystreams(concrete(Multikey, XsdAnyUri))]
+    pub assertion_method: Option<OneOrMany<Either<Multikey, serde_json::Value>>>,
+}
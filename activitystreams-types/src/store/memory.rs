@@ -0,0 +1,122 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use serde_json::Value;
+use std::{collections::HashMap, convert::Infallible};
+
+use primitives::XsdAnyUri;
+use store::{ActivityStore, Reference};
+
+/// An `ActivityStore` that keeps values in memory
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{
+///     collection::OrderedCollection,
+///     store::{ActivityStore, MemoryActivityStore},
+/// };
+///
+/// # fn main() {
+/// let mut store = MemoryActivityStore::new();
+///
+/// let outbox: OrderedCollection = serde_json::from_value(json!({
+///     "type": "OrderedCollection",
+///     "id": "https://example.com/users/alice/outbox",
+///     "items": [
+///         "https://example.com/activities/2",
+///         { "type": "Like", "actor": "https://example.com/users/alice", "object": "https://example.org/notes/3" },
+///         "https://example.com/activities/1"
+///     ]
+/// })).unwrap();
+/// let id = store.put(&outbox).unwrap();
+///
+/// for n in 1..3 {
+///     store.put(&json!({
+///         "type": "Create",
+///         "id": format!("https://example.com/activities/{}", n),
+///         "actor": "https://example.com/users/alice",
+///         "object": format!("https://example.com/notes/{}", n)
+///     })).unwrap();
+/// }
+///
+/// let kinds: Vec<_> = store
+///     .collection_items(&id)
+///     .unwrap()
+///     .iter()
+///     .map(|item| item.kind().unwrap().to_owned())
+///     .collect();
+/// assert_eq!(kinds, vec!["Create", "Like", "Create"]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryActivityStore {
+    values: HashMap<XsdAnyUri, (u64, Value)>,
+    next_sequence: u64,
+}
+
+impl MemoryActivityStore {
+    pub fn new() -> Self {
+        MemoryActivityStore::default()
+    }
+
+    /// The number of stored values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether no values are stored
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl ActivityStore for MemoryActivityStore {
+    type Error = Infallible;
+
+    fn put_value(&mut self, id: &XsdAnyUri, value: &Value) -> Result<(), Self::Error> {
+        let sequence = match self.values.get(id) {
+            Some(&(sequence, _)) => sequence,
+            None => {
+                self.next_sequence += 1;
+                self.next_sequence
+            }
+        };
+
+        self.values.insert(id.clone(), (sequence, value.clone()));
+        Ok(())
+    }
+
+    fn get_value(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error> {
+        Ok(self.values.get(id).map(|(_, value)| value.clone()))
+    }
+
+    fn find_values(&self, reference: Reference, id: &XsdAnyUri) -> Result<Vec<Value>, Self::Error> {
+        let mut found: Vec<&(u64, Value)> = self
+            .values
+            .values()
+            .filter(|(_, value)| reference.ids(value).contains(id))
+            .collect();
+        found.sort_by_key(|(sequence, _)| *sequence);
+
+        Ok(found.into_iter().map(|(_, value)| value.clone()).collect())
+    }
+}
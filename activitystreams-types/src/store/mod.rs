@@ -0,0 +1,421 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Storage for objects and activities
+//!
+//! An `ActivityStore` keeps the serialized form of objects, activities, actors and collections by
+//! their `id`. Stored values are read back as a `StoredObject`, which holds the type from this
+//! crate that matches the stored `type`.
+//!
+//! Besides looking values up by id, a store lists the items of a collection in order, and finds
+//! the objects that refer to another one through `inReplyTo`, `attributedTo` or `object`.
+//!
+//! `MemoryActivityStore` keeps values in memory. With the `sqlite` feature, `SqliteActivityStore`
+//! keeps them in a SQLite database.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! #[macro_use]
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     object::{AnyObject, Note},
+//!     store::{ActivityStore, MemoryActivityStore, StoredObject},
+//! };
+//!
+//! # fn main() {
+//! let mut store = MemoryActivityStore::new();
+//!
+//! let note: Note = serde_json::from_value(json!({
+//!     "type": "Note",
+//!     "id": "https://example.com/notes/1",
+//!     "attributedTo": "https://example.com/users/alice",
+//!     "content": "Hello"
+//! })).unwrap();
+//!
+//! let reply: Note = serde_json::from_value(json!({
+//!     "type": "Note",
+//!     "id": "https://example.org/notes/2",
+//!     "inReplyTo": "https://example.com/notes/1",
+//!     "content": "Hi"
+//! })).unwrap();
+//!
+//! let id = store.put(&note).unwrap();
+//! store.put(&reply).unwrap();
+//!
+//! match store.get(&id).unwrap() {
+//!     Some(StoredObject::Object(AnyObject::Note(note))) => {
+//!         assert_eq!(note.object_props.content_string().unwrap(), "Hello");
+//!     }
+//!     _ => panic!("Expected a Note"),
+//! }
+//!
+//! let replies = store.replies(&id).unwrap();
+//! assert_eq!(replies.len(), 1);
+//! assert_eq!(replies[0].kind(), Some("Note"));
+//! # }
+//! ```
+
+use activitystreams_traits::{
+    self,
+    properties::{from_value, to_value},
+};
+use serde::{
+    de::DeserializeOwned,
+    ser::{Serialize, Serializer},
+};
+use serde_json::Value;
+use std::{collections::HashSet, error::Error, fmt};
+
+use activity::AnyActivity;
+use actor::AnyActor;
+use addressing::{address_id, values};
+use collection::AnyCollection;
+use custom_props::kinds;
+use object::AnyObject;
+use primitives::XsdAnyUri;
+
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use self::memory::MemoryActivityStore;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteActivityStore;
+
+/// The properties through which stored values refer to other objects
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reference {
+    /// The objects a value is a reply to
+    InReplyTo,
+
+    /// The actors a value is attributed to
+    AttributedTo,
+
+    /// The objects of an activity
+    Object,
+}
+
+impl Reference {
+    /// Every reference that stores keep track of
+    pub const ALL: [Reference; 3] = [
+        Reference::InReplyTo,
+        Reference::AttributedTo,
+        Reference::Object,
+    ];
+
+    /// The name of the property
+    pub fn property(&self) -> &'static str {
+        match *self {
+            Reference::InReplyTo => "inReplyTo",
+            Reference::AttributedTo => "attributedTo",
+            Reference::Object => "object",
+        }
+    }
+
+    /// Read the ids a value refers to through this property
+    ///
+    /// References may be given as ids, or as embedded objects or links with an id. They are parsed,
+    /// so that they match ids however they were written, and references that are not valid IRIs
+    /// are skipped.
+    pub fn ids(&self, value: &Value) -> Vec<XsdAnyUri> {
+        value
+            .get(self.property())
+            .map(values)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|reference| match *reference {
+                Value::Object(ref link) if link.contains_key("href") => {
+                    link.get("href").and_then(Value::as_str)
+                }
+                ref reference => address_id(reference),
+            })
+            .filter_map(|id| id.parse().ok())
+            .collect()
+    }
+}
+
+/// A stored value, read back as the type from this crate that matches its `type`
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum StoredObject {
+    Activity(AnyActivity),
+    Actor(AnyActor),
+    Collection(AnyCollection),
+    Object(AnyObject),
+
+    /// Holds any value whose `type` is not recognised
+    Unknown(Value),
+}
+
+impl StoredObject {
+    /// Read a value as the type that matches its `type`
+    ///
+    /// When a value has several types, the first of activity, actor, collection and object types
+    /// that recognises one of them is used.
+    pub fn from_value(value: Value) -> activitystreams_traits::Result<Self> {
+        let kinds = value.as_object().map(kinds).unwrap_or_default();
+        let recognises = |known: &[&str]| kinds.iter().any(|kind| known.contains(kind));
+
+        if recognises(AnyActivity::KINDS) {
+            from_value(&value).map(StoredObject::Activity)
+        } else if recognises(AnyActor::KINDS) {
+            from_value(&value).map(StoredObject::Actor)
+        } else if recognises(AnyCollection::KINDS) {
+            from_value(&value).map(StoredObject::Collection)
+        } else if recognises(AnyObject::KINDS) {
+            from_value(&value).map(StoredObject::Object)
+        } else {
+            Ok(StoredObject::Unknown(value))
+        }
+    }
+
+    /// Fetch the `type` of the contained value, if one is present
+    pub fn kind(&self) -> Option<&str> {
+        match *self {
+            StoredObject::Activity(ref activity) => activity.kind(),
+            StoredObject::Actor(ref actor) => actor.kind(),
+            StoredObject::Collection(ref collection) => collection.kind(),
+            StoredObject::Object(ref object) => object.kind(),
+            StoredObject::Unknown(ref value) => value.get("type").and_then(Value::as_str),
+        }
+    }
+
+    /// Convert the contained value into another type that shares its JSON representation
+    pub fn into_concrete<T>(self) -> activitystreams_traits::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        from_value(&to_value(&self)?)
+    }
+}
+
+impl Serialize for StoredObject {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            StoredObject::Activity(ref activity) => activity.serialize(serializer),
+            StoredObject::Actor(ref actor) => actor.serialize(serializer),
+            StoredObject::Collection(ref collection) => collection.serialize(serializer),
+            StoredObject::Object(ref object) => object.serialize(serializer),
+            StoredObject::Unknown(ref value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// The error produced when a value can not be stored or read
+#[derive(Debug)]
+pub enum StoreError<E> {
+    /// The value has no `id` that is a valid IRI, so it can not be stored
+    MissingId,
+
+    /// The value could not be serialized, or a stored value could not be read
+    Serde(activitystreams_traits::Error),
+
+    /// The backend of the store failed
+    Backend(E),
+}
+
+impl<E> fmt::Display for StoreError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StoreError::MissingId => f.write_str("Value has no id"),
+            StoreError::Serde(ref e) => fmt::Display::fmt(e, f),
+            StoreError::Backend(ref e) => write!(f, "Store failed, {}", e),
+        }
+    }
+}
+
+impl<E> Error for StoreError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            StoreError::Serde(ref e) => Some(e),
+            StoreError::Backend(ref e) => Some(e),
+            StoreError::MissingId => None,
+        }
+    }
+}
+
+impl<E> From<activitystreams_traits::Error> for StoreError<E> {
+    fn from(e: activitystreams_traits::Error) -> Self {
+        StoreError::Serde(e)
+    }
+}
+
+/// A store of objects and activities
+///
+/// Backends only store and find serialized values. Reading values back as the types from this
+/// crate is provided on top of that.
+pub trait ActivityStore {
+    /// The error produced when the backend fails
+    type Error;
+
+    /// Store a serialized value under an id, replacing any value stored under that id before
+    ///
+    /// A replaced value keeps its place in the order values are found in.
+    fn put_value(&mut self, id: &XsdAnyUri, value: &Value) -> Result<(), Self::Error>;
+
+    /// Find the serialized value stored under an id
+    fn get_value(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error>;
+
+    /// Find the serialized values that refer to an id through a property, in the order they were
+    /// first stored in
+    fn find_values(&self, reference: Reference, id: &XsdAnyUri) -> Result<Vec<Value>, Self::Error>;
+
+    /// Store an object, activity, actor or collection under its `id`, and return that id
+    fn put<T>(&mut self, object: &T) -> Result<XsdAnyUri, StoreError<Self::Error>>
+    where
+        T: Serialize,
+    {
+        let value = to_value(object)?;
+        let id = value
+            .get("id")
+            .and_then(Value::as_str)
+            .and_then(|id| id.parse().ok())
+            .ok_or(StoreError::MissingId)?;
+
+        self.put_value(&id, &value).map_err(StoreError::Backend)?;
+        Ok(id)
+    }
+
+    /// Find the value stored under an id
+    fn get(&self, id: &XsdAnyUri) -> Result<Option<StoredObject>, StoreError<Self::Error>> {
+        match self.get_value(id).map_err(StoreError::Backend)? {
+            Some(value) => Ok(Some(StoredObject::from_value(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the value stored under an id, as a specific type
+    fn get_as<T>(&self, id: &XsdAnyUri) -> Result<Option<T>, StoreError<Self::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.get_value(id).map_err(StoreError::Backend)? {
+            Some(value) => Ok(Some(from_value(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the values that refer to an id through a property
+    fn find(
+        &self,
+        reference: Reference,
+        id: &XsdAnyUri,
+    ) -> Result<Vec<StoredObject>, StoreError<Self::Error>> {
+        self.find_values(reference, id)
+            .map_err(StoreError::Backend)?
+            .into_iter()
+            .map(|value| StoredObject::from_value(value).map_err(StoreError::Serde))
+            .collect()
+    }
+
+    /// Find the replies to an object
+    fn replies(&self, id: &XsdAnyUri) -> Result<Vec<StoredObject>, StoreError<Self::Error>> {
+        self.find(Reference::InReplyTo, id)
+    }
+
+    /// Find the objects attributed to an actor
+    fn attributed_to(&self, id: &XsdAnyUri) -> Result<Vec<StoredObject>, StoreError<Self::Error>> {
+        self.find(Reference::AttributedTo, id)
+    }
+
+    /// Find the activities whose `object` is an object
+    fn activities_on(&self, id: &XsdAnyUri) -> Result<Vec<StoredObject>, StoreError<Self::Error>> {
+        self.find(Reference::Object, id)
+    }
+
+    /// List the items of a stored collection, in order
+    ///
+    /// The items are read from the `items` or `orderedItems` of the collection. When the
+    /// collection is paged, its pages are followed from `first` through `next`, as long as they
+    /// are stored as well. Items given by id are looked up in the store, and skipped when they
+    /// are not stored.
+    fn collection_items(
+        &self,
+        id: &XsdAnyUri,
+    ) -> Result<Vec<StoredObject>, StoreError<Self::Error>> {
+        let mut items = Vec::new();
+        let mut visited = HashSet::new();
+        let mut is_collection = true;
+        let mut next = self.get_value(id).map_err(StoreError::Backend)?;
+
+        while let Some(page) = next.take() {
+            if let Some(id) = page.get("id").and_then(Value::as_str) {
+                if !visited.insert(id.to_owned()) {
+                    break;
+                }
+            }
+
+            for item in page_items(&page) {
+                let item = if item.is_object() {
+                    Some(item.clone())
+                } else {
+                    lookup(self, item)?
+                };
+
+                if let Some(item) = item {
+                    items.push(StoredObject::from_value(item)?);
+                }
+            }
+
+            let link = if is_collection {
+                page.get("first")
+            } else {
+                page.get("next")
+            };
+            is_collection = false;
+
+            next = match link {
+                Some(page) if page_items(page).is_empty() => lookup(self, page)?,
+                Some(page) => Some(page.clone()),
+                None => None,
+            };
+        }
+
+        Ok(items)
+    }
+}
+
+/// Borrow the items of a collection or of one of its pages
+fn page_items(page: &Value) -> Vec<&Value> {
+    page.get("orderedItems")
+        .or_else(|| page.get("items"))
+        .map(values)
+        .unwrap_or_default()
+}
+
+/// Look up the value a reference points to, given as an id or as an object with an id
+fn lookup<S>(store: &S, reference: &Value) -> Result<Option<Value>, StoreError<S::Error>>
+where
+    S: ActivityStore + ?Sized,
+{
+    match address_id(reference).and_then(|id| id.parse().ok()) {
+        Some(id) => store.get_value(&id).map_err(StoreError::Backend),
+        None => Ok(None),
+    }
+}
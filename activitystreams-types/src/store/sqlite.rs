@@ -0,0 +1,179 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use rusqlite::{params, types::Type, Connection, OptionalExtension};
+use serde_json::{self, Value};
+use std::path::Path;
+
+use primitives::XsdAnyUri;
+use store::{ActivityStore, Reference};
+
+/// Create the tables of the store, unless they exist already
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS activitystreams_objects (
+        sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+        id TEXT NOT NULL UNIQUE,
+        kind TEXT,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS activitystreams_references (
+        object INTEGER NOT NULL REFERENCES activitystreams_objects (sequence) ON DELETE CASCADE,
+        property TEXT NOT NULL,
+        target TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS activitystreams_references_target
+        ON activitystreams_references (property, target);
+";
+
+/// An `ActivityStore` that keeps values in a SQLite database
+///
+/// Values are kept as JSON in the `activitystreams_objects` table, and the references between
+/// them in the `activitystreams_references` table. Both are created when the store is opened.
+///
+/// ```rust
+/// extern crate activitystreams_types;
+/// #[macro_use]
+/// extern crate serde_json;
+///
+/// use activitystreams_types::{
+///     actor::Person,
+///     store::{ActivityStore, SqliteActivityStore},
+/// };
+///
+/// # fn main() {
+/// let mut store = SqliteActivityStore::open_in_memory().unwrap();
+///
+/// let id = store.put(&json!({
+///     "type": "Person",
+///     "id": "https://example.com/users/alice",
+///     "name": "Alice"
+/// })).unwrap();
+///
+/// let alice: Person = store.get_as(&id).unwrap().unwrap();
+/// assert_eq!(alice.object_props.name_string().unwrap(), "Alice");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SqliteActivityStore {
+    connection: Connection,
+}
+
+impl SqliteActivityStore {
+    /// Open the database at the given path, creating it if it does not exist
+    pub fn open<P>(path: P) -> rusqlite::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        SqliteActivityStore::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a database that only lives in memory
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        SqliteActivityStore::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Use an open database
+    pub fn from_connection(connection: Connection) -> rusqlite::Result<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(SqliteActivityStore { connection })
+    }
+
+    /// Borrow the database
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+impl ActivityStore for SqliteActivityStore {
+    type Error = rusqlite::Error;
+
+    fn put_value(&mut self, id: &XsdAnyUri, value: &Value) -> Result<(), Self::Error> {
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO activitystreams_objects (id, kind, value) VALUES (?1, ?2, ?3)
+                ON CONFLICT (id) DO UPDATE SET kind = excluded.kind, value = excluded.value",
+            params![
+                id.as_str(),
+                value.get("type").and_then(Value::as_str),
+                value.to_string()
+            ],
+        )?;
+
+        let sequence: i64 = transaction.query_row(
+            "SELECT sequence FROM activitystreams_objects WHERE id = ?1",
+            params![id.as_str()],
+            |row| row.get(0),
+        )?;
+
+        transaction.execute(
+            "DELETE FROM activitystreams_references WHERE object = ?1",
+            params![sequence],
+        )?;
+
+        for reference in &Reference::ALL {
+            for target in reference.ids(value) {
+                transaction.execute(
+                    "INSERT INTO activitystreams_references (object, property, target)
+                        VALUES (?1, ?2, ?3)",
+                    params![sequence, reference.property(), target.as_str()],
+                )?;
+            }
+        }
+
+        transaction.commit()
+    }
+
+    fn get_value(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error> {
+        self.connection
+            .query_row(
+                "SELECT value FROM activitystreams_objects WHERE id = ?1",
+                params![id.as_str()],
+                |row| parse(row.get(0)?),
+            )
+            .optional()
+    }
+
+    fn find_values(&self, reference: Reference, id: &XsdAnyUri) -> Result<Vec<Value>, Self::Error> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT DISTINCT o.sequence, o.value
+                FROM activitystreams_objects o
+                JOIN activitystreams_references r ON r.object = o.sequence
+                WHERE r.property = ?1 AND r.target = ?2
+                ORDER BY o.sequence",
+        )?;
+
+        let values = statement
+            .query_map(params![reference.property(), id.as_str()], |row| {
+                parse(row.get(1)?)
+            })?
+            .collect();
+        values
+    }
+}
+
+/// Parse a stored value
+fn parse(value: String) -> rusqlite::Result<Value> {
+    serde_json::from_str(&value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))
+}
@@ -0,0 +1,543 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Strict deserialization, for documents that should be rejected rather than repaired
+//!
+//! Deserializing with `serde_json` is lenient: properties a type does not know about are dropped,
+//! and properties that accept any `Object` or `Link` accept any JSON value, only failing later in
+//! a getter. That suits federation, where other servers send all kinds of documents, but not a
+//! client-to-server API, where a typo such as `inReplyTO` should be reported to the client.
+//!
+//! `strict::from_value` and `strict::from_str` check every property against the range documented
+//! for it, reject properties that are not part of the vocabulary or not supported by the type,
+//! and report the JSON pointer of the offending value.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//!
+//! use activitystreams_types::{object::Note, strict::{self, StrictErrorKind}};
+//!
+//! # fn main() {
+//! let error = strict::from_str::<Note>(r#"{
+//!     "type": "Note",
+//!     "inReplyTO": "https://example.com/notes/1"
+//! }"#).unwrap_err();
+//!
+//! assert_eq!(error.path, "/inReplyTO");
+//! assert_eq!(error.kind, StrictErrorKind::UnknownProperty);
+//!
+//! let error = strict::from_str::<Note>(r#"{
+//!     "type": "Note",
+//!     "published": 1528884000
+//! }"#).unwrap_err();
+//!
+//! assert_eq!(error.path, "/published");
+//! assert_eq!(error.to_string(), "Invalid value at /published, expected `xsd:dateTime`");
+//! # }
+//! ```
+//!
+//! The vocabulary is read from the `PROPERTIES` tables generated for every struct deriving
+//! `Properties`, so values are checked against the range documented on each field. The `type` and
+//! the properties a document may have at the top level come from the `Vocabulary` of the type it
+//! is read as. Embedded objects are checked against the whole vocabulary, since their type is not
+//! known. The extending properties of a `CustomObject` or `CustomLink` are accepted at the top
+//! level if their `Vocabulary` lists them, but are not checked beyond what their own
+//! `Deserialize` implementation does.
+//!
+//! Terms defined in the document's `@context`, such as the `sensitive` flag and the `toot:`
+//! prefix used by Mastodon, are accepted anywhere without being checked, as are JSON-LD keywords
+//! such as `@context` itself. At the top level they are still only kept if the type keeps them,
+//! as `WithUnknown` does.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//!
+//! use activitystreams_types::{object::Note, strict, WithUnknown};
+//!
+//! # fn main() {
+//! let note = strict::from_str::<WithUnknown<Note>>(r#"{
+//!     "@context": [
+//!         "https://www.w3.org/ns/activitystreams",
+//!         { "toot": "http://joinmastodon.org/ns#", "sensitive": "as:sensitive" }
+//!     ],
+//!     "type": "Note",
+//!     "sensitive": true,
+//!     "tag": [{ "type": "toot:Emoji", "name": ":blobcat:", "toot:featured": true }]
+//! }"#).unwrap();
+//!
+//! assert_eq!(note.unknown_as::<bool>("sensitive").unwrap(), true);
+//! # }
+//! ```
+
+use chrono::DateTime;
+use mime::Mime;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Map, Value};
+use std::{error::Error, fmt};
+
+use activitystreams_traits::properties::{PropertyInfo, Vocabulary};
+
+use activity::properties::{
+    ActivityProperties, ActorAndObject, ActorAndObjectOptOrigin, ActorAndObjectOptOthers,
+    ActorAndObjectOptTarget, ActorAndOrigin, ActorObjectAndTarget, ActorOptOriginAndTarget,
+    QuestionProperties,
+};
+use actor::properties::{ActorProperties, Endpoints};
+use collection::properties::{
+    CollectionPageProperties, CollectionProperties, OrderedCollectionPageProperties,
+    OrderedCollectionProperties,
+};
+use custom_props::kinds;
+use jsonld::Context;
+use link::properties::LinkProperties;
+use object::properties::{
+    ObjectProperties, PlaceProperties, ProfileProperties, RelationshipProperties,
+    TombstoneProperties,
+};
+use primitives::{LanguageTag, XsdAnyUri, XsdDuration};
+use security::{properties::SecurityProperties, Multikey, Proof, PublicKey};
+
+/// The properties of every struct in the crate's types
+const VOCABULARY: &[&[PropertyInfo]] = &[
+    ObjectProperties::PROPERTIES,
+    PlaceProperties::PROPERTIES,
+    ProfileProperties::PROPERTIES,
+    RelationshipProperties::PROPERTIES,
+    TombstoneProperties::PROPERTIES,
+    LinkProperties::PROPERTIES,
+    ActorProperties::PROPERTIES,
+    Endpoints::PROPERTIES,
+    CollectionProperties::PROPERTIES,
+    CollectionPageProperties::PROPERTIES,
+    OrderedCollectionProperties::PROPERTIES,
+    OrderedCollectionPageProperties::PROPERTIES,
+    ActivityProperties::PROPERTIES,
+    ActorOptOriginAndTarget::PROPERTIES,
+    ActorAndObject::PROPERTIES,
+    ActorObjectAndTarget::PROPERTIES,
+    ActorAndObjectOptTarget::PROPERTIES,
+    ActorAndObjectOptOrigin::PROPERTIES,
+    ActorAndObjectOptOthers::PROPERTIES,
+    ActorAndOrigin::PROPERTIES,
+    QuestionProperties::PROPERTIES,
+    SecurityProperties::PROPERTIES,
+    PublicKey::PROPERTIES,
+    Multikey::PROPERTIES,
+    Proof::PROPERTIES,
+];
+
+/// Every type stores `type` in its own way, so it is not part of any `PROPERTIES` table
+const TYPE: PropertyInfo = PropertyInfo {
+    name: "type",
+    range: "The name or IRI of a type",
+    functional: false,
+    required: false,
+};
+
+/// One of the alternatives of a documented range
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    /// An instance of a class such as `Object` or `Link`, embedded or referenced by its IRI
+    Node,
+
+    Iri,
+    DateTime,
+    Duration,
+    NonNegativeInteger,
+    Float,
+    String,
+    LangString,
+    LanguageTag,
+    MediaType,
+
+    /// A string that must be exactly this value, such as `cm` for `units`
+    Literal(&'static str),
+
+    /// Anything, for properties whose range is not documented
+    Any,
+}
+
+impl Kind {
+    /// The alternatives of a range, as written in the `- Range:` line of a property
+    fn of(range: &'static str) -> Vec<Kind> {
+        if range.is_empty() {
+            return vec![Kind::Any];
+        }
+        if range.contains("BCP47") {
+            return vec![Kind::LanguageTag];
+        }
+
+        let kinds: Vec<Kind> = range
+            .split('`')
+            .skip(1)
+            .step_by(2)
+            .map(|name| match name {
+                "xsd:anyUri" | "xsd:anyURI" | "anyUri" => Kind::Iri,
+                "xsd:dateTime" => Kind::DateTime,
+                "xsd:duration" => Kind::Duration,
+                "xsd:nonNegativeInteger" => Kind::NonNegativeInteger,
+                "xsd:float" => Kind::Float,
+                "xsd:string" => Kind::String,
+                "rdf:langString" => Kind::LangString,
+                "Mime Media Type" => Kind::MediaType,
+                name if name.starts_with(char::is_uppercase) => Kind::Node,
+                name => Kind::Literal(name),
+            })
+            .collect();
+
+        // Ranges described in prose, such as link relations, accept any string
+        if kinds.is_empty() {
+            vec![Kind::String]
+        } else {
+            kinds
+        }
+    }
+
+    /// Check a single value, which is not an array
+    fn accepts(self, value: &Value) -> bool {
+        let string = value.as_str();
+
+        match self {
+            Kind::Node => value.is_object() || string.is_some_and(is_iri),
+            Kind::Iri => string.is_some_and(is_iri),
+            Kind::DateTime => string.is_some_and(|s| DateTime::parse_from_rfc3339(s).is_ok()),
+            Kind::Duration => string.is_some_and(|s| s.parse::<XsdDuration>().is_ok()),
+            Kind::NonNegativeInteger => value.is_u64(),
+            Kind::Float => value.is_number(),
+            Kind::String => string.is_some(),
+            // Language maps, such as `contentMap`, share the range of the property they map
+            Kind::LangString => {
+                string.is_some()
+                    || value.as_object().is_some_and(|map| {
+                        map.iter().all(|(tag, value)| {
+                            tag.parse::<LanguageTag>().is_ok() && value.is_string()
+                        })
+                    })
+            }
+            Kind::LanguageTag => string.is_some_and(|s| s.parse::<LanguageTag>().is_ok()),
+            Kind::MediaType => string.is_some_and(|s| s.parse::<Mime>().is_ok()),
+            Kind::Literal(literal) => string == Some(literal),
+            Kind::Any => true,
+        }
+    }
+}
+
+/// The terms defined by the `@context` of a document and of the objects embedding a value
+#[derive(Clone, Default)]
+struct Terms {
+    contexts: Vec<Context>,
+}
+
+impl Terms {
+    /// Add the terms defined by the `@context` of an object, if it has one
+    fn with(&self, map: &Map<String, Value>) -> Terms {
+        let mut terms = self.clone();
+        if let Some(context) = map
+            .get("@context")
+            .and_then(|context| serde_json::from_value(context.clone()).ok())
+        {
+            terms.contexts.push(context);
+        }
+        terms
+    }
+
+    /// Check whether a key is a defined term, a compact IRI with a defined prefix, or an IRI
+    fn defines(&self, key: &str) -> bool {
+        let term = |name: &str| self.contexts.iter().any(|c| c.term(name).is_some());
+
+        term(key)
+            || key.find(':').is_some_and(|index| {
+                let (prefix, suffix) = key.split_at(index);
+                suffix.starts_with("://") || term(prefix)
+            })
+    }
+}
+
+/// Deserialize a document, rejecting anything that does not match the documented vocabulary
+pub fn from_value<T>(document: &Value) -> Result<T, StrictError>
+where
+    T: DeserializeOwned + Vocabulary,
+{
+    let map = document
+        .as_object()
+        .ok_or_else(|| StrictError::new(String::new(), StrictErrorKind::NotAnObject))?;
+    let terms = Terms::default().with(map);
+
+    // Check the values first, so that a mistyped value is reported with its path rather than as
+    // a deserialization error
+    for (key, value) in map {
+        if let Some(property) = property(key) {
+            check_property(property, value, &pointer("", key), &terms)?;
+        }
+    }
+
+    let kinds = kinds(map);
+    check_type(T::TYPE, map, &kinds)?;
+
+    // Types that keep every property, such as `WithUnknown`, support anything in the vocabulary
+    if let Some(properties) = T::properties(&kinds) {
+        if let Some(missing) = properties
+            .iter()
+            .find(|property| property.required && !map.contains_key(property.name))
+        {
+            return Err(StrictError::new(
+                pointer("", missing.name),
+                StrictErrorKind::MissingProperty,
+            ));
+        }
+
+        for (key, value) in map {
+            if key == TYPE.name
+                || value.is_null()
+                || is_keyword(key)
+                || properties.iter().any(|property| property.name == key)
+            {
+                continue;
+            }
+
+            let kind = match property(key) {
+                Some(_) => StrictErrorKind::UnsupportedProperty,
+                None if terms.defines(key) => continue,
+                None => StrictErrorKind::UnknownProperty,
+            };
+            return Err(StrictError::new(pointer("", key), kind));
+        }
+    }
+
+    serde_json::from_value(document.clone())
+        .map_err(|e| StrictError::new(String::new(), StrictErrorKind::Deserialize(e)))
+}
+
+/// Parse and strictly deserialize a document
+pub fn from_str<T>(document: &str) -> Result<T, StrictError>
+where
+    T: DeserializeOwned + Vocabulary,
+{
+    let document = serde_json::from_str(document)
+        .map_err(|e| StrictError::new(String::new(), StrictErrorKind::Deserialize(e)))?;
+    from_value(&document)
+}
+
+/// Look up a property of the vocabulary, which JSON-LD keywords such as `@context` are not part of
+fn property(name: &str) -> Option<&'static PropertyInfo> {
+    if is_keyword(name) {
+        return None;
+    }
+    if name == TYPE.name {
+        return Some(&TYPE);
+    }
+
+    VOCABULARY
+        .iter()
+        .flat_map(|properties| properties.iter())
+        .find(|property| property.name == name)
+}
+
+/// Check that a document has a `type` in the range of the type it is read as, if it has one
+fn check_type(
+    range: &'static str,
+    map: &Map<String, Value>,
+    kinds: &[&str],
+) -> Result<(), StrictError> {
+    if range.is_empty() {
+        return Ok(());
+    }
+    if !map.contains_key(TYPE.name) {
+        return Err(StrictError::new(
+            pointer("", TYPE.name),
+            StrictErrorKind::MissingProperty,
+        ));
+    }
+
+    let mut expected = range.split('`').skip(1).step_by(2);
+    if expected.any(|name| kinds.contains(&name)) {
+        Ok(())
+    } else {
+        Err(StrictError::new(
+            pointer("", TYPE.name),
+            StrictErrorKind::InvalidValue { expected: range },
+        ))
+    }
+}
+
+fn check_property(
+    property: &PropertyInfo,
+    value: &Value,
+    path: &str,
+    terms: &Terms,
+) -> Result<(), StrictError> {
+    let invalid = |path: String| {
+        StrictError::new(
+            path,
+            StrictErrorKind::InvalidValue {
+                expected: property.range,
+            },
+        )
+    };
+
+    let values: Vec<(String, &Value)> = match *value {
+        Value::Null => return Ok(()),
+        Value::Array(_) if property.functional => return Err(invalid(path.to_owned())),
+        Value::Array(ref values) => values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (format!("{}/{}", path, index), value))
+            .collect(),
+        ref value => vec![(path.to_owned(), value)],
+    };
+
+    let kinds = Kind::of(property.range);
+
+    for (path, value) in values {
+        if value.is_array() || !kinds.iter().any(|kind| kind.accepts(value)) {
+            return Err(invalid(path));
+        }
+
+        if let Value::Object(ref map) = *value {
+            if kinds.contains(&Kind::Node) {
+                check_node(map, &path, terms)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check an embedded object, whose properties must all be part of the vocabulary or defined by
+/// a `@context`
+fn check_node(map: &Map<String, Value>, path: &str, terms: &Terms) -> Result<(), StrictError> {
+    let terms = terms.with(map);
+
+    for (key, value) in map {
+        let path = pointer(path, key);
+
+        match property(key) {
+            Some(property) => check_property(property, value, &path, &terms)?,
+            None if is_keyword(key) || terms.defines(key) => (),
+            None => return Err(StrictError::new(path, StrictErrorKind::UnknownProperty)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Append a key to a JSON pointer, escaping it as RFC 6901 requires
+pub(crate) fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn is_keyword(key: &str) -> bool {
+    key.starts_with('@')
+}
+
+fn is_iri(value: &str) -> bool {
+    value.parse::<XsdAnyUri>().is_ok()
+}
+
+/// The error returned when a document is rejected by strict deserialization
+#[derive(Debug)]
+pub struct StrictError {
+    /// The JSON pointer of the offending property or value, empty for the whole document
+    pub path: String,
+
+    /// Why the document was rejected
+    pub kind: StrictErrorKind,
+}
+
+impl StrictError {
+    fn new(path: String, kind: StrictErrorKind) -> Self {
+        StrictError { path, kind }
+    }
+}
+
+/// The reasons a document can be rejected by strict deserialization
+#[derive(Debug)]
+pub enum StrictErrorKind {
+    /// The property is not part of the vocabulary, which is usually a typo
+    UnknownProperty,
+
+    /// The property is part of the vocabulary, but the type does not support it
+    UnsupportedProperty,
+
+    /// The type requires the property, but the document does not have it
+    MissingProperty,
+
+    /// The value is not in the range documented for the property
+    InvalidValue {
+        /// The documented range, such as `` `xsd:dateTime` ``
+        expected: &'static str,
+    },
+
+    /// The document is not a JSON object
+    NotAnObject,
+
+    /// The document could not be deserialized for another reason
+    Deserialize(serde_json::Error),
+}
+
+impl PartialEq for StrictErrorKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StrictErrorKind::UnknownProperty, StrictErrorKind::UnknownProperty)
+            | (StrictErrorKind::UnsupportedProperty, StrictErrorKind::UnsupportedProperty)
+            | (StrictErrorKind::MissingProperty, StrictErrorKind::MissingProperty)
+            | (StrictErrorKind::NotAnObject, StrictErrorKind::NotAnObject) => true,
+            (
+                StrictErrorKind::InvalidValue { expected: a },
+                StrictErrorKind::InvalidValue { expected: b },
+            ) => a == b,
+            // serde_json errors can not be compared, so neither can these
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for StrictError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            self.path.as_str()
+        };
+
+        match self.kind {
+            StrictErrorKind::UnknownProperty => write!(f, "Unknown property at {}", path),
+            StrictErrorKind::UnsupportedProperty => {
+                write!(f, "Property at {} is not supported by this type", path)
+            }
+            StrictErrorKind::MissingProperty => write!(f, "Missing required property at {}", path),
+            StrictErrorKind::InvalidValue { expected } => {
+                write!(f, "Invalid value at {}, expected {}", path, expected)
+            }
+            StrictErrorKind::NotAnObject => write!(f, "The document is not a JSON object"),
+            StrictErrorKind::Deserialize(ref e) => {
+                write!(f, "Failed to deserialize document, {}", e)
+            }
+        }
+    }
+}
+
+impl Error for StrictError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.kind {
+            StrictErrorKind::Deserialize(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,459 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+//! Validation of the rules of ActivityStreams and ActivityPub that types can not enforce
+//!
+//! Some rules of the specifications involve more than one property, such as an `endTime` that
+//! must not be before the `startTime`, and can not be expressed in the types of this crate. The
+//! `Validate` trait checks a value against these rules and lists every violation it finds, with
+//! the JSON pointer of the offending property and a severity. Embedded objects, such as the
+//! object of a `Create`, are checked as well.
+//!
+//! Violations of rules that a specification states with MUST are errors, and violations of
+//! rules that are only expected to hold are warnings.
+//!
+//! ```rust
+//! extern crate activitystreams_types;
+//! extern crate serde_json;
+//!
+//! use activitystreams_types::{
+//!     activity::Create,
+//!     validate::{Rule, Severity, Validate},
+//! };
+//!
+//! # fn main() {
+//! let create: Create = serde_json::from_str(r#"{
+//!     "type": "Create",
+//!     "actor": "https://example.com/users/alice",
+//!     "object": {
+//!         "type": "Event",
+//!         "attributedTo": "https://example.com/users/bob",
+//!         "startTime": "2018-06-01T12:00:00Z",
+//!         "endTime": "2018-06-01T11:00:00Z"
+//!     }
+//! }"#).unwrap();
+//!
+//! let violations = create.validate();
+//! assert_eq!(violations.len(), 2);
+//!
+//! assert_eq!(violations[0].rule, Rule::AttributionMismatch);
+//! assert_eq!(violations[0].severity, Severity::Warning);
+//! assert_eq!(violations[0].path, "/object/attributedTo");
+//!
+//! assert_eq!(violations[1].rule, Rule::EndBeforeStart);
+//! assert_eq!(violations[1].severity, Severity::Warning);
+//! assert_eq!(violations[1].path, "/object/endTime");
+//!
+//! assert!(create.is_valid());
+//! # }
+//! ```
+
+use chrono::DateTime;
+use serde::ser::Serialize;
+use serde_json::{self, Map, Value};
+use std::fmt;
+
+use activity::*;
+use actor::*;
+use addressing::values;
+use collection::*;
+use custom_props::{CustomLink, CustomObject, WithUnknown};
+use link::*;
+use object::*;
+use strict::pointer;
+
+/// The types of activity that have no object
+const INTRANSITIVE_TYPES: &[&str] = &["IntransitiveActivity", "Arrive", "Question", "Travel"];
+
+/// The types of collection whose items are all present when the collection is not paged
+const COLLECTION_TYPES: &[&str] = &["Collection", "OrderedCollection"];
+
+/// Properties whose values are not ActivityStreams objects, and are not checked
+const OPAQUE: &[&str] = &["@context", "contentMap", "nameMap", "summaryMap", "proof"];
+
+/// Check a value against the rules of ActivityStreams and ActivityPub
+///
+/// Raw JSON can be validated as well, which allows checking documents before they are
+/// deserialized into one of the types of this crate. Other types are validated through their
+/// JSON form, and a value that can not be serialized is reported as a `Rule::Serialize` error.
+///
+/// ```rust
+/// # extern crate activitystreams_types;
+/// # #[macro_use] extern crate serde_json;
+/// use activitystreams_types::validate::{Rule, Validate};
+///
+/// # fn main() {
+/// let question = json!({
+///     "type": "Question",
+///     "oneOf": [{ "type": "Note", "name": "Yes" }],
+///     "anyOf": [{ "type": "Note", "name": "No" }],
+///     "object": "https://example.com/notes/1"
+/// });
+///
+/// let rules: Vec<Rule> = question.validate().into_iter().map(|v| v.rule).collect();
+/// assert_eq!(rules, vec![Rule::ExclusiveChoices, Rule::IntransitiveObject]);
+/// # }
+/// ```
+pub trait Validate {
+    /// List every rule the value violates, in the order of the document
+    fn validate(&self) -> Vec<Violation>;
+
+    /// Check whether the value violates no rules with the severity `Error`
+    fn is_valid(&self) -> bool {
+        self.validate()
+            .iter()
+            .all(|violation| violation.severity != Severity::Error)
+    }
+}
+
+/// A rule that a value breaks
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Violation {
+    /// The JSON pointer of the offending property
+    pub path: String,
+
+    /// How serious the violation is
+    pub severity: Severity,
+
+    /// The rule that is violated
+    pub rule: Rule,
+
+    /// A description of the violation
+    pub message: String,
+}
+
+/// How serious a violation is
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// The value breaks a rule the specification states with MUST
+    Error,
+
+    /// The value breaks a rule that is expected to hold, and may confuse other servers
+    Warning,
+}
+
+/// The rules that are checked
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Rule {
+    /// A `Question` has both `oneOf` and `anyOf`
+    ExclusiveChoices,
+
+    /// The `endTime` is before the `startTime`
+    EndBeforeStart,
+
+    /// The `totalItems` of a collection does not match the number of its `items`
+    TotalItemsMismatch,
+
+    /// An intransitive activity, such as `Arrive`, has an `object`
+    IntransitiveObject,
+
+    /// The `actor` of a `Create` is not the `attributedTo` of its object
+    AttributionMismatch,
+
+    /// The value could not be serialized to JSON, so no other rule could be checked
+    Serialize,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Severity::Error => write!(f, "Error"),
+            Severity::Warning => write!(f, "Warning"),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}, {}", self.severity, self.path, self.message)
+    }
+}
+
+impl Validate for Value {
+    fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        check_value(self, "", &mut violations);
+        violations
+    }
+}
+
+/// Validate a value through its serialized form
+fn validate_serialized<T>(value: &T) -> Vec<Violation>
+where
+    T: Serialize,
+{
+    match serde_json::to_value(value) {
+        Ok(value) => value.validate(),
+        Err(e) => vec![Violation {
+            path: String::new(),
+            severity: Severity::Error,
+            rule: Rule::Serialize,
+            message: format!("the value could not be serialized, {}", e),
+        }],
+    }
+}
+
+macro_rules! impl_validate {
+    ($($kind:ty),* $(,)*) => {
+        $(
+            impl Validate for $kind {
+                fn validate(&self) -> Vec<Violation> {
+                    validate_serialized(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_validate!(
+    Accept,
+    Add,
+    AMove,
+    Announce,
+    Arrive,
+    Block,
+    Create,
+    Delete,
+    Dislike,
+    Flag,
+    Follow,
+    Ignore,
+    Invite,
+    Join,
+    Leave,
+    Like,
+    Listen,
+    Offer,
+    Question,
+    Read,
+    Reject,
+    Remove,
+    TentativeAccept,
+    TentativeReject,
+    Travel,
+    Undo,
+    Update,
+    View,
+    AnyActivity,
+);
+impl_validate!(Application, Group, Organization, Person, Service, AnyActor);
+impl_validate!(
+    UnorderedCollection,
+    OrderedCollection,
+    UnorderedCollectionPage,
+    OrderedCollectionPage,
+    AnyCollection,
+);
+impl_validate!(Mention, AnyLink);
+impl_validate!(
+    Article,
+    Audio,
+    Document,
+    Event,
+    Image,
+    Note,
+    Page,
+    Place,
+    Profile,
+    Relationship,
+    Tombstone,
+    Video,
+    AnyObject,
+);
+
+impl<T> Validate for WithUnknown<T>
+where
+    T: Serialize,
+{
+    fn validate(&self) -> Vec<Violation> {
+        validate_serialized(self)
+    }
+}
+
+impl<C, O> Validate for CustomObject<C, O>
+where
+    C: Serialize,
+    O: Serialize,
+{
+    fn validate(&self) -> Vec<Violation> {
+        validate_serialized(self)
+    }
+}
+
+impl<C, L> Validate for CustomLink<C, L>
+where
+    C: Serialize,
+    L: Serialize,
+{
+    fn validate(&self) -> Vec<Violation> {
+        validate_serialized(self)
+    }
+}
+
+fn check_value(value: &Value, path: &str, violations: &mut Vec<Violation>) {
+    match *value {
+        Value::Object(ref node) => check_node(node, path, violations),
+        Value::Array(ref values) => {
+            for (index, value) in values.iter().enumerate() {
+                check_value(value, &format!("{}/{}", path, index), violations);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn check_node(node: &Map<String, Value>, path: &str, violations: &mut Vec<Violation>) {
+    let mut violation = |keys: &[&str], severity: Severity, rule: Rule, message: String| {
+        violations.push(Violation {
+            path: keys
+                .iter()
+                .fold(path.to_owned(), |path, key| pointer(&path, key)),
+            severity,
+            rule,
+            message,
+        });
+    };
+
+    let kinds: Vec<&str> = match node.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let is = |types: &[&str]| kinds.iter().any(|kind| types.contains(kind));
+
+    if is(&["Question"]) && present(node, "oneOf") && present(node, "anyOf") {
+        violation(
+            &["anyOf"],
+            Severity::Error,
+            Rule::ExclusiveChoices,
+            "a Question must not have both oneOf and anyOf".to_owned(),
+        );
+    }
+
+    if is(INTRANSITIVE_TYPES) && present(node, "object") {
+        violation(
+            &["object"],
+            Severity::Error,
+            Rule::IntransitiveObject,
+            "an intransitive activity must not have an object".to_owned(),
+        );
+    }
+
+    if is(&["Create"]) {
+        let actors = ids(node.get("actor"));
+        let objects = node.get("object").map(values).unwrap_or_default();
+        let many = node.get("object").is_some_and(Value::is_array);
+
+        for (index, object) in objects.into_iter().enumerate() {
+            let attributed = match object.get("attributedTo") {
+                Some(attributed_to) => ids(Some(attributed_to)),
+                None => continue,
+            };
+
+            if !actors.is_empty() && actors.iter().any(|actor| !attributed.contains(actor)) {
+                let index = index.to_string();
+                let keys: &[&str] = if many {
+                    &["object", &index, "attributedTo"]
+                } else {
+                    &["object", "attributedTo"]
+                };
+
+                violation(
+                    keys,
+                    Severity::Warning,
+                    Rule::AttributionMismatch,
+                    "the object of a Create should be attributed to its actor".to_owned(),
+                );
+            }
+        }
+    }
+
+    // Only check collections that are not paged, whose items must all be present
+    if is(COLLECTION_TYPES) && !present(node, "first") {
+        let total = node.get("totalItems").and_then(Value::as_u64);
+        let items =
+            node.get("items")
+                .or_else(|| node.get("orderedItems"))
+                .map(|items| match *items {
+                    Value::Array(ref items) => items.len() as u64,
+                    Value::Null => 0,
+                    _ => 1,
+                });
+
+        if let (Some(total), Some(items)) = (total, items) {
+            if total != items {
+                violation(
+                    &["totalItems"],
+                    Severity::Warning,
+                    Rule::TotalItemsMismatch,
+                    format!(
+                        "totalItems is {}, but the collection has {} items",
+                        total, items
+                    ),
+                );
+            }
+        }
+    }
+
+    let time = |key: &str| {
+        node.get(key)
+            .and_then(Value::as_str)
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+    };
+    if let (Some(start), Some(end)) = (time("startTime"), time("endTime")) {
+        if end < start {
+            violation(
+                &["endTime"],
+                Severity::Warning,
+                Rule::EndBeforeStart,
+                "endTime is before startTime".to_owned(),
+            );
+        }
+    }
+
+    for (key, value) in node {
+        if !OPAQUE.contains(&key.as_str()) {
+            check_value(value, &pointer(path, key), violations);
+        }
+    }
+}
+
+/// Check whether a property has a value
+fn present(node: &Map<String, Value>, key: &str) -> bool {
+    match node.get(key) {
+        None | Some(&Value::Null) => false,
+        Some(Value::Array(values)) => !values.is_empty(),
+        Some(_) => true,
+    }
+}
+
+/// Collect the ids of the objects and links a property refers to
+fn ids(value: Option<&Value>) -> Vec<&str> {
+    match value {
+        Some(Value::String(id)) => vec![id.as_str()],
+        Some(Value::Object(node)) => node
+            .get("id")
+            .or_else(|| node.get("href"))
+            .and_then(Value::as_str)
+            .into_iter()
+            .collect(),
+        Some(Value::Array(values)) => values.iter().flat_map(|v| ids(Some(v))).collect(),
+        _ => vec![],
+    }
+}
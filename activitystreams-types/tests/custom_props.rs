@@ -0,0 +1,199 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_traits;
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_traits::ErrorKind;
+use activitystreams_types::{
+    activity::{ActivityExt, AnyActivity, Create},
+    actor::{ActorExt, Person},
+    collection::{CollectionExt, CollectionPageExt, OrderedCollectionPage},
+    link::{LinkExt, Mention},
+    object::{Note, ObjectExt},
+    security::SecurityExt,
+    WithUnknown,
+};
+use std::error::Error;
+
+#[test]
+fn unknown_keys_round_trip() {
+    let value = json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "content": "Hello",
+        "sensitive": true,
+        "_misskey_content": "Hello",
+        "tag": [{ "type": "Emoji", "name": ":blobcat:" }],
+        "quoteUrl": null,
+    });
+
+    let note: WithUnknown<Note> = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(note.unknown.len(), 3);
+    assert_eq!(note.unknown("quoteUrl"), Some(&json!(null)));
+    assert!(note.unknown("content").is_none());
+
+    let serialized = serde_json::to_value(&note).unwrap();
+    assert_eq!(serialized, value);
+
+    let again: WithUnknown<Note> = serde_json::from_value(serialized).unwrap();
+    assert_eq!(again.unknown, note.unknown);
+}
+
+#[test]
+fn keys_are_classified_by_type() {
+    // A property of the type is known even when it is not serialized again
+    let note: WithUnknown<Note> = serde_json::from_value(json!({
+        "type": "Note",
+        "summary": null,
+        "sensitive": true,
+    }))
+    .unwrap();
+    assert_eq!(note.unknown.keys().collect::<Vec<_>>(), vec!["sensitive"]);
+
+    // An Arrive has no object, so its object is unknown
+    let value = json!({
+        "type": "Arrive",
+        "actor": "https://example.com/users/alice",
+        "origin": "https://example.com/places/0",
+        "object": "https://example.com/places/1",
+    });
+    let arrive: WithUnknown<AnyActivity> = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(arrive.inner.kind(), Some("Arrive"));
+    assert_eq!(
+        arrive.unknown("object"),
+        Some(&json!("https://example.com/places/1"))
+    );
+    assert!(arrive.unknown("actor").is_none());
+    assert_eq!(serde_json::to_value(&arrive).unwrap(), value);
+
+    // Values of an unrecognised type keep every property themselves
+    let react: WithUnknown<AnyActivity> = serde_json::from_value(json!({
+        "type": "EmojiReact",
+        "actor": "https://example.com/users/alice",
+        "content": ":blobcat:",
+    }))
+    .unwrap();
+    assert!(react.inner.is_unknown());
+    assert!(react.unknown.is_empty());
+}
+
+#[test]
+fn known_properties_take_precedence() {
+    let mut note = WithUnknown::new(Note::default());
+    note.inner
+        .object_props
+        .set_content_string("Known".to_owned())
+        .unwrap();
+    note.set_unknown("content", "Unknown").unwrap();
+    note.set_unknown("sensitive", true).unwrap();
+
+    let value = serde_json::to_value(&note).unwrap();
+    assert_eq!(value["content"], "Known");
+    assert_eq!(value["sensitive"], true);
+
+    assert_eq!(note.remove_unknown("sensitive"), Some(json!(true)));
+    assert!(serde_json::to_value(&note)
+        .unwrap()
+        .get("sensitive")
+        .is_none());
+}
+
+#[test]
+fn unknown_errors_name_the_property() {
+    let note: WithUnknown<Note> = serde_json::from_value(json!({
+        "type": "Note",
+        "sensitive": "yes",
+    }))
+    .unwrap();
+
+    let missing = note.unknown_as::<bool>("quoteUrl").unwrap_err();
+    assert_eq!(missing.kind(), ErrorKind::NotFound);
+    assert_eq!(missing.property(), Some("quoteUrl"));
+    assert!(missing.source().is_none());
+
+    let invalid = note.unknown_as::<bool>("sensitive").unwrap_err();
+    assert_eq!(invalid.kind(), ErrorKind::Deserialize);
+    assert_eq!(invalid.property(), Some("sensitive"));
+    assert_eq!(invalid.variant(), Some("bool"));
+    assert!(invalid.source().is_some());
+}
+
+#[test]
+fn ext_traits_are_forwarded() {
+    let mut person: WithUnknown<Person> = serde_json::from_value(json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "preferredUsername": "alice",
+        "publicKey": {
+            "id": "https://example.com/users/alice#main-key",
+            "owner": "https://example.com/users/alice",
+            "publicKeyPem": "-----BEGIN PUBLIC KEY-----",
+        },
+        "discoverable": true,
+    }))
+    .unwrap();
+
+    assert_eq!(
+        ObjectExt::props(&person).id_string().unwrap(),
+        "https://example.com/users/alice"
+    );
+    assert_eq!(
+        ActorExt::props(&person)
+            .preferred_username_string()
+            .unwrap(),
+        "alice"
+    );
+    assert!(SecurityExt::props(&person).public_key.is_some());
+
+    ObjectExt::props_mut(&mut person)
+        .set_name_string("Alice".to_owned())
+        .unwrap();
+    assert_eq!(person.inner.object_props.name_string().unwrap(), "Alice");
+    assert_eq!(person.unknown("discoverable"), Some(&json!(true)));
+
+    let mention: WithUnknown<Mention> = serde_json::from_value(json!({
+        "type": "Mention",
+        "href": "https://example.com/users/alice",
+        "name": "@alice",
+    }))
+    .unwrap();
+    assert_eq!(
+        LinkExt::props(&mention).href_string().unwrap(),
+        "https://example.com/users/alice"
+    );
+
+    let mut create = WithUnknown::new(Create::default());
+    ActivityExt::props_mut(&mut create)
+        .set_result_object(Note::default())
+        .unwrap();
+    assert!(create.inner.activity_props.result.is_some());
+
+    let page: WithUnknown<OrderedCollectionPage> = serde_json::from_value(json!({
+        "type": "OrderedCollectionPage",
+        "totalItems": 2,
+        "items": [],
+        "next": "https://example.com/outbox?page=2",
+    }))
+    .unwrap();
+    assert_eq!(CollectionExt::props(&page).total_items_u64().unwrap(), &2);
+    assert!(CollectionPageExt::props(&page).next.is_some());
+}
@@ -0,0 +1,201 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+extern crate chrono;
+extern crate serde_json;
+
+use activitystreams_types::{
+    object::properties::UtcTime,
+    primitives::{XsdDuration, XsdDurationError},
+};
+use chrono::Duration;
+use std::convert::TryFrom;
+
+fn time(s: &str) -> UtcTime {
+    s.parse().unwrap()
+}
+
+fn duration(s: &str) -> XsdDuration {
+    s.parse().unwrap()
+}
+
+#[test]
+fn malformed_durations_are_rejected() {
+    let invalid = [
+        "",
+        "-",
+        "P",
+        "PT",
+        "1D",
+        "P1",
+        "P1DT",
+        "PT1D",
+        "P1H",
+        "P1M1Y",
+        "P1D1D",
+        "P-1D",
+        "P+1D",
+        "P1.5D",
+        "PT.S",
+        "PT1..5S",
+        "PT1.5.S",
+        "PT1.0000000001S",
+        "P1DT1H1M1S1",
+        "p1d",
+        "P1X",
+        "P１D",
+        " P1D",
+        "--P1D",
+    ];
+
+    for s in invalid.iter() {
+        assert_eq!(
+            s.parse::<XsdDuration>(),
+            Err(XsdDurationError::Invalid),
+            "{:?}",
+            s
+        );
+    }
+
+    assert_eq!(
+        "P99999999999999999999Y".parse::<XsdDuration>(),
+        Err(XsdDurationError::OutOfRange)
+    );
+
+    let error = serde_json::from_str::<XsdDuration>("\"P1H\"").unwrap_err();
+    assert!(error.to_string().contains("Invalid xsd:duration"));
+    assert!(serde_json::from_str::<XsdDuration>("3600").is_err());
+}
+
+#[test]
+fn components_are_kept_as_written() {
+    let parsed = duration("-P1Y2M3DT4H5M6.075S");
+    assert_eq!(
+        parsed,
+        XsdDuration {
+            negative: true,
+            years: 1,
+            months: 2,
+            days: 3,
+            hours: 4,
+            minutes: 5,
+            seconds: 6,
+            nanoseconds: 75_000_000,
+        }
+    );
+    assert_eq!(parsed.to_string(), "-P1Y2M3DT4H5M6.075S");
+
+    assert_eq!(duration("PT.5S").to_string(), "PT0.5S");
+    assert_eq!(duration("PT5.S").to_string(), "PT5S");
+    assert_eq!(duration("PT90M").to_string(), "PT90M");
+    assert_eq!(duration("P0D").to_string(), "PT0S");
+    assert!(duration("P0Y0M").is_zero());
+}
+
+#[test]
+fn months_and_years_follow_the_calendar() {
+    let end_of_january = time("2018-01-31T12:00:00Z");
+    assert_eq!(
+        end_of_january + duration("P1M"),
+        time("2018-02-28T12:00:00Z")
+    );
+    assert_eq!(
+        end_of_january + duration("P1Y1M"),
+        time("2019-02-28T12:00:00Z")
+    );
+    assert_eq!(
+        end_of_january - duration("P2M"),
+        time("2017-11-30T12:00:00Z")
+    );
+    assert_eq!(
+        end_of_january + duration("-P2M"),
+        time("2017-11-30T12:00:00Z")
+    );
+
+    let leap_day = time("2020-02-29T00:00:00Z");
+    assert_eq!(leap_day + duration("P1Y"), time("2021-02-28T00:00:00Z"));
+    assert_eq!(leap_day + duration("P4Y"), time("2024-02-29T00:00:00Z"));
+    assert_eq!(leap_day - duration("P12M"), time("2019-02-28T00:00:00Z"));
+
+    // Months are applied before the exact part of the duration
+    assert_eq!(
+        time("2018-01-31T00:00:00Z") + duration("P1MT24H"),
+        time("2018-03-01T00:00:00Z")
+    );
+    assert_eq!(
+        time("2018-03-31T00:00:00Z") - duration("P1MT1S"),
+        time("2018-02-27T23:59:59Z")
+    );
+}
+
+#[test]
+fn conversion_to_chrono_uses_fixed_months() {
+    assert_eq!(
+        Duration::try_from(duration("P1Y1M1DT1H1M1.5S")).unwrap(),
+        Duration::days(365 + 30 + 1)
+            + Duration::hours(1)
+            + Duration::minutes(1)
+            + Duration::milliseconds(1500)
+    );
+    assert_eq!(
+        Duration::try_from(duration("-PT1S")).unwrap(),
+        Duration::seconds(-1)
+    );
+    assert_eq!(
+        XsdDuration::from(Duration::milliseconds(-90_500)).to_string(),
+        "-PT1M30.5S"
+    );
+}
+
+#[test]
+fn overflow_is_reported() {
+    let now = time("2018-06-01T12:00:00Z");
+    let huge = duration("P300000Y");
+
+    assert_eq!(huge.checked_add_to(now), None);
+    assert_eq!(huge.checked_sub_from(now), None);
+    assert_eq!(duration("P18446744073709551615M").checked_add_to(now), None);
+    assert_eq!(duration("P18446744073709551615D").checked_add_to(now), None);
+    assert_eq!(
+        Duration::try_from(duration("P18446744073709551615Y")),
+        Err(XsdDurationError::OutOfRange)
+    );
+    assert_eq!(
+        Duration::try_from(duration("P400000000Y")),
+        Err(XsdDurationError::OutOfRange)
+    );
+
+    assert_eq!(
+        duration("P1D").checked_add_to(now),
+        Some(time("2018-06-02T12:00:00Z"))
+    );
+}
+
+#[test]
+#[should_panic(expected = "`UtcTime + XsdDuration` out of range")]
+fn adding_out_of_range_panics() {
+    let _ = time("2018-06-01T12:00:00Z") + duration("P300000Y");
+}
+
+#[test]
+#[should_panic(expected = "`UtcTime - XsdDuration` out of range")]
+fn subtracting_out_of_range_panics() {
+    let _ = time("2018-06-01T12:00:00Z") - duration("P300000Y");
+}
@@ -0,0 +1,131 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    actor::Person,
+    object::{properties::UtcTime, Note},
+    primitives::XsdDuration,
+};
+use serde_json::Value;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+// Allocations are counted per thread, so the tests can run alongside each other
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T, F>(f: F) -> (T, usize)
+where
+    F: FnOnce() -> T,
+{
+    let before = ALLOCATIONS.with(Cell::get);
+    let out = f();
+    (out, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn reading_strings_as_other_types_does_not_allocate() {
+    let note: Note = serde_json::from_value(json!({
+        "type": "Note",
+        "published": "2020-01-01T00:00:00Z",
+        "duration": "PT5M",
+    }))
+    .unwrap();
+
+    let (published, count) = allocations(|| note.object_props.published_utctime());
+    assert_eq!(count, 0);
+    assert_eq!(
+        published.unwrap(),
+        "2020-01-01T00:00:00Z".parse::<UtcTime>().unwrap()
+    );
+
+    let (duration, count) = allocations(|| note.object_props.duration_xsdduration());
+    assert_eq!(count, 0);
+    assert_eq!(duration.unwrap(), "PT5M".parse::<XsdDuration>().unwrap());
+}
+
+#[test]
+fn reading_objects_does_not_clone_the_value() {
+    let value = json!({
+        "type": "Person",
+        "id": "https://example.com/users/alice",
+        "name": "Alice",
+    });
+    let note: Note = serde_json::from_value(json!({
+        "type": "Note",
+        "attributedTo": value,
+    }))
+    .unwrap();
+
+    let (expected, direct) = allocations(|| serde_json::from_value::<Person>(value.clone()));
+    let (person, count) = allocations(|| note.object_props.attributed_to_object::<Person>());
+
+    // Deserializing from a clone pays for the clone as well as the object itself
+    assert!(
+        count < direct,
+        "{} allocations, {} from a clone",
+        count,
+        direct
+    );
+    assert_eq!(
+        serde_json::to_value(person.unwrap()).unwrap(),
+        serde_json::to_value(expected.unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn reading_mistyped_values_still_fails() {
+    let note: Note = serde_json::from_value(json!({
+        "type": "Note",
+        "published": "yesterday",
+        "duration": 5,
+    }))
+    .unwrap();
+
+    assert!(note.object_props.published_utctime().is_err());
+    assert!(note.object_props.duration_xsdduration().is_err());
+    assert_eq!(note.object_props.published_string().unwrap(), "yesterday");
+    assert_eq!(
+        serde_json::to_value(&note).unwrap()["duration"],
+        Value::from(5)
+    );
+}
@@ -0,0 +1,168 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+
+use activitystreams_types::security::{
+    http_signatures::{verify, verify_headers, HttpRequest, SignatureError, Signer},
+    PublicKey,
+};
+
+const PRIVATE_KEY: &str = include_str!("keys/rsa-private.pem");
+const PUBLIC_KEY: &str = include_str!("keys/rsa-public.pem");
+
+const KEY_ID: &str = "https://example.com/users/alice#main-key";
+
+fn public_key(id: &str) -> PublicKey {
+    PublicKey::new(
+        id.parse().unwrap(),
+        "https://example.com/users/alice".parse().unwrap(),
+        PUBLIC_KEY,
+    )
+}
+
+fn request() -> HttpRequest {
+    HttpRequest::new("POST", "/users/bob/inbox")
+        .header("Host", "example.org")
+        .header("Date", "Tue, 07 Jun 2022 20:51:35 GMT")
+        .body(r#"{"type":"Follow"}"#)
+}
+
+fn signed(signer: &Signer) -> HttpRequest {
+    let mut request = request();
+    signer.sign(&mut request).unwrap();
+    request
+}
+
+fn signer() -> Signer {
+    Signer::from_pem(KEY_ID, PRIVATE_KEY).unwrap()
+}
+
+#[test]
+fn verifies_signed_request() {
+    verify(&signed(&signer()), &public_key(KEY_ID)).unwrap();
+}
+
+#[test]
+fn rejects_tampered_signature() {
+    let mut request = signed(&signer());
+    let header = request.get_header("Signature").unwrap();
+    let tampered = header.replacen("signature=\"", "signature=\"AAAA", 1);
+    request.set_header("Signature", tampered);
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::BadSignature) => (),
+        other => panic!("Expected a bad signature, got {:?}", other),
+    }
+
+    let mut request = signed(&signer());
+    request.path = "/users/carol/inbox".to_owned();
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::BadSignature) => (),
+        other => panic!("Expected a bad signature, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_wrong_key_id() {
+    let request = signed(&signer());
+
+    match verify(
+        &request,
+        &public_key("https://example.com/users/mallory#main-key"),
+    ) {
+        Err(SignatureError::KeyMismatch) => (),
+        other => panic!("Expected a key mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_digest_mismatch() {
+    let mut request = signed(&signer());
+    request.body = br#"{"type":"Block"}"#.to_vec();
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::DigestMismatch) => (),
+        other => panic!("Expected a digest mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_unsupported_algorithm() {
+    let mut request = signed(&signer());
+    let header = request.get_header("Signature").unwrap();
+    request.set_header("Signature", header.replace("rsa-sha256", "hmac-sha256"));
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::UnsupportedAlgorithm(ref algorithm)) if algorithm == "hmac-sha256" => {}
+        other => panic!("Expected an unsupported algorithm, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_unsigned_headers() {
+    let request = signed(&signer().headers(&[]));
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::NotSigned(ref name)) if name == "(request-target)" => (),
+        other => panic!("Expected an unsigned header, got {:?}", other),
+    }
+
+    // Without a headers parameter only the Date header is signed
+    let mut request = signed(&signer().headers(&["date"]));
+    let header = request.get_header("Signature").unwrap();
+    request.set_header("Signature", header.replace(",headers=\"date\"", ""));
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::NotSigned(ref name)) if name == "(request-target)" => (),
+        other => panic!("Expected an unsigned header, got {:?}", other),
+    }
+
+    // A body must come with a signed digest
+    let request = signed(&signer().headers(&["(request-target)", "host", "date"]));
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::NotSigned(ref name)) if name == "digest" => (),
+        other => panic!("Expected an unsigned digest, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_missing_signed_header() {
+    let mut request = signed(&signer());
+    request.headers.retain(|(name, _)| name != "Host");
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::MissingHeader(ref name)) if name == "host" => (),
+        other => panic!("Expected a missing header, got {:?}", other),
+    }
+}
+
+#[test]
+fn verifies_custom_required_headers() {
+    let request = signed(&signer().headers(&["(request-target)", "date", "digest"]));
+
+    verify_headers(&request, &public_key(KEY_ID), &["(request-target)", "date"]).unwrap();
+
+    match verify(&request, &public_key(KEY_ID)) {
+        Err(SignatureError::NotSigned(ref name)) if name == "host" => (),
+        other => panic!("Expected an unsigned header, got {:?}", other),
+    }
+}
@@ -0,0 +1,337 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    activity::AnyActivity,
+    inbox::{receive, FollowState, InboxError, MemoryStore, StateChange},
+    primitives::XsdAnyUri,
+};
+use serde_json::Value;
+use std::convert::Infallible;
+
+const ALICE: &str = "https://example.com/users/alice";
+const BOB: &str = "https://example.org/users/bob";
+const MALLORY: &str = "https://evil.example/users/mallory";
+const NOTE: &str = "https://example.com/notes/1";
+const FEATURED: &str = "https://example.com/users/alice/featured";
+
+fn uri(id: &str) -> XsdAnyUri {
+    id.parse().unwrap()
+}
+
+fn send(store: &mut MemoryStore, value: Value) -> Result<Vec<StateChange>, InboxError<Infallible>> {
+    let activity: AnyActivity = serde_json::from_value(value).unwrap();
+    receive(&activity, store)
+}
+
+fn follow(store: &mut MemoryStore) {
+    send(
+        store,
+        json!({
+            "type": "Follow",
+            "id": "https://example.com/follows/1",
+            "actor": ALICE,
+            "object": BOB,
+        }),
+    )
+    .unwrap();
+}
+
+fn accept(store: &mut MemoryStore) {
+    send(
+        store,
+        json!({
+            "type": "Accept",
+            "actor": BOB,
+            "object": "https://example.com/follows/1",
+        }),
+    )
+    .unwrap();
+}
+
+fn assert_mismatch(result: Result<Vec<StateChange>, InboxError<Infallible>>, expected: &str) {
+    match result {
+        Err(InboxError::ActorMismatch {
+            expected: ref owner,
+            ref actor,
+        }) => {
+            assert_eq!(owner, expected);
+            assert_eq!(actor, MALLORY);
+        }
+        other => panic!("expected an actor mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn reject_of_pending_follow() {
+    let mut store = MemoryStore::new();
+    follow(&mut store);
+
+    let changes = send(
+        &mut store,
+        json!({
+            "type": "Reject",
+            "actor": BOB,
+            "object": "https://example.com/follows/1",
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(
+        changes,
+        vec![StateChange::FollowRejected {
+            follower: uri(ALICE),
+            followed: uri(BOB),
+        }]
+    );
+    assert_eq!(store.follow_state(&uri(ALICE), &uri(BOB)), None);
+}
+
+#[test]
+fn reject_of_accepted_follow() {
+    let mut store = MemoryStore::new();
+    follow(&mut store);
+    accept(&mut store);
+    assert_eq!(store.followers(&uri(BOB)), vec![&uri(ALICE)]);
+
+    let changes = send(
+        &mut store,
+        json!({
+            "type": "Reject",
+            "actor": BOB,
+            "object": {
+                "type": "Follow",
+                "actor": ALICE,
+                "object": BOB,
+            },
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(changes.len(), 1);
+    assert!(store.followers(&uri(BOB)).is_empty());
+}
+
+#[test]
+fn accept_by_wrong_actor() {
+    let mut store = MemoryStore::new();
+    follow(&mut store);
+
+    let result = send(
+        &mut store,
+        json!({
+            "type": "Accept",
+            "actor": MALLORY,
+            "object": "https://example.com/follows/1",
+        }),
+    );
+
+    assert_mismatch(result, BOB);
+    assert_eq!(
+        store.follow_state(&uri(ALICE), &uri(BOB)),
+        Some(FollowState::Pending)
+    );
+}
+
+#[test]
+fn repeated_follow_after_accept() {
+    let mut store = MemoryStore::new();
+    follow(&mut store);
+    accept(&mut store);
+
+    let changes = send(
+        &mut store,
+        json!({
+            "type": "Follow",
+            "id": "https://example.com/follows/2",
+            "actor": ALICE,
+            "object": BOB,
+        }),
+    )
+    .unwrap();
+
+    assert!(changes.is_empty());
+    assert_eq!(
+        store.follow_state(&uri(ALICE), &uri(BOB)),
+        Some(FollowState::Accepted)
+    );
+}
+
+#[test]
+fn undo_by_id_and_embedded() {
+    for kind in &["Like", "Announce", "Block"] {
+        for embedded in &[false, true] {
+            let mut store = MemoryStore::new();
+            let activity = json!({
+                "type": kind,
+                "id": "https://example.org/activities/1",
+                "actor": BOB,
+                "object": NOTE,
+            });
+            send(&mut store, activity.clone()).unwrap();
+
+            let recorded = match *kind {
+                "Like" => store.likes(&uri(NOTE)).len() == 1,
+                "Announce" => store.announces(&uri(NOTE)).len() == 1,
+                _ => store.is_blocked(&uri(BOB), &uri(NOTE)),
+            };
+            assert!(recorded, "{} was not recorded", kind);
+
+            let object = if *embedded {
+                activity
+            } else {
+                json!("https://example.org/activities/1")
+            };
+            let changes = send(
+                &mut store,
+                json!({
+                    "type": "Undo",
+                    "actor": BOB,
+                    "object": object,
+                }),
+            )
+            .unwrap();
+
+            let (actor, object) = (uri(BOB), uri(NOTE));
+            let expected = match *kind {
+                "Like" => StateChange::Unliked { actor, object },
+                "Announce" => StateChange::Unannounced { actor, object },
+                _ => StateChange::Unblocked { actor, object },
+            };
+            assert_eq!(changes, vec![expected]);
+            assert!(store.likes(&uri(NOTE)).is_empty());
+            assert!(store.announces(&uri(NOTE)).is_empty());
+            assert!(!store.is_blocked(&uri(BOB), &uri(NOTE)));
+        }
+    }
+}
+
+#[test]
+fn undo_by_other_actor() {
+    let mut store = MemoryStore::new();
+    send(
+        &mut store,
+        json!({
+            "type": "Like",
+            "id": "https://example.org/likes/1",
+            "actor": BOB,
+            "object": NOTE,
+        }),
+    )
+    .unwrap();
+
+    let result = send(
+        &mut store,
+        json!({
+            "type": "Undo",
+            "actor": MALLORY,
+            "object": "https://example.org/likes/1",
+        }),
+    );
+
+    assert_mismatch(result, BOB);
+    assert_eq!(store.likes(&uri(NOTE)), vec![&uri(BOB)]);
+}
+
+#[test]
+fn activity_without_actor() {
+    let mut store = MemoryStore::new();
+
+    let result = send(
+        &mut store,
+        json!({
+            "type": "Like",
+            "actor": [],
+            "object": NOTE,
+        }),
+    );
+
+    match result {
+        Err(InboxError::MissingActor) => (),
+        other => panic!("expected a missing actor, got {:?}", other),
+    }
+    assert!(store.likes(&uri(NOTE)).is_empty());
+}
+
+#[test]
+fn add_and_remove_by_owner() {
+    let mut store = MemoryStore::new();
+    store.set_target_owner(uri(FEATURED), uri(ALICE));
+
+    for kind in &["Add", "Remove"] {
+        send(
+            &mut store,
+            json!({
+                "type": kind,
+                "actor": ALICE,
+                "object": NOTE,
+                "target": FEATURED,
+            }),
+        )
+        .unwrap();
+
+        if *kind == "Add" {
+            assert_eq!(store.collection(&uri(FEATURED)), &[uri(NOTE)]);
+        }
+    }
+
+    assert!(store.collection(&uri(FEATURED)).is_empty());
+}
+
+#[test]
+fn add_by_other_actor() {
+    let mut store = MemoryStore::new();
+    store.set_target_owner(uri(FEATURED), uri(ALICE));
+
+    let result = send(
+        &mut store,
+        json!({
+            "type": "Add",
+            "actor": MALLORY,
+            "object": NOTE,
+            "target": FEATURED,
+        }),
+    );
+
+    assert_mismatch(result, ALICE);
+    assert!(store.collection(&uri(FEATURED)).is_empty());
+}
+
+#[test]
+fn add_to_unknown_target() {
+    let mut store = MemoryStore::new();
+
+    let changes = send(
+        &mut store,
+        json!({
+            "type": "Add",
+            "actor": MALLORY,
+            "object": NOTE,
+            "target": FEATURED,
+        }),
+    )
+    .unwrap();
+
+    assert!(changes.is_empty());
+    assert!(store.collection(&uri(FEATURED)).is_empty());
+}
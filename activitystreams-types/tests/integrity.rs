@@ -0,0 +1,179 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    object::Note,
+    security::{
+        integrity::{verify, verify_purpose, IntegrityError, ProofExt, Signer},
+        Multikey,
+    },
+};
+use serde_json::Value;
+
+const KEY_ID: &str = "https://example.com/users/alice#ed25519-key";
+
+fn signer() -> Signer {
+    Signer::from_multibase(KEY_ID, "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq").unwrap()
+}
+
+fn key(signer: &Signer) -> Multikey {
+    Multikey::from_ed25519(
+        KEY_ID.parse().unwrap(),
+        "https://example.com/users/alice".parse().unwrap(),
+        &signer.verifying_key(),
+    )
+}
+
+fn signed_note() -> Value {
+    let mut note = Note::default();
+    note.object_props
+        .set_content_string("Hello".to_owned())
+        .unwrap();
+    signer().sign_at(&note, "2023-02-24T23:36:38Z").unwrap()
+}
+
+#[test]
+fn other_purposes_are_rejected() {
+    let signer = signer();
+    let mut note = signed_note();
+    note["proof"]["proofPurpose"] = json!("authentication");
+
+    match verify(&note, &key(&signer)) {
+        Err(IntegrityError::PurposeMismatch(ref purpose)) => assert_eq!(purpose, "authentication"),
+        other => panic!("expected PurposeMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn expected_purpose_is_checked() {
+    let signer = signer();
+    let note = signed_note();
+
+    verify_purpose(&note, &key(&signer), "assertionMethod").unwrap();
+    match verify_purpose(&note, &key(&signer), "authentication") {
+        Err(IntegrityError::PurposeMismatch(ref purpose)) => assert_eq!(purpose, "assertionMethod"),
+        other => panic!("expected PurposeMismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn nonconforming_proofs_do_not_reject_the_object() {
+    let signer = signer();
+    let mut note = signed_note();
+    let proof = note["proof"].take();
+    note["proof"] = json!([
+        { "type": "DataIntegrityProof", "cryptosuite": "eddsa-rdfc-2022" },
+        proof,
+    ]);
+
+    let parsed: Note = serde_json::from_value(note.clone()).unwrap();
+    assert_eq!(parsed.object_props.proof.as_ref().map(|p| p.len()), Some(2));
+    assert!(parsed.object_props.proof_proof_vec().is_err());
+
+    verify(&note, &key(&signer)).unwrap();
+    parsed.verify_proof(&key(&signer)).unwrap();
+}
+
+// The `eddsa-jcs-2022` example of the Data Integrity EdDSA Cryptosuites specification,
+// <https://www.w3.org/TR/vc-di-eddsa/#representation-eddsa-jcs-2022>
+const SPEC_METHOD: &str = "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2#z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2";
+const PROOF_VALUE: &str =
+    "z2HnFSSPPBzR36zdDgK8PbEHeXbR56YF24jwMpt3R1eHXQzJDMWS93FCzpvJpwTWd3GAVFuUfjoJdcnTMuVor51aX";
+
+fn credential() -> Value {
+    json!({
+        "@context": [
+            "https://www.w3.org/ns/credentials/v2",
+            "https://www.w3.org/ns/credentials/examples/v2"
+        ],
+        "id": "urn:uuid:58172aac-d8ba-11ed-83dd-0b3aef56cc33",
+        "type": ["VerifiableCredential", "AlumniCredential"],
+        "name": "Alumni Credential",
+        "description": "A minimum viable example of an Alumni Credential.",
+        "issuer": "https://vc.example/issuers/5678",
+        "validFrom": "2023-01-01T00:00:00Z",
+        "credentialSubject": {
+            "id": "did:example:abcdefgh",
+            "alumniOf": "The School of Examples"
+        }
+    })
+}
+
+fn secured() -> Value {
+    let mut secured = credential();
+    secured["proof"] = json!({
+        "type": "DataIntegrityProof",
+        "cryptosuite": "eddsa-jcs-2022",
+        "created": "2023-02-24T23:36:38Z",
+        "verificationMethod": SPEC_METHOD,
+        "proofPurpose": "assertionMethod",
+        "@context": [
+            "https://www.w3.org/ns/credentials/v2",
+            "https://www.w3.org/ns/credentials/examples/v2"
+        ],
+        "proofValue": PROOF_VALUE
+    });
+    secured
+}
+
+fn spec_signer() -> Signer {
+    Signer::from_multibase(
+        SPEC_METHOD,
+        "z3u2en7t5LR2WtQH5PfFqMqwVHBeXouLzo6haApm8XHqvjxq",
+    )
+    .unwrap()
+}
+
+#[test]
+fn signs_like_the_specification() {
+    let signed = spec_signer()
+        .sign_at(&credential(), "2023-02-24T23:36:38Z")
+        .unwrap();
+
+    assert_eq!(signed["proof"]["proofValue"], PROOF_VALUE);
+    assert_eq!(signed, secured());
+}
+
+#[test]
+fn verifies_the_specification_example() {
+    let key = Multikey::from_ed25519(
+        SPEC_METHOD.parse().unwrap(),
+        "did:key:z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+            .parse()
+            .unwrap(),
+        &spec_signer().verifying_key(),
+    );
+    assert_eq!(
+        key.public_key_multibase,
+        "z6MkrJVnaZkeFzdQyMZu1cgjg7k1pZZ6pvBQ7XJPt4swbTQ2"
+    );
+
+    verify(&secured(), &key).unwrap();
+
+    let mut tampered = secured();
+    tampered["credentialSubject"]["alumniOf"] = json!("The School of Counterexamples");
+    match verify(&tampered, &key) {
+        Err(IntegrityError::BadProof) => (),
+        other => panic!("expected BadProof, got {:?}", other),
+    }
+}
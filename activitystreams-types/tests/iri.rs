@@ -0,0 +1,127 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_traits;
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_traits::ErrorKind;
+use activitystreams_types::{link::Mention, object::Note, primitives::XsdAnyUri};
+
+#[test]
+fn received_iris_serialize_as_written() {
+    let json = r#"{"type":"Note","id":"https://Example.com","url":"https://例え.jp"}"#;
+    let note: Note = serde_json::from_str(json).unwrap();
+
+    let id = note.object_props.id_xsdanyuri().unwrap();
+    assert_eq!(id.as_str(), "https://example.com/");
+    assert_eq!(id.original(), "https://Example.com");
+    assert_eq!(
+        note.object_props.id_string().unwrap(),
+        "https://Example.com"
+    );
+
+    assert_eq!(serde_json::to_string(&note).unwrap(), json);
+}
+
+#[test]
+fn iris_compare_normalised() {
+    let written: XsdAnyUri = "HTTPS://Example.com".parse().unwrap();
+    let normalised: XsdAnyUri = "https://example.com/".parse().unwrap();
+
+    assert_eq!(written, normalised);
+    assert_eq!(written.to_string(), "https://example.com/");
+    assert_eq!(
+        serde_json::to_value(&written).unwrap(),
+        json!("HTTPS://Example.com")
+    );
+}
+
+#[test]
+fn url_setters_reject_invalid_iris() {
+    let mut note = Note::default();
+
+    let error = note
+        .object_props
+        .set_url_string("not a url".to_owned())
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    assert_eq!(error.property(), Some("url"));
+    assert!(note.object_props.url.is_none());
+
+    let error = note
+        .object_props
+        .set_url_string_vec(vec![
+            "https://example.com/notes/1".to_owned(),
+            "/notes/1".to_owned(),
+        ])
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    assert!(note.object_props.url.is_none());
+
+    note.object_props
+        .set_url_string("https://example.com/notes/1".to_owned())
+        .unwrap();
+    assert_eq!(
+        note.object_props.url_string().unwrap(),
+        "https://example.com/notes/1"
+    );
+    assert_eq!(
+        note.object_props.url_xsdanyuri().unwrap().as_str(),
+        "https://example.com/notes/1"
+    );
+}
+
+#[test]
+fn received_invalid_urls_are_kept() {
+    let note: Note = serde_json::from_value(json!({
+        "type": "Note",
+        "url": ["not a url", "https://example.com/notes/1"],
+    }))
+    .unwrap();
+
+    assert_eq!(
+        note.object_props.url_string_vec().unwrap(),
+        vec!["not a url", "https://example.com/notes/1"]
+    );
+    assert!(note.object_props.url_xsdanyuri_vec().is_err());
+}
+
+#[test]
+fn href_setter_rejects_invalid_iris() {
+    let mut mention = Mention::default();
+
+    let error = mention
+        .link_props
+        .set_href_string("not a url".to_owned())
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    assert_eq!(error.property(), Some("href"));
+    assert!(mention.link_props.href.is_none());
+
+    mention
+        .link_props
+        .set_href_string("https://example.com/users/alice".to_owned())
+        .unwrap();
+    assert_eq!(
+        mention.link_props.href_string().unwrap(),
+        "https://example.com/users/alice"
+    );
+}
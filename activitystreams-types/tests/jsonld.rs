@@ -0,0 +1,114 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::jsonld::{Context, ContextEntry};
+use serde_json::Value;
+
+fn round_trip(context: &Value) -> Context {
+    let parsed: Context = serde_json::from_value(context.clone()).unwrap();
+    assert_eq!(&serde_json::to_value(&parsed).unwrap(), context);
+    parsed
+}
+
+fn bundled(document: &str) -> Value {
+    let document: Value = serde_json::from_str(document).unwrap();
+    document["@context"].clone()
+}
+
+#[test]
+fn bundled_contexts_round_trip() {
+    let activitystreams = round_trip(&bundled(include_str!(
+        "../src/jsonld/contexts/activitystreams.jsonld"
+    )));
+    assert_eq!(activitystreams.vocab(), Some("_:"));
+    assert_eq!(
+        activitystreams
+            .term("orderedItems")
+            .and_then(|term| term.container.as_ref())
+            .map(String::as_str),
+        Some("@list")
+    );
+
+    let security = round_trip(&bundled(include_str!(
+        "../src/jsonld/contexts/security-v1.jsonld"
+    )));
+    assert!(security.term("publicKeyPem").is_some());
+
+    round_trip(&bundled(include_str!(
+        "../src/jsonld/contexts/identity-v1.jsonld"
+    )));
+}
+
+#[test]
+fn explicit_nulls_round_trip() {
+    let context = round_trip(&json!([
+        "https://www.w3.org/ns/activitystreams",
+        {
+            "@language": "en",
+            "@vocab": "http://example.com/vocab#",
+        },
+        {
+            "@language": null,
+            "@vocab": null,
+            "summary": null,
+            "sensitive": { "@id": null },
+            "toot": "http://joinmastodon.org/ns#",
+            "plain": { "@id": "toot:plain", "@language": null },
+        }
+    ]));
+
+    assert_eq!(context.language(), None);
+    assert_eq!(context.vocab(), None);
+    assert!(context.term("summary").is_none());
+    assert_eq!(
+        context.term("plain").and_then(|term| term.language.clone()),
+        Some(None)
+    );
+
+    match context.entries[2] {
+        ContextEntry::Inline(ref inline) => {
+            assert_eq!(inline.terms.get("summary"), Some(&None));
+            assert_eq!(inline.language, Some(None));
+        }
+        ref entry => panic!("Expected an inline context, got {:?}", entry),
+    }
+}
+
+#[test]
+fn unrecognised_members_round_trip() {
+    let context = round_trip(&json!({
+        "@version": 1.1,
+        "@protected": true,
+        "followedBy": { "@reverse": "as:following", "@type": "@id" },
+        "tags": { "@id": "as:tag", "@container": ["@set", "@language"] },
+        "featured": { "@id": "toot:featured", "@type": "@id", "@protected": true },
+    }));
+
+    let tags = context.term("tags").unwrap();
+    assert_eq!(tags.container, None);
+    assert_eq!(tags.other["@container"], json!(["@set", "@language"]));
+
+    let followed_by = context.term("followedBy").unwrap();
+    assert_eq!(followed_by.id, None);
+    assert_eq!(followed_by.other["@reverse"], "as:following");
+}
@@ -0,0 +1,61 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{activity::AnyActivity, object::AnyObject, object::Note};
+
+#[test]
+fn any_types_keep_every_type() {
+    let note = json!({
+        "type": ["Note", "toot:Thing"],
+        "content": "Hello",
+    });
+
+    let object: AnyObject = serde_json::from_value(note.clone()).unwrap();
+    assert_eq!(object.kind(), Some("Note"));
+    assert_eq!(serde_json::to_value(&object).unwrap(), note);
+
+    let create = json!({
+        "type": ["litepub:Thing", "Create"],
+        "actor": "https://example.com/users/alice",
+        "object": "https://example.com/notes/1",
+    });
+
+    let activity: AnyActivity = serde_json::from_value(create.clone()).unwrap();
+    assert_eq!(activity.kind(), Some("Create"));
+    assert_eq!(serde_json::to_value(&activity).unwrap(), create);
+}
+
+#[test]
+fn typed_values_accept_lists_including_their_type() {
+    let note: Note = serde_json::from_value(json!({ "type": ["toot:Thing", "Note"] })).unwrap();
+    assert_eq!(
+        serde_json::to_value(&note).unwrap()["type"],
+        json!(["toot:Thing", "Note"])
+    );
+
+    let note: Note = serde_json::from_value(json!({ "type": "Note" })).unwrap();
+    assert_eq!(serde_json::to_value(&note).unwrap()["type"], json!("Note"));
+
+    assert!(serde_json::from_value::<Note>(json!({ "type": ["Article", "toot:Thing"] })).is_err());
+    assert!(serde_json::from_value::<Note>(json!({ "type": ["Note", 1] })).is_err());
+}
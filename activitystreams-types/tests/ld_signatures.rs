@@ -0,0 +1,69 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+extern crate serde_json;
+
+use activitystreams_types::security::{
+    ld_signatures::{verify, LinkedDataSignature},
+    PublicKey,
+};
+use serde_json::Value;
+
+const KEY: &str = include_str!("ld_signatures/mastodon-key.pem");
+const KEY_ID: &str = "https://mastodon.example/users/alice#main-key";
+
+fn activity() -> Value {
+    serde_json::from_str(include_str!("ld_signatures/mastodon-create.json")).unwrap()
+}
+
+fn public_key() -> PublicKey {
+    PublicKey::new(
+        KEY_ID.parse().unwrap(),
+        "https://mastodon.example/users/alice".parse().unwrap(),
+        KEY,
+    )
+}
+
+#[test]
+fn verifies_independently_signed_activity() {
+    let activity = activity();
+
+    let signature = LinkedDataSignature::from_document(&activity).unwrap();
+    assert_eq!(signature.kind, "RsaSignature2017");
+    assert_eq!(signature.creator, KEY_ID);
+
+    verify(&activity, &public_key()).unwrap();
+}
+
+#[test]
+fn rejects_tampered_activity() {
+    let mut activity = activity();
+    activity["object"]["contentMap"]["en"] = Value::from("<p>Goodbye, relays</p>");
+
+    assert!(verify(&activity, &public_key()).is_err());
+}
+
+#[test]
+fn rejects_tampered_options() {
+    let mut activity = activity();
+    activity["signature"]["created"] = Value::from("2024-06-01T12:00:02Z");
+
+    assert!(verify(&activity, &public_key()).is_err());
+}
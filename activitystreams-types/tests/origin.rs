@@ -0,0 +1,198 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    activity::{Add, Announce, Create},
+    origin::{OriginExt, OriginMismatch, Trust},
+    primitives::XsdAnyUri,
+};
+use serde_json::Value;
+
+fn key_owner() -> XsdAnyUri {
+    "https://good.example/users/alice".parse().unwrap()
+}
+
+fn refetch(id: &str) -> Trust {
+    Trust::Refetch(id.parse().unwrap())
+}
+
+fn trust(value: Value) -> Vec<(&'static str, Trust)> {
+    let create: Create = serde_json::from_value(value).unwrap();
+    create
+        .check_origins(&key_owner())
+        .unwrap()
+        .embedded
+        .into_iter()
+        .map(|object| (object.property, object.trust))
+        .collect()
+}
+
+#[test]
+fn anonymous_activities_are_not_authentic() {
+    let announce: Announce = serde_json::from_value(json!({
+        "type": "Announce",
+        "actor": [],
+        "object": "https://good.example/notes/1",
+    }))
+    .unwrap();
+
+    let check = announce.check_origins(&key_owner()).unwrap();
+    assert!(!check.is_authentic());
+    assert_eq!(check.mismatches, vec![OriginMismatch::Actor(None)]);
+
+    // An id on the right origin does not make up for the missing actor
+    let announce: Announce = serde_json::from_value(json!({
+        "type": "Announce",
+        "id": "https://good.example/activities/1",
+        "actor": [],
+        "object": "https://good.example/notes/1",
+    }))
+    .unwrap();
+    assert!(!announce.check_origins(&key_owner()).unwrap().is_authentic());
+}
+
+#[test]
+fn actors_without_ids_are_not_authentic() {
+    let announce: Announce = serde_json::from_value(json!({
+        "type": "Announce",
+        "actor": { "type": "Person", "name": "Alice" },
+        "object": "https://good.example/notes/1",
+    }))
+    .unwrap();
+
+    let check = announce.check_origins(&key_owner()).unwrap();
+    assert_eq!(check.mismatches, vec![OriginMismatch::Actor(None)]);
+}
+
+#[test]
+fn foreign_targets_are_refetched() {
+    let add: Add = serde_json::from_value(json!({
+        "type": "Add",
+        "actor": "https://good.example/users/alice",
+        "object": { "type": "Note", "id": "https://good.example/notes/1" },
+        "target": {
+            "type": "OrderedCollection",
+            "id": "https://evil.example/users/mallory/featured",
+        },
+    }))
+    .unwrap();
+
+    let check = add.check_origins(&key_owner()).unwrap();
+    assert!(check.is_authentic());
+
+    let trust: Vec<(&str, Trust)> = check
+        .embedded
+        .into_iter()
+        .map(|object| (object.property, object.trust))
+        .collect();
+    assert_eq!(
+        trust,
+        vec![
+            ("object", Trust::Trusted),
+            (
+                "target",
+                refetch("https://evil.example/users/mallory/featured")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn every_object_of_an_array_is_checked() {
+    let trust = trust(json!({
+        "type": "Create",
+        "actor": "https://good.example/users/alice",
+        "object": [
+            { "type": "Note", "id": "https://good.example/notes/1" },
+            { "type": "Note", "id": "https://evil.example/notes/2" },
+            { "type": "Note", "content": "No id" },
+            "https://evil.example/notes/3",
+        ],
+    }));
+
+    assert_eq!(
+        trust,
+        vec![
+            ("object", Trust::Trusted),
+            ("object", refetch("https://evil.example/notes/2")),
+            ("object", Trust::Trusted),
+        ]
+    );
+}
+
+#[test]
+fn foreign_attribution_is_refetched() {
+    let trust = trust(json!({
+        "type": "Create",
+        "actor": "https://good.example/users/alice",
+        "object": [
+            {
+                "type": "Note",
+                "id": "https://good.example/notes/1",
+                "attributedTo": "https://evil.example/users/mallory",
+            },
+            {
+                "type": "Note",
+                "id": "https://good.example/notes/2",
+                "attributedTo": [
+                    "https://good.example/users/alice",
+                    { "type": "Person", "id": "https://evil.example/users/mallory" },
+                ],
+            },
+            {
+                "type": "Note",
+                "attributedTo": "https://evil.example/users/mallory",
+            },
+        ],
+    }));
+
+    assert_eq!(
+        trust,
+        vec![
+            ("object", refetch("https://good.example/notes/1")),
+            ("object", refetch("https://good.example/notes/2")),
+            ("object", Trust::Discard),
+        ]
+    );
+}
+
+#[test]
+fn objects_of_unauthentic_activities_are_not_trusted() {
+    let trust = trust(json!({
+        "type": "Create",
+        "id": "https://evil.example/activities/1",
+        "actor": "https://good.example/users/alice",
+        "object": [
+            { "type": "Note", "id": "https://good.example/notes/1" },
+            { "type": "Note" },
+        ],
+    }));
+
+    assert_eq!(
+        trust,
+        vec![
+            ("object", refetch("https://good.example/notes/1")),
+            ("object", Trust::Discard),
+        ]
+    );
+}
@@ -0,0 +1,204 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+extern crate serde_json;
+
+use activitystreams_types::collection::{
+    paginate::{FetchSource, PaginateError, Paginator},
+    OrderedCollectionPage,
+};
+use serde_json::Value;
+
+fn numbers(count: usize) -> Vec<usize> {
+    (0..count).collect()
+}
+
+#[test]
+fn empty_collection_has_no_pages() {
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page={page}",
+        10,
+        numbers(0),
+    );
+
+    let collection = serde_json::to_value(paginator.collection().unwrap()).unwrap();
+    assert_eq!(collection["totalItems"], 0);
+    assert!(collection.get("items").is_none());
+    assert!(collection.get("first").is_none());
+    assert!(collection.get("last").is_none());
+
+    assert_eq!(paginator.page_count().unwrap(), 0);
+    assert!(paginator.page(1).unwrap().is_none());
+    assert_eq!(paginator.pages().count(), 0);
+}
+
+#[test]
+fn links_walk_every_item_in_order() {
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?offset={offset}",
+        3,
+        numbers(10),
+    );
+
+    let collection = serde_json::to_value(paginator.collection().unwrap()).unwrap();
+    let pages = paginator
+        .pages()
+        .map(|page| serde_json::to_value(page.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(pages.len(), 4);
+    assert_eq!(collection["first"], pages[0]["id"]);
+    assert_eq!(collection["last"], pages[3]["id"]);
+    assert_eq!(pages[3]["id"], "https://example.com/outbox?offset=9");
+
+    let mut items = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        assert_eq!(page["partOf"], collection["id"]);
+        assert_eq!(page["startIndex"], index * 3);
+
+        match pages.get(index + 1) {
+            Some(next) => {
+                assert_eq!(page["next"], next["id"]);
+                assert_eq!(next["prev"], page["id"]);
+            }
+            None => assert!(page.get("next").is_none()),
+        }
+
+        // Pages of an ordered collection hold their items in order
+        assert!(page.get("items").is_none());
+        items.extend(page["orderedItems"].as_array().unwrap().iter().cloned());
+    }
+    assert!(pages[0].get("prev").is_none());
+    assert_eq!(items, (0..10).map(Value::from).collect::<Vec<_>>());
+}
+
+#[test]
+fn template_needs_a_placeholder() {
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page=1",
+        2,
+        numbers(3),
+    );
+
+    match paginator.collection() {
+        Err(PaginateError::InvalidTemplate(_)) => (),
+        other => panic!("Expected an invalid template, got {:?}", other.map(|_| ())),
+    }
+
+    let cursor = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page={page}",
+        2,
+        numbers(3),
+    )
+    .cursor(|n: &usize| n.to_string());
+
+    match cursor.page(1) {
+        Err(PaginateError::InvalidTemplate(_)) => (),
+        other => panic!("Expected an invalid template, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn source_errors_are_surfaced() {
+    let source = FetchSource::new(5, |offset, _| -> Result<Vec<usize>, String> {
+        Err(format!("Can't read from {}", offset))
+    });
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page={page}",
+        2,
+        source,
+    );
+
+    match paginator.page(2) {
+        Err(PaginateError::Source(e)) => assert_eq!(e, "Can't read from 2"),
+        other => panic!("Expected a source error, got {:?}", other.map(|_| ())),
+    }
+    assert!(paginator.pages().next().unwrap().is_err());
+}
+
+#[test]
+fn page_numbers_need_aligned_offsets() {
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page={page}",
+        3,
+        numbers(10),
+    );
+
+    match paginator.page_at(4) {
+        Err(PaginateError::UnalignedOffset(4)) => (),
+        other => panic!("Expected an unaligned offset, got {:?}", other.map(|_| ())),
+    }
+    assert!(paginator.page_at(3).unwrap().is_some());
+
+    let offsets = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?offset={offset}",
+        3,
+        numbers(10),
+    );
+
+    let page = serde_json::to_value(offsets.page_at(4).unwrap().unwrap()).unwrap();
+    assert_eq!(page["id"], "https://example.com/outbox?offset=4");
+    assert_eq!(page["prev"], "https://example.com/outbox?offset=1");
+    assert_eq!(page["next"], "https://example.com/outbox?offset=7");
+}
+
+#[test]
+fn cursors_name_their_pages() {
+    let paginator = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?from={cursor}",
+        3,
+        numbers(10),
+    )
+    .cursor(|n: &usize| format!("n{}", n));
+
+    let second = serde_json::to_value(paginator.page(2).unwrap().unwrap()).unwrap();
+    let found = serde_json::to_value(paginator.page_for_cursor("n3").unwrap().unwrap()).unwrap();
+    assert_eq!(found, second);
+    assert_eq!(found["id"], "https://example.com/outbox?from=n3");
+
+    // Items added in front of a page leave its cursor, and the items it starts with, unchanged
+    let page = serde_json::to_value(paginator.page_for_cursor("n5").unwrap().unwrap()).unwrap();
+    assert_eq!(page["orderedItems"], serde_json::json!([5, 6, 7]));
+
+    let read: OrderedCollectionPage = serde_json::from_value(page.clone()).unwrap();
+    assert_eq!(
+        read.ordered_collection_props.ordered_items.unwrap().len(),
+        3
+    );
+    assert_eq!(page["prev"], "https://example.com/outbox?from=n2");
+
+    assert!(paginator.page_for_cursor("n10").unwrap().is_none());
+
+    let offsets = Paginator::new(
+        "https://example.com/outbox".parse().unwrap(),
+        "https://example.com/outbox?page={page}",
+        3,
+        numbers(10),
+    );
+    assert!(offsets.page_for_cursor("n3").unwrap().is_none());
+}
@@ -0,0 +1,413 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    actor::Person,
+    collection::{OrderedCollection, UnorderedCollectionPage},
+    primitives::XsdAnyUri,
+    resolve::{
+        AsyncResolver, Dereferencer, PagingExt, ResolveError, ResolveExt, ResolveFuture, Resolver,
+        Stop,
+    },
+};
+use serde_json::Value;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll, Waker},
+};
+
+fn uri(id: &str) -> XsdAnyUri {
+    id.parse().unwrap()
+}
+
+/// A chain of links, each pointing to the next, ending in a Person
+fn links(length: usize) -> HashMap<XsdAnyUri, Value> {
+    let mut objects = HashMap::new();
+
+    for n in 0..length {
+        objects.insert(
+            uri(&format!("https://example.com/links/{}", n)),
+            json!({ "type": "Link", "href": format!("https://example.com/links/{}", n + 1) }),
+        );
+    }
+    objects.insert(
+        uri(&format!("https://example.com/links/{}", length)),
+        json!({ "type": "Person", "id": "https://example.com/users/alice" }),
+    );
+
+    objects
+}
+
+/// A resolver that counts how often it is asked for a value
+struct Counting {
+    objects: HashMap<XsdAnyUri, Value>,
+    calls: Cell<usize>,
+}
+
+impl Resolver for Counting {
+    type Error = Infallible;
+
+    fn resolve(&self, id: &XsdAnyUri) -> Result<Option<Value>, Self::Error> {
+        self.calls.set(self.calls.get() + 1);
+        Ok(self.objects.get(id).cloned())
+    }
+}
+
+/// A future that is pending the first time it is polled
+struct Delayed(Option<Option<Value>>, bool);
+
+impl Future for Delayed {
+    type Output = Result<Option<Value>, Infallible>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if self.1 {
+            Poll::Ready(Ok(self.0.take().unwrap()))
+        } else {
+            self.1 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// An asynchronous resolver whose values are not ready straight away
+struct Slow {
+    objects: HashMap<XsdAnyUri, Value>,
+    calls: AtomicUsize,
+}
+
+impl AsyncResolver for Slow {
+    type Error = Infallible;
+
+    fn resolve(&self, id: XsdAnyUri) -> ResolveFuture<'_, Self::Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Box::pin(Delayed(Some(self.objects.get(&id).cloned()), false))
+    }
+}
+
+fn block_on<F>(future: F) -> F::Output
+where
+    F: Future + Unpin,
+{
+    let mut future = future;
+    let mut cx = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = Pin::new(&mut future).poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn follows_links_up_to_the_depth_limit() {
+    let dereferencer = Dereferencer::new(links(3)).max_depth(4);
+    let alice = dereferencer
+        .dereference_as::<Person>(&json!("https://example.com/links/0"))
+        .unwrap();
+    assert_eq!(
+        alice.object_props.id_xsdanyuri().unwrap().as_str(),
+        "https://example.com/users/alice"
+    );
+
+    let dereferencer = Dereferencer::new(links(4)).max_depth(4);
+    match dereferencer.dereference(&json!("https://example.com/links/0")) {
+        Err(ResolveError::TooDeep(id)) => assert_eq!(id.as_str(), "https://example.com/links/4"),
+        other => panic!("Expected the depth limit, got {:?}", other),
+    }
+}
+
+#[test]
+fn detects_cycles_and_missing_values() {
+    let mut objects = HashMap::new();
+    objects.insert(
+        uri("https://example.com/links/a"),
+        json!({ "href": "https://example.com/links/b" }),
+    );
+    objects.insert(
+        uri("https://example.com/links/b"),
+        json!({ "type": "Link", "href": "https://example.com/links/a" }),
+    );
+    let dereferencer = Dereferencer::new(objects);
+
+    match dereferencer.dereference(&json!("https://example.com/links/a")) {
+        Err(ResolveError::Cycle(id)) => assert_eq!(id.as_str(), "https://example.com/links/a"),
+        other => panic!("Expected a cycle, got {:?}", other),
+    }
+
+    match dereferencer.dereference(&json!("https://example.com/missing")) {
+        Err(ResolveError::NotFound(id)) => assert_eq!(id.as_str(), "https://example.com/missing"),
+        other => panic!("Expected a missing value, got {:?}", other),
+    }
+
+    match dereferencer.dereference(&json!("not an iri")) {
+        Err(ResolveError::InvalidId(id)) => assert_eq!(id, "not an iri"),
+        other => panic!("Expected an invalid id, got {:?}", other),
+    }
+}
+
+#[test]
+fn caches_the_least_recently_used_values() {
+    let resolver = Counting {
+        objects: links(2),
+        calls: Cell::new(0),
+    };
+    let dereferencer = Dereferencer::new(resolver).cache_capacity(2);
+
+    // Fetches links 0, 1 and 2, and only keeps the last two
+    dereferencer
+        .dereference(&json!("https://example.com/links/0"))
+        .unwrap();
+    assert_eq!(dereferencer.resolver().calls.get(), 3);
+    assert_eq!(dereferencer.cache_len(), 2);
+
+    dereferencer
+        .dereference(&json!("https://example.com/links/1"))
+        .unwrap();
+    assert_eq!(dereferencer.resolver().calls.get(), 3);
+
+    // Link 0 was forgotten, and fetching it again pushes out link 1, then link 2, in turn
+    dereferencer
+        .dereference(&json!("https://example.com/links/0"))
+        .unwrap();
+    assert_eq!(dereferencer.resolver().calls.get(), 6);
+
+    dereferencer.clear_cache();
+    assert_eq!(dereferencer.cache_len(), 0);
+}
+
+#[test]
+fn resolves_asynchronously() {
+    let mut objects = links(1);
+    objects.insert(
+        uri("https://example.org/users/bob"),
+        json!({ "type": "Person", "id": "https://example.org/users/bob" }),
+    );
+    let resolver = Slow {
+        objects,
+        calls: AtomicUsize::new(0),
+    };
+    let dereferencer = Dereferencer::new(resolver);
+
+    let note = json!({
+        "type": "Note",
+        "attributedTo": [
+            "https://example.com/links/0",
+            { "type": "Person", "id": "https://example.net/users/carol" },
+            "https://example.org/users/bob",
+        ],
+    });
+
+    let people: Vec<Person> =
+        block_on(note.resolve_property_vec_async("attributedTo", &dereferencer)).unwrap();
+    let ids: Vec<&str> = people
+        .iter()
+        .map(|person| person.object_props.id_xsdanyuri().unwrap().as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "https://example.com/users/alice",
+            "https://example.net/users/carol",
+            "https://example.org/users/bob",
+        ]
+    );
+    assert_eq!(dereferencer.resolver().calls.load(Ordering::SeqCst), 3);
+
+    // Everything is cached now
+    let first = block_on(note.resolve_attributed_to_object_async::<Person>(&dereferencer)).unwrap();
+    assert!(first.is_some());
+    assert_eq!(dereferencer.resolver().calls.load(Ordering::SeqCst), 3);
+
+    match block_on(dereferencer.dereference_async(&json!("https://example.com/missing"))) {
+        Err(ResolveError::NotFound(_)) => (),
+        other => panic!("Expected a missing value, got {:?}", other),
+    }
+}
+
+#[test]
+fn pages_asynchronously() {
+    let mut objects = HashMap::new();
+    objects.insert(
+        uri("https://example.com/followers?page=2"),
+        json!({
+            "type": "CollectionPage",
+            "id": "https://example.com/followers?page=2",
+            "items": ["https://example.net/users/carol"],
+            "next": "https://example.com/followers?page=3",
+        }),
+    );
+    let resolver = Slow {
+        objects,
+        calls: AtomicUsize::new(0),
+    };
+    let dereferencer = Dereferencer::new(resolver);
+
+    // The first page is embedded, and the third one is missing
+    let followers: OrderedCollection = serde_json::from_value(json!({
+        "type": "OrderedCollection",
+        "id": "https://example.com/followers",
+        "items": [],
+        "first": {
+            "type": "CollectionPage",
+            "items": ["https://example.com/users/alice", "https://example.org/users/bob"],
+            "next": "https://example.com/followers?page=2",
+        },
+    }))
+    .unwrap();
+
+    let mut items = followers.items_async(&dereferencer);
+    let mut ids = Vec::new();
+    let error = loop {
+        match block_on(items.next()) {
+            Some(Ok(item)) => ids.push(item.as_str().unwrap().to_owned()),
+            Some(Err(e)) => break e,
+            None => panic!("Expected the missing page to fail"),
+        }
+    };
+
+    assert_eq!(
+        ids,
+        vec![
+            "https://example.com/users/alice",
+            "https://example.org/users/bob",
+            "https://example.net/users/carol",
+        ]
+    );
+    match error {
+        ResolveError::NotFound(id) => {
+            assert_eq!(id.as_str(), "https://example.com/followers?page=3")
+        }
+        other => panic!("Expected a missing page, got {:?}", other),
+    }
+    assert!(block_on(items.next()).is_none());
+    assert_eq!(items.stop(), Some(&Stop::Error));
+    assert_eq!(items.pages(), 3);
+}
+
+#[test]
+fn pages_from_a_page() {
+    let mut objects = HashMap::new();
+    objects.insert(
+        uri("https://example.com/likes?page=1"),
+        json!({
+            "type": "CollectionPage",
+            "id": "https://example.com/likes?page=1",
+            "items": ["https://example.com/likes/1", "https://example.com/likes/2"],
+        }),
+    );
+    let dereferencer = Dereferencer::new(objects);
+
+    let page: UnorderedCollectionPage = serde_json::from_value(json!({
+        "type": "CollectionPage",
+        "id": "https://example.com/likes?page=2",
+        "items": ["https://example.com/likes/3", "https://example.com/likes/4"],
+        "prev": "https://example.com/likes?page=1",
+    }))
+    .unwrap();
+
+    let forward: Vec<Value> = page.items(&dereferencer).map(Result::unwrap).collect();
+    assert_eq!(forward.len(), 2);
+
+    let mut backward = page.items(&dereferencer).backward();
+    let ids: Vec<Value> = backward.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        ids,
+        vec![
+            json!("https://example.com/likes/4"),
+            json!("https://example.com/likes/3"),
+            json!("https://example.com/likes/2"),
+            json!("https://example.com/likes/1"),
+        ]
+    );
+    assert_eq!(backward.stop(), Some(&Stop::End));
+}
+
+#[test]
+fn pages_from_a_page_linking_to_the_first_page() {
+    let mut objects = HashMap::new();
+    objects.insert(
+        uri("https://example.com/outbox?page=1"),
+        json!({
+            "type": "OrderedCollectionPage",
+            "id": "https://example.com/outbox?page=1",
+            "partOf": "https://example.com/outbox",
+            "orderedItems": ["https://example.com/notes/a", "https://example.com/notes/b"],
+            "next": "https://example.com/outbox?page=2",
+        }),
+    );
+    objects.insert(
+        uri("https://example.com/outbox?page=3"),
+        json!({
+            "type": "OrderedCollectionPage",
+            "id": "https://example.com/outbox?page=3",
+            "partOf": "https://example.com/outbox",
+            "orderedItems": ["https://example.com/notes/e"],
+            "prev": "https://example.com/outbox?page=2",
+        }),
+    );
+    let dereferencer = Dereferencer::new(objects);
+
+    // Some servers repeat the links of the collection in each page
+    let page: UnorderedCollectionPage = serde_json::from_value(json!({
+        "type": "CollectionPage",
+        "id": "https://example.com/outbox?page=2",
+        "partOf": "https://example.com/outbox",
+        "first": "https://example.com/outbox?page=1",
+        "last": "https://example.com/outbox?page=3",
+        "items": ["https://example.com/notes/c", "https://example.com/notes/d"],
+        "prev": "https://example.com/outbox?page=1",
+        "next": "https://example.com/outbox?page=3",
+    }))
+    .unwrap();
+
+    let mut forward = page.items(&dereferencer);
+    let ids: Vec<Value> = forward.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        ids,
+        vec![
+            json!("https://example.com/notes/c"),
+            json!("https://example.com/notes/d"),
+            json!("https://example.com/notes/e"),
+        ]
+    );
+    assert_eq!(forward.stop(), Some(&Stop::End));
+
+    let mut backward = page.items(&dereferencer).backward();
+    let ids: Vec<Value> = backward.by_ref().map(Result::unwrap).collect();
+    assert_eq!(
+        ids,
+        vec![
+            json!("https://example.com/notes/d"),
+            json!("https://example.com/notes/c"),
+            json!("https://example.com/notes/b"),
+            json!("https://example.com/notes/a"),
+        ]
+    );
+    assert_eq!(backward.stop(), Some(&Stop::End));
+}
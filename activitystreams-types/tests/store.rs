@@ -0,0 +1,222 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    activity::AnyActivity,
+    collection::AnyCollection,
+    object::AnyObject,
+    primitives::XsdAnyUri,
+    store::{ActivityStore, MemoryActivityStore, StoreError, StoredObject},
+};
+use std::fmt::Debug;
+
+fn uri(id: &str) -> XsdAnyUri {
+    id.parse().unwrap()
+}
+
+fn ids(objects: Vec<StoredObject>) -> Vec<String> {
+    objects
+        .into_iter()
+        .map(|object| {
+            serde_json::to_value(&object).unwrap()["id"]
+                .as_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect()
+}
+
+/// Exercise the behaviour every backend must share
+fn exercise<S>(mut store: S)
+where
+    S: ActivityStore,
+    S::Error: Debug,
+{
+    let values = vec![
+        json!({
+            "type": "Note",
+            "id": "https://example.com/notes/1",
+            "attributedTo": "https://example.com/users/alice",
+        }),
+        json!({
+            "type": "Note",
+            "id": "https://example.org/notes/2",
+            "attributedTo": { "type": "Person", "id": "https://example.org/users/bob" },
+            "inReplyTo": "https://example.com/notes/1",
+        }),
+        json!({
+            "type": "Like",
+            "id": "https://example.org/likes/1",
+            "actor": "https://example.org/users/bob",
+            "object": "https://example.com/notes/1",
+        }),
+        json!({
+            "type": "Note",
+            "id": "https://example.net/notes/3",
+            "attributedTo": "https://example.net/users/carol",
+            "inReplyTo": ["https://example.com/notes/1", "https://example.org/notes/2"],
+        }),
+        json!({
+            "type": "OrderedCollection",
+            "id": "https://example.com/users/alice/outbox",
+            "items": [],
+            "first": "https://example.com/users/alice/outbox?page=1",
+        }),
+        json!({
+            "type": "OrderedCollectionPage",
+            "id": "https://example.com/users/alice/outbox?page=1",
+            "items": ["https://example.org/likes/1", "https://example.com/missing"],
+            "next": "https://example.com/users/alice/outbox?page=2",
+        }),
+        json!({
+            "type": "OrderedCollectionPage",
+            "id": "https://example.com/users/alice/outbox?page=2",
+            "items": [{ "type": "Note", "id": "https://example.com/notes/4" }],
+            "next": "https://example.com/users/alice/outbox?page=1",
+        }),
+    ];
+
+    for value in &values {
+        store.put(value).unwrap();
+    }
+
+    match store.put(&json!({ "type": "Note" })) {
+        Err(StoreError::MissingId) => (),
+        other => panic!("Expected a missing id, got {:?}", other),
+    }
+
+    assert!(store
+        .get(&uri("https://example.com/notes/9"))
+        .unwrap()
+        .is_none());
+
+    match store.get(&uri("https://example.org/likes/1")).unwrap() {
+        Some(StoredObject::Activity(AnyActivity::Like(_))) => (),
+        other => panic!("Expected a Like, got {:?}", other),
+    }
+
+    match store
+        .get(&uri("https://example.com/users/alice/outbox"))
+        .unwrap()
+    {
+        Some(StoredObject::Collection(AnyCollection::OrderedCollection(_))) => (),
+        other => panic!("Expected an OrderedCollection, got {:?}", other),
+    }
+
+    let note = uri("https://example.com/notes/1");
+    assert_eq!(
+        ids(store.replies(&note).unwrap()),
+        vec!["https://example.org/notes/2", "https://example.net/notes/3"]
+    );
+    assert_eq!(
+        ids(store.activities_on(&note).unwrap()),
+        vec!["https://example.org/likes/1"]
+    );
+    assert_eq!(
+        ids(store
+            .attributed_to(&uri("https://example.org/users/bob"))
+            .unwrap()),
+        vec!["https://example.org/notes/2"]
+    );
+
+    // Pages are followed until they loop, and items that are not stored are skipped
+    assert_eq!(
+        ids(store
+            .collection_items(&uri("https://example.com/users/alice/outbox"))
+            .unwrap()),
+        vec!["https://example.org/likes/1", "https://example.com/notes/4"]
+    );
+
+    // Replacing a value updates its references, and keeps its place in the order
+    store
+        .put(&json!({
+            "type": "Note",
+            "id": "https://example.org/notes/2",
+            "inReplyTo": "https://example.com/notes/1",
+        }))
+        .unwrap();
+
+    assert_eq!(
+        ids(store.replies(&note).unwrap()),
+        vec!["https://example.org/notes/2", "https://example.net/notes/3"]
+    );
+    assert!(store
+        .attributed_to(&uri("https://example.org/users/bob"))
+        .unwrap()
+        .is_empty());
+
+    // References match ids however either of them is written
+    store
+        .put(&json!({
+            "type": "Note",
+            "id": "https://example.org/notes/5",
+            "inReplyTo": "https://example.com",
+        }))
+        .unwrap();
+    store
+        .put(&json!({
+            "type": "Announce",
+            "id": "https://example.org/announces/1",
+            "actor": "https://example.org/users/bob",
+            "object": "HTTPS://EXAMPLE.COM:443/notes/1",
+        }))
+        .unwrap();
+
+    assert_eq!(
+        ids(store.replies(&uri("https://example.com/")).unwrap()),
+        vec!["https://example.org/notes/5"]
+    );
+    assert_eq!(
+        ids(store.activities_on(&note).unwrap()),
+        vec![
+            "https://example.org/likes/1",
+            "https://example.org/announces/1"
+        ]
+    );
+
+    // Values with several types are read as the first family that recognises one of them
+    store
+        .put(&json!({
+            "type": ["toot:Thing", "Note"],
+            "id": "https://example.org/notes/6",
+        }))
+        .unwrap();
+
+    match store.get(&uri("https://example.org/notes/6")).unwrap() {
+        Some(StoredObject::Object(AnyObject::Note(_))) => (),
+        other => panic!("Expected a Note, got {:?}", other),
+    }
+}
+
+#[test]
+fn memory() {
+    exercise(MemoryActivityStore::new());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite() {
+    use activitystreams_types::store::SqliteActivityStore;
+
+    exercise(SqliteActivityStore::open_in_memory().unwrap());
+}
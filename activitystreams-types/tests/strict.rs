@@ -0,0 +1,358 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    activity::{
+        properties::{
+            ActivityProperties, ActorAndObject, ActorAndObjectOptOrigin, ActorAndObjectOptOthers,
+            ActorAndObjectOptTarget, ActorAndOrigin, ActorObjectAndTarget, ActorOptOriginAndTarget,
+            QuestionProperties,
+        },
+        AnyActivity, Create,
+    },
+    actor::{
+        properties::{ActorProperties, Endpoints},
+        Person,
+    },
+    collection::properties::{
+        CollectionPageProperties, CollectionProperties, OrderedCollectionPageProperties,
+        OrderedCollectionProperties,
+    },
+    link::{properties::LinkProperties, Mention},
+    object::{
+        properties::{
+            ObjectProperties, PlaceProperties, ProfileProperties, RelationshipProperties,
+            TombstoneProperties,
+        },
+        AnyObject, Note, Place,
+    },
+    security::{properties::SecurityProperties, Multikey, Proof, PublicKey},
+    strict::{self, StrictError, StrictErrorKind},
+    WithUnknown,
+};
+use serde_json::Value;
+
+fn note_error(value: Value) -> StrictError {
+    strict::from_value::<Note>(&value).unwrap_err()
+}
+
+fn invalid(expected: &'static str) -> StrictErrorKind {
+    StrictErrorKind::InvalidValue { expected }
+}
+
+#[test]
+fn properties_are_read_from_the_derive() {
+    let in_reply_to = ObjectProperties::PROPERTIES
+        .iter()
+        .find(|property| property.name == "inReplyTo")
+        .unwrap();
+    assert_eq!(in_reply_to.range, "`Object` | `Link`");
+    assert!(!in_reply_to.functional);
+
+    let hreflang = LinkProperties::PROPERTIES
+        .iter()
+        .find(|property| property.name == "hreflang")
+        .unwrap();
+    assert_eq!(
+        hreflang.range,
+        "[[BCP47](https://tools.ietf.org/html/bcp47)] Language Tag"
+    );
+    assert!(hreflang.functional);
+
+    let context = ObjectProperties::PROPERTIES
+        .iter()
+        .find(|property| property.name == "@context");
+    assert!(context.is_some());
+}
+
+#[test]
+fn every_property_documents_its_range() {
+    let tables = [
+        ObjectProperties::PROPERTIES,
+        PlaceProperties::PROPERTIES,
+        ProfileProperties::PROPERTIES,
+        RelationshipProperties::PROPERTIES,
+        TombstoneProperties::PROPERTIES,
+        LinkProperties::PROPERTIES,
+        ActorProperties::PROPERTIES,
+        Endpoints::PROPERTIES,
+        CollectionProperties::PROPERTIES,
+        CollectionPageProperties::PROPERTIES,
+        OrderedCollectionProperties::PROPERTIES,
+        OrderedCollectionPageProperties::PROPERTIES,
+        ActivityProperties::PROPERTIES,
+        ActorOptOriginAndTarget::PROPERTIES,
+        ActorAndObject::PROPERTIES,
+        ActorObjectAndTarget::PROPERTIES,
+        ActorAndObjectOptTarget::PROPERTIES,
+        ActorAndObjectOptOrigin::PROPERTIES,
+        ActorAndObjectOptOthers::PROPERTIES,
+        ActorAndOrigin::PROPERTIES,
+        QuestionProperties::PROPERTIES,
+        SecurityProperties::PROPERTIES,
+        PublicKey::PROPERTIES,
+        Multikey::PROPERTIES,
+        Proof::PROPERTIES,
+    ];
+
+    for property in tables.iter().flat_map(|table| table.iter()) {
+        assert!(!property.range.is_empty(), "{}", property.name);
+    }
+}
+
+#[test]
+fn values_are_checked_against_their_range() {
+    let cases = vec![
+        (
+            json!({ "type": "Note", "published": "yesterday" }),
+            "/published",
+            "`xsd:dateTime`",
+        ),
+        (
+            json!({ "type": "Note", "startTime": "noon" }),
+            "/startTime",
+            "`xsd:dateTime`",
+        ),
+        (
+            json!({ "type": "Note", "duration": "5 minutes" }),
+            "/duration",
+            "`xsd:duration`",
+        ),
+        (
+            json!({ "type": "Note", "mediaType": "html" }),
+            "/mediaType",
+            "`Mime Media Type`",
+        ),
+        (
+            json!({ "type": "Note", "inReplyTo": 5 }),
+            "/inReplyTo",
+            "`Object` | `Link`",
+        ),
+        (
+            json!({ "type": "Note", "url": ["https://example.com", 5] }),
+            "/url/1",
+            "`xsd:anyUri` | `Link`",
+        ),
+        (
+            json!({ "type": "Note", "replies": "replies" }),
+            "/replies",
+            "`Collection`",
+        ),
+        (
+            json!({ "type": "Note", "contentMap": { "en": 5 } }),
+            "/contentMap",
+            "`rdf:langString`",
+        ),
+    ];
+
+    for (value, path, expected) in cases {
+        let error = note_error(value);
+        assert_eq!(error.path, path);
+        assert_eq!(error.kind, invalid(expected));
+    }
+}
+
+#[test]
+fn functional_properties_hold_one_value() {
+    let error = note_error(json!({
+        "type": "Note",
+        "published": ["2018-06-13T10:00:00Z", "2018-06-14T10:00:00Z"],
+    }));
+    assert_eq!(error.path, "/published");
+    assert_eq!(error.kind, invalid("`xsd:dateTime`"));
+}
+
+#[test]
+fn ranges_with_literals_and_prose() {
+    let place: Place = strict::from_value(&json!({
+        "type": "Place",
+        "units": "km",
+        "radius": 5,
+    }))
+    .unwrap();
+    assert_eq!(place.place.units_string().unwrap(), "km");
+
+    let error =
+        strict::from_value::<Place>(&json!({ "type": "Place", "units": "yards" })).unwrap_err();
+    assert_eq!(error.path, "/units");
+
+    strict::from_value::<Mention>(&json!({
+        "type": "Mention",
+        "href": "https://example.com/users/alice",
+        "hreflang": "en-US",
+        "rel": ["me", "canonical"],
+    }))
+    .unwrap();
+
+    let error = strict::from_value::<Mention>(&json!({
+        "type": "Mention",
+        "hreflang": "not a tag",
+    }))
+    .unwrap_err();
+    assert_eq!(error.path, "/hreflang");
+}
+
+#[test]
+fn embedded_objects_are_checked() {
+    let error = note_error(json!({
+        "type": "Note",
+        "tag": [
+            { "type": "Mention", "href": "https://example.com/users/alice" },
+            { "type": "Hashtag", "nmae": "#rust" },
+        ],
+    }));
+    assert_eq!(error.path, "/tag/1/nmae");
+    assert_eq!(error.kind, StrictErrorKind::UnknownProperty);
+
+    let error = strict::from_value::<Person>(&json!({
+        "type": "Person",
+        "publicKey": {
+            "id": "https://example.com/users/alice#main-key",
+            "owner": "alice",
+            "publicKeyPem": "-----BEGIN PUBLIC KEY-----",
+        },
+    }))
+    .unwrap_err();
+    assert_eq!(error.path, "/publicKey/owner");
+    assert_eq!(error.kind, invalid("`xsd:anyUri`"));
+}
+
+#[test]
+fn unsupported_and_missing_properties() {
+    let error = note_error(json!({
+        "type": "Note",
+        "actor": "https://example.com/users/alice",
+    }));
+    assert_eq!(error.path, "/actor");
+    assert_eq!(error.kind, StrictErrorKind::UnsupportedProperty);
+
+    let error = strict::from_value::<Create>(&json!({
+        "type": "Create",
+        "object": "https://example.com/notes/1",
+    }))
+    .unwrap_err();
+    assert_eq!(error.path, "/actor");
+    assert_eq!(error.kind, StrictErrorKind::MissingProperty);
+    assert_eq!(error.to_string(), "Missing required property at /actor");
+
+    let error = note_error(json!({ "content": "Hello" }));
+    assert_eq!(error.path, "/type");
+    assert_eq!(error.kind, StrictErrorKind::MissingProperty);
+}
+
+#[test]
+fn types_are_checked() {
+    let error = strict::from_str::<Note>(r#"{"type":"Person"}"#).unwrap_err();
+    assert_eq!(error.path, "/type");
+    assert_eq!(error.kind, invalid("`Note`"));
+    assert_eq!(error.to_string(), "Invalid value at /type, expected `Note`");
+
+    let error = note_error(json!({ "type": ["Article", "toot:Thing"] }));
+    assert_eq!(error.path, "/type");
+
+    strict::from_value::<Note>(&json!({ "type": ["toot:Thing", "Note"] })).unwrap();
+    strict::from_value::<AnyObject>(&json!({ "type": "toot:Emoji" })).unwrap();
+
+    // Any types check the properties of the type they pick
+    let error = strict::from_value::<AnyActivity>(&json!({
+        "type": "Create",
+        "object": "https://example.com/notes/1",
+    }))
+    .unwrap_err();
+    assert_eq!(error.path, "/actor");
+    assert_eq!(error.kind, StrictErrorKind::MissingProperty);
+}
+
+#[test]
+fn context_terms_are_accepted() {
+    let document = json!({
+        "@context": [
+            "https://www.w3.org/ns/activitystreams",
+            {
+                "toot": "http://joinmastodon.org/ns#",
+                "sensitive": "as:sensitive",
+                "Emoji": "toot:Emoji",
+            },
+        ],
+        "type": "Note",
+        "sensitive": true,
+        "tag": [{
+            "type": "Emoji",
+            "name": ":blobcat:",
+            "toot:featured": true,
+            "http://example.com/ns#weight": 3,
+            "icon": { "type": "Image", "url": "https://example.com/blobcat.png" },
+        }],
+        "attachment": [{
+            "@context": { "blurhash": "toot:blurhash" },
+            "type": "Document",
+            "blurhash": "UBL_:rOpGG-oBUNG,qRj2so|=eE1w^n4S5NH",
+            "sensitive": false,
+        }],
+    });
+
+    let note: WithUnknown<Note> = strict::from_value(&document).unwrap();
+    assert_eq!(note.unknown("sensitive"), Some(&json!(true)));
+
+    // Without WithUnknown the term is dropped, but not rejected
+    strict::from_value::<Note>(&document).unwrap();
+
+    // Terms are scoped to the object that defines them
+    let mut outside = document.clone();
+    outside["tag"][0]["blurhash"] = json!("UBL_");
+    let error = strict::from_value::<Note>(&outside).unwrap_err();
+    assert_eq!(error.path, "/tag/0/blurhash");
+    assert_eq!(error.kind, StrictErrorKind::UnknownProperty);
+}
+
+#[test]
+fn undefined_terms_are_rejected() {
+    let error = note_error(json!({
+        "@context": "https://www.w3.org/ns/activitystreams",
+        "type": "Note",
+        "sensitive": true,
+    }));
+    assert_eq!(error.path, "/sensitive");
+    assert_eq!(error.kind, StrictErrorKind::UnknownProperty);
+
+    let error = note_error(json!({
+        "@context": ["https://www.w3.org/ns/activitystreams", { "toot": null }],
+        "type": "Note",
+        "tag": { "type": "Hashtag", "toot:featured": true },
+    }));
+    assert_eq!(error.path, "/tag/toot:featured");
+    assert_eq!(error.kind, StrictErrorKind::UnknownProperty);
+}
+
+#[test]
+fn documents_must_be_objects() {
+    let error = strict::from_value::<Note>(&json!(["Note"])).unwrap_err();
+    assert_eq!(error.path, "");
+    assert_eq!(error.kind, StrictErrorKind::NotAnObject);
+
+    let error = strict::from_str::<Note>("{").unwrap_err();
+    match error.kind {
+        StrictErrorKind::Deserialize(_) => (),
+        ref kind => panic!("expected a Deserialize error, got {:?}", kind),
+    }
+}
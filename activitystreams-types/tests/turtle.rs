@@ -0,0 +1,331 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    collection::OrderedCollection,
+    jsonld,
+    object::Note,
+    rdf::{
+        self, Literal, Quad, RdfError, Term, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN,
+        XSD_DECIMAL, XSD_DOUBLE, XSD_INTEGER,
+    },
+};
+
+const AS: &str = "https://www.w3.org/ns/activitystreams#";
+
+fn parse(document: &str) -> Vec<Quad> {
+    rdf::parse_turtle(document).unwrap()
+}
+
+fn objects(document: &str) -> Vec<Term> {
+    parse(document)
+        .into_iter()
+        .map(|quad| quad.object)
+        .collect()
+}
+
+fn literal(value: &str) -> Term {
+    Term::Literal(Literal::string(value))
+}
+
+fn typed(value: &str, datatype: &str) -> Term {
+    Term::Literal(Literal::typed(value, datatype))
+}
+
+fn error_line(document: &str) -> (usize, String) {
+    match rdf::parse_turtle(document).unwrap_err() {
+        RdfError::Syntax { line, message } => (line, message),
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn escapes_are_decoded() {
+    assert_eq!(
+        objects(r#"<a:s> <a:p> "tab\there\nquote\" slash\\ \u00e9 \U0001F600" ."#),
+        vec![literal("tab\there\nquote\" slash\\ \u{e9} \u{1F600}")]
+    );
+    assert_eq!(
+        objects(r#"<a:s> <a:p> 'single \'quoted\'' ."#),
+        vec![literal("single 'quoted'")]
+    );
+    assert_eq!(
+        objects(r#"<a:s> <a:p> <https://example.com/\u0041> ."#),
+        vec![Term::iri("https://example.com/A")]
+    );
+}
+
+#[test]
+fn long_strings_keep_newlines_and_quotes() {
+    let quads = parse(
+        "<a:s> <a:p> \"\"\"first\nsecond \"quoted\" \"\" line\"\"\" ;\n    \
+         <a:q> '''one\ntwo''' .\n",
+    );
+
+    assert_eq!(
+        quads,
+        vec![
+            Quad::new(
+                Term::iri("a:s"),
+                Term::iri("a:p"),
+                literal("first\nsecond \"quoted\" \"\" line"),
+            ),
+            Quad::new(Term::iri("a:s"), Term::iri("a:q"), literal("one\ntwo")),
+        ]
+    );
+}
+
+#[test]
+fn literals_carry_languages_and_datatypes() {
+    assert_eq!(
+        objects(
+            "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .\n\
+             <a:s> <a:p> \"Bonjour\"@fr-CA, \"2018-06-01T12:00:00Z\"^^xsd:dateTime, \
+             \"5\"^^<http://www.w3.org/2001/XMLSchema#integer> .\n",
+        ),
+        vec![
+            Term::Literal(Literal::language("Bonjour", "fr-CA")),
+            typed(
+                "2018-06-01T12:00:00Z",
+                "http://www.w3.org/2001/XMLSchema#dateTime",
+            ),
+            typed("5", XSD_INTEGER),
+        ]
+    );
+}
+
+#[test]
+fn numeric_and_boolean_literals() {
+    assert_eq!(
+        objects("<a:s> <a:p> 42, -7, +3, 1.5, -.5, 1e10, 2.5E-3, true, false .\n"),
+        vec![
+            typed("42", XSD_INTEGER),
+            typed("-7", XSD_INTEGER),
+            typed("+3", XSD_INTEGER),
+            typed("1.5", XSD_DECIMAL),
+            typed("-.5", XSD_DECIMAL),
+            typed("1e10", XSD_DOUBLE),
+            typed("2.5E-3", XSD_DOUBLE),
+            typed("true", XSD_BOOLEAN),
+            typed("false", XSD_BOOLEAN),
+        ]
+    );
+
+    // A dot directly after a number ends the statement
+    assert_eq!(objects("<a:s> <a:p> 42."), vec![typed("42", XSD_INTEGER)]);
+}
+
+#[test]
+fn collections_become_lists() {
+    let quads = parse("<a:s> <a:p> (1 <a:o>) ; <a:q> () .\n");
+
+    // The list is built from its last cell, so that cell takes the first label
+    assert_eq!(
+        quads,
+        vec![
+            Quad::new(
+                Term::blank_node("b0"),
+                Term::iri(RDF_FIRST),
+                Term::iri("a:o")
+            ),
+            Quad::new(
+                Term::blank_node("b0"),
+                Term::iri(RDF_REST),
+                Term::iri(RDF_NIL)
+            ),
+            Quad::new(
+                Term::blank_node("b1"),
+                Term::iri(RDF_FIRST),
+                typed("1", XSD_INTEGER),
+            ),
+            Quad::new(
+                Term::blank_node("b1"),
+                Term::iri(RDF_REST),
+                Term::blank_node("b0"),
+            ),
+            Quad::new(Term::iri("a:s"), Term::iri("a:p"), Term::blank_node("b1")),
+            Quad::new(Term::iri("a:s"), Term::iri("a:q"), Term::iri(RDF_NIL)),
+        ]
+    );
+}
+
+#[test]
+fn blank_node_property_lists() {
+    let quads = parse(
+        "@prefix as: <https://www.w3.org/ns/activitystreams#> .\n\
+         <a:s> as:attributedTo [ a as:Person ; as:name \"Ana\" ] .\n\
+         [ as:name \"Subject\" ] as:content _:x .\n\
+         _:x as:name \"Labelled\" .\n",
+    );
+
+    let mut subjects = quads
+        .iter()
+        .filter_map(|quad| quad.subject.as_blank_node())
+        .collect::<Vec<_>>();
+    subjects.sort();
+    subjects.dedup();
+    assert_eq!(subjects.len(), 3);
+
+    let person = quads
+        .iter()
+        .find(|quad| quad.subject == Term::iri("a:s"))
+        .map(|quad| quad.object.clone())
+        .unwrap();
+    assert!(person.as_blank_node().is_some());
+    assert!(quads.contains(&Quad::new(
+        person.clone(),
+        Term::iri(RDF_TYPE),
+        Term::iri(format!("{}Person", AS)),
+    )));
+    assert!(quads.contains(&Quad::new(
+        person,
+        Term::iri(format!("{}name", AS)),
+        literal("Ana"),
+    )));
+
+    // `_:x` is the same node in both statements
+    let labelled = quads
+        .iter()
+        .find(|quad| quad.object == literal("Labelled"))
+        .map(|quad| quad.subject.clone())
+        .unwrap();
+    assert!(quads.iter().any(
+        |quad| quad.predicate == Term::iri(format!("{}content", AS)) && quad.object == labelled
+    ));
+}
+
+#[test]
+fn base_and_prefix_directives() {
+    let quads = parse(
+        "@base <https://example.com/users/> .\n\
+         PREFIX as: <https://www.w3.org/ns/activitystreams#>\n\
+         prefix : <https://example.com/terms#>\n\
+         <ana> a as:Person ; :rank 1 .\n\
+         BASE <https://other.example/>\n\
+         <bo> as:follows <../users/ana#me> .\n",
+    );
+
+    assert_eq!(
+        quads,
+        vec![
+            Quad::new(
+                Term::iri("https://example.com/users/ana"),
+                Term::iri(RDF_TYPE),
+                Term::iri(format!("{}Person", AS)),
+            ),
+            Quad::new(
+                Term::iri("https://example.com/users/ana"),
+                Term::iri("https://example.com/terms#rank"),
+                typed("1", XSD_INTEGER),
+            ),
+            Quad::new(
+                Term::iri("https://other.example/bo"),
+                Term::iri(format!("{}follows", AS)),
+                Term::iri("https://other.example/users/ana#me"),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn errors_report_their_line() {
+    let (line, message) = error_line("<a:s> <a:p> <a:o> .\n\n<a:s> as:name \"x\" .\n");
+    assert_eq!(line, 3);
+    assert!(message.contains("undefined prefix"), "{}", message);
+
+    let (line, message) = error_line("<a:s> <a:p> \"\"\"one\ntwo\"\"\" .\n<a:s> <a:p> \"open\n");
+    assert_eq!(line, 3);
+    assert!(message.contains("unterminated literal"), "{}", message);
+
+    let (line, _) = error_line("# comment\n\"literal\" <a:p> <a:o> .\n");
+    assert_eq!(line, 2);
+
+    let (line, _) = error_line("<a:s> <a:p> <a:o>\n");
+    assert_eq!(line, 2);
+
+    assert_eq!(
+        rdf::parse_turtle("\n<a:s> <a:p> \"\\q\" .")
+            .unwrap_err()
+            .to_string(),
+        "Invalid RDF on line 2, invalid escape in literal"
+    );
+}
+
+#[test]
+fn note_round_trips() {
+    let note: Note = serde_json::from_value(json!({
+        "type": "Note",
+        "id": "https://example.com/notes/1",
+        "content": "Line one\n\"Two\"",
+        "published": "2018-06-01T12:00:00Z",
+        "attributedTo": "https://example.com/users/ana",
+        "to": ["https://www.w3.org/ns/activitystreams#Public", "https://example.com/users/bo"],
+    }))
+    .unwrap();
+
+    let turtle = jsonld::to_turtle(&note).unwrap();
+    let parsed: Note = jsonld::from_turtle(&turtle).unwrap();
+
+    assert_eq!(
+        parsed.object_props.id_string().unwrap(),
+        "https://example.com/notes/1"
+    );
+    assert_eq!(
+        parsed.object_props.content_string().unwrap(),
+        "Line one\n\"Two\""
+    );
+    assert_eq!(
+        parsed.object_props.published_string().unwrap(),
+        "2018-06-01T12:00:00Z"
+    );
+    assert_eq!(jsonld::to_turtle(&parsed).unwrap(), turtle);
+}
+
+#[test]
+fn ordered_collection_round_trips() {
+    let collection: OrderedCollection = serde_json::from_value(json!({
+        "type": "OrderedCollection",
+        "id": "https://example.com/outbox",
+        "totalItems": 3,
+        "orderedItems": [
+            "https://example.com/notes/3",
+            "https://example.com/notes/1",
+            "https://example.com/notes/2",
+        ],
+    }))
+    .unwrap();
+
+    let turtle = jsonld::to_turtle(&collection).unwrap();
+    let parsed: OrderedCollection = jsonld::from_turtle(&turtle).unwrap();
+
+    assert_eq!(*parsed.collection_props.total_items_u64().unwrap(), 3);
+    assert_eq!(
+        serde_json::to_value(&parsed.ordered_collection_props.ordered_items).unwrap(),
+        json!([
+            "https://example.com/notes/3",
+            "https://example.com/notes/1",
+            "https://example.com/notes/2",
+        ])
+    );
+    assert_eq!(jsonld::to_turtle(&parsed).unwrap(), turtle);
+}
@@ -0,0 +1,57 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+
+use activitystreams_types::rdf::{parse_nquads, urdna2015::canonicalize_to_nquads};
+use std::{fs, path::Path};
+
+#[test]
+fn w3c_test_vectors() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/urdna2015");
+    let mut inputs: Vec<_> = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with("-in.nq"))
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+
+    let mut failures = Vec::new();
+    for input in inputs {
+        let name = input
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .replace("-in.nq", "");
+        let expected =
+            fs::read_to_string(directory.join(format!("{}-urdna2015.nq", name))).unwrap();
+
+        let quads = parse_nquads(&fs::read_to_string(&input).unwrap()).unwrap();
+        match canonicalize_to_nquads(&quads) {
+            Ok(ref canonical) if *canonical == expected => (),
+            Ok(canonical) => {
+                failures.push(format!("{}:\n{}\nexpected:\n{}", name, canonical, expected))
+            }
+            Err(e) => failures.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
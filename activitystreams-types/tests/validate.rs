@@ -0,0 +1,121 @@
+/*
+ * This file is part of ActivityStreams Types.
+ *
+ * Copyright © 2018 Riley Trautman
+ *
+ * ActivityStreams Types is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * ActivityStreams Types is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with ActivityStreams Types.  If not, see <http://www.gnu.org/licenses/>.
+ */
+
+extern crate activitystreams_types;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+use activitystreams_types::{
+    activity::Create,
+    validate::{Rule, Severity, Validate},
+    WithUnknown,
+};
+use serde::ser::{Error, Serialize, Serializer};
+
+/// A value that can never be serialized
+struct Unserializable;
+
+impl Serialize for Unserializable {
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Err(S::Error::custom("no JSON form"))
+    }
+}
+
+#[test]
+fn serialization_errors_are_violations() {
+    let value = WithUnknown::new(Unserializable);
+
+    let violations = value.validate();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, Rule::Serialize);
+    assert_eq!(violations[0].severity, Severity::Error);
+    assert_eq!(violations[0].path, "");
+    assert!(violations[0].message.contains("no JSON form"));
+    assert!(!value.is_valid());
+}
+
+#[test]
+fn create_attribution_is_checked_for_one_object() {
+    let create: Create = serde_json::from_value(json!({
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "object": {
+            "type": "Note",
+            "attributedTo": "https://example.com/users/bob",
+        },
+    }))
+    .unwrap();
+
+    let violations = create.validate();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, Rule::AttributionMismatch);
+    assert_eq!(violations[0].path, "/object/attributedTo");
+}
+
+#[test]
+fn create_attribution_is_checked_for_every_object() {
+    let create: Create = serde_json::from_value(json!({
+        "type": "Create",
+        "actor": "https://example.com/users/alice",
+        "object": [
+            {
+                "type": "Note",
+                "attributedTo": "https://example.com/users/alice",
+            },
+            {
+                "type": "Note",
+                "attributedTo": { "type": "Person", "id": "https://example.com/users/bob" },
+            },
+            "https://example.com/notes/3",
+            { "type": "Note" },
+        ],
+    }))
+    .unwrap();
+
+    let violations = create.validate();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, Rule::AttributionMismatch);
+    assert_eq!(violations[0].severity, Severity::Warning);
+    assert_eq!(violations[0].path, "/object/1/attributedTo");
+    assert!(create.is_valid());
+}
+
+#[test]
+fn matching_attribution_is_valid() {
+    let create = json!({
+        "type": "Create",
+        "actor": ["https://example.com/users/alice"],
+        "object": [
+            {
+                "type": "Note",
+                "attributedTo": ["https://example.com/users/alice", "https://example.com/users/bob"],
+            },
+            {
+                "type": "Note",
+                "attributedTo": { "type": "Person", "id": "https://example.com/users/alice" },
+            },
+        ],
+    });
+
+    assert!(create.validate().is_empty());
+}
@@ -21,7 +21,7 @@
 
 pub use activitystreams_traits::{Activity, IntransitiveActivity};
 pub use activitystreams_types::activity::{
-    kind, properties, AMove, Accept, ActivityExt, Add, Announce, Arrive, Block, Create, Delete,
-    Dislike, Flag, Follow, Ignore, Invite, Join, Leave, Like, Listen, Offer, Question, Read,
-    Reject, Remove, TentativeAccept, TentativeReject, Travel, Undo, Update, View,
+    kind, properties, AMove, Accept, ActivityExt, Add, Announce, AnyActivity, Arrive, Block,
+    Create, Delete, Dislike, Flag, Follow, Ignore, Invite, Join, Leave, Like, Listen, Offer,
+    Question, Read, Reject, Remove, TentativeAccept, TentativeReject, Travel, Undo, Update, View,
 };
@@ -20,4 +20,6 @@
 //! Actor traits and types
 
 pub use activitystreams_traits::Actor;
-pub use activitystreams_types::actor::{kind, Application, Group, Organization, Person, Service};
+pub use activitystreams_types::actor::{
+    kind, properties, ActorExt, AnyActor, Application, Group, Organization, Person, Service,
+};
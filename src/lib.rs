@@ -149,10 +149,13 @@ pub mod link;
 pub mod object;
 
 pub use self::activity::{Activity, ActivityExt, IntransitiveActivity};
-pub use self::actor::Actor;
+pub use self::actor::{Actor, ActorExt};
 pub use self::collection::{Collection, CollectionExt, CollectionPage, CollectionPageExt};
 pub use self::error::{Error, Result};
 pub use self::link::{Link, LinkExt};
 pub use self::object::{Object, ObjectExt};
 pub use activitystreams_traits::properties;
-pub use activitystreams_types::context;
+pub use activitystreams_types::{
+    addressing, context, inbox, jsonld, origin, primitives, rdf, resolve, security,
+    security_context, store, strict, validate,
+};